@@ -1,12 +1,20 @@
-use orchestrator::worker::{listen, Error};
+use orchestrator::worker::{listen, Error, DEFAULT_OUTPUT_BYTE_LIMIT};
 use std::env;
 
 #[tokio::main(flavor = "current_thread")]
 #[snafu::report]
 pub async fn main() -> Result<(), Error> {
-    let project_dir = env::args_os()
-        .nth(1)
+    let mut args = env::args_os().skip(1);
+
+    let project_dir = args
+        .next()
         .expect("Please specify project directory as the first argument");
 
-    listen(project_dir).await
+    let output_byte_limit = args.next().map_or(DEFAULT_OUTPUT_BYTE_LIMIT, |l| {
+        l.to_str()
+            .and_then(|l| l.parse().ok())
+            .expect("The output byte limit must be a number")
+    });
+
+    listen(project_dir, output_byte_limit).await
 }
@@ -1,13 +1,16 @@
 use futures::{
     future::BoxFuture,
-    stream::{BoxStream, FuturesUnordered},
+    stream::{BoxStream, FuturesOrdered, FuturesUnordered},
     Future, FutureExt, Stream, StreamExt, TryStreamExt,
 };
-use serde::Deserialize;
+use serde::{de::IgnoredAny, Deserialize};
 use snafu::prelude::*;
 use std::{
-    collections::{BTreeSet, HashMap},
-    fmt, mem, ops,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt, mem,
+    num::NonZeroU32,
+    ops,
+    path::{Path, PathBuf},
     pin::{self, pin},
     process::Stdio,
     sync::{
@@ -15,9 +18,11 @@ use std::{
         Arc, LazyLock, Mutex,
     },
     task,
-    time::Duration,
+    time::{Duration, SystemTime},
 };
+use tempfile::TempDir;
 use tokio::{
+    join,
     process::{Child, ChildStdin, ChildStdout, Command},
     select,
     sync::{mpsc, oneshot, OnceCell},
@@ -32,9 +37,12 @@ use tracing::{error, info, info_span, instrument, trace, trace_span, warn, Instr
 use crate::{
     bincode_input_closed,
     message::{
-        CommandStatistics, CoordinatorMessage, DeleteFileRequest, ExecuteCommandRequest,
-        ExecuteCommandResponse, JobId, Multiplexed, OneToOneResponse, ReadFileRequest,
-        ReadFileResponse, SerializedError2, WorkerMessage, WriteFileRequest,
+        Capabilities, CommandStatistics, CoordinatorMessage, DeleteFileRequest,
+        ExecuteCommandRequest, ExecuteCommandResponse, FileKind, Hello, JobId,
+        ListDirectoryRequest, Multiplexed, OneToOneResponse, OutputLimit, ReadFileChunkRequest,
+        ReadFileRequest, ReadFileResponse, SerializedError2, StatFileRequest, WorkerMessage,
+        WriteFileChunkRequest, WriteFileRequest, WriteFileResponse, MAX_CHUNK_LEN,
+        MIN_PROTOCOL_VERSION,
     },
     DropErrorDetailsExt, TaskAbortExt as _,
 };
@@ -237,15 +245,105 @@ pub enum ProcessAssembly {
     Raw,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MirOptLevel {
+    L0,
+    L1,
+    L2,
+    L3,
+    L4,
+}
+
+impl MirOptLevel {
+    fn to_flag(self) -> &'static str {
+        match self {
+            MirOptLevel::L0 => "-Zmir-opt-level=0",
+            MirOptLevel::L1 => "-Zmir-opt-level=1",
+            MirOptLevel::L2 => "-Zmir-opt-level=2",
+            MirOptLevel::L3 => "-Zmir-opt-level=3",
+            MirOptLevel::L4 => "-Zmir-opt-level=4",
+        }
+    }
+}
+
+/// The MIR optimization passes that may be dumped.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MirPass {
+    CopyProp,
+    DataflowConstProp,
+    DestinationPropagation,
+    Gvn,
+    Inline,
+    JumpThreading,
+    ReferencePropagation,
+}
+
+impl MirPass {
+    fn to_flag(self) -> &'static str {
+        match self {
+            MirPass::CopyProp => "-Zdump-mir=CopyProp",
+            MirPass::DataflowConstProp => "-Zdump-mir=DataflowConstProp",
+            MirPass::DestinationPropagation => "-Zdump-mir=DestinationPropagation",
+            MirPass::Gvn => "-Zdump-mir=GVN",
+            MirPass::Inline => "-Zdump-mir=Inline",
+            MirPass::JumpThreading => "-Zdump-mir=JumpThreading",
+            MirPass::ReferencePropagation => "-Zdump-mir=ReferencePropagation",
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum CompileTarget {
     Assembly(AssemblyFlavor, DemangleAssembly, ProcessAssembly),
     Hir,
     LlvmIr,
+    /// LLVM's machine IR, after register allocation and the other
+    /// code generation passes.
+    LlvmMachineIr,
     Mir,
+    /// The MIR at the given optimization level. When a pass is
+    /// chosen, the MIR of each function right after that pass.
+    OptimizedMir(MirOptLevel, Option<MirPass>),
+    /// The disassembled object file, along with the size of each
+    /// symbol.
+    Disassembly,
     Wasm,
 }
 
+impl CompileTarget {
+    /// Whether producing this output relies on unstable compiler flags.
+    fn requires_nightly(self) -> bool {
+        matches!(
+            self,
+            CompileTarget::OptimizedMir(..) | CompileTarget::LlvmMachineIr
+        )
+    }
+}
+
+/// The non-host targets that code may be compiled for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TargetTriple {
+    Aarch64UnknownLinuxGnu,
+    I686UnknownLinuxGnu,
+    Riscv64gcUnknownLinuxGnu,
+    Thumbv7emNoneEabihf,
+}
+
+impl TargetTriple {
+    pub(crate) fn to_str(self) -> &'static str {
+        match self {
+            TargetTriple::Aarch64UnknownLinuxGnu => "aarch64-unknown-linux-gnu",
+            TargetTriple::I686UnknownLinuxGnu => "i686-unknown-linux-gnu",
+            TargetTriple::Riscv64gcUnknownLinuxGnu => "riscv64gc-unknown-linux-gnu",
+            TargetTriple::Thumbv7emNoneEabihf => "thumbv7em-none-eabihf",
+        }
+    }
+
+    fn is_x86(self) -> bool {
+        self == TargetTriple::I686UnknownLinuxGnu
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Channel {
     Stable,
@@ -259,11 +357,42 @@ pub enum AliasingModel {
     Tree,
 }
 
+/// A dynamic checker that instruments the program. Requires the
+/// nightly channel.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Sanitizer {
+    Address,
+    Leak,
+    Memory,
+    Thread,
+}
+
+impl Sanitizer {
+    pub(crate) fn to_str(self) -> &'static str {
+        match self {
+            Sanitizer::Address => "address",
+            Sanitizer::Leak => "leak",
+            Sanitizer::Memory => "memory",
+            Sanitizer::Thread => "thread",
+        }
+    }
+
+    /// The name used in reports, such as `AddressSanitizer`.
+    fn from_report_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "AddressSanitizer" => Sanitizer::Address,
+            "LeakSanitizer" => Sanitizer::Leak,
+            "MemorySanitizer" => Sanitizer::Memory,
+            "ThreadSanitizer" => Sanitizer::Thread,
+            _ => return None,
+        })
+    }
+}
+
 impl Channel {
     #[cfg(test)]
     pub(crate) const ALL: [Self; 3] = [Self::Stable, Self::Beta, Self::Nightly];
 
-    #[cfg(test)]
     pub(crate) fn to_str(self) -> &'static str {
         match self {
             Channel::Stable => "stable",
@@ -279,6 +408,16 @@ pub enum Mode {
     Release,
 }
 
+impl Mode {
+    /// The directory beneath `target` that Cargo builds into.
+    pub(crate) fn profile_dir(self) -> &'static str {
+        match self {
+            Mode::Debug => "debug",
+            Mode::Release => "release",
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Edition {
     Rust2015,
@@ -308,6 +447,16 @@ impl Edition {
     pub(crate) fn to_cargo_toml_key(self) -> &'static str {
         self.to_str()
     }
+
+    /// The edition that `cargo fix --edition` migrates to.
+    pub fn next(self) -> Option<Self> {
+        match self {
+            Edition::Rust2015 => Some(Edition::Rust2018),
+            Edition::Rust2018 => Some(Edition::Rust2021),
+            Edition::Rust2021 => Some(Edition::Rust2024),
+            Edition::Rust2024 => None,
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -364,6 +513,276 @@ pub enum LibraryType {
     ProcMacro,
 }
 
+/// Additional compiler flags, Cargo features, and environment
+/// variables supplied by the user. Only options from an allow-list
+/// are accepted; see [`ExtraOptions::new`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtraOptions {
+    rustc_flags: Vec<String>,
+    features: Vec<String>,
+    envs: BTreeMap<String, String>,
+}
+
+impl ExtraOptions {
+    pub const NONE: Self = Self {
+        rustc_flags: Vec::new(),
+        features: Vec::new(),
+        envs: BTreeMap::new(),
+    };
+
+    const CODEGEN_OPTIONS: &[&str] = &[
+        "code-model",
+        "codegen-units",
+        "debug-assertions",
+        "debuginfo",
+        "force-frame-pointers",
+        "force-unwind-tables",
+        "lto",
+        "opt-level",
+        "overflow-checks",
+        "panic",
+        "relocation-model",
+        "strip",
+        "symbol-mangling-version",
+        "target-cpu",
+        "target-feature",
+    ];
+
+    const UNSTABLE_OPTIONS: &[&str] = &[
+        "box-noalias",
+        "inline-mir",
+        "inline-mir-threshold",
+        "mir-opt-level",
+        "mutable-noalias",
+        "polonius",
+        "print-type-sizes",
+        "randomize-layout",
+        "share-generics",
+        "trap-unreachable",
+        "verbose-asm",
+    ];
+
+    const ENVS: &[&str] = &[
+        "RUST_BACKTRACE",
+        "RUST_LIB_BACKTRACE",
+        "RUST_LOG",
+        "RUST_MIN_STACK",
+        "RUST_TEST_THREADS",
+    ];
+
+    /// The wrapper passes the flags to `rustc` for the playground
+    /// crate only so that the precompiled dependencies are reused.
+    const RUSTC_WRAPPER: &str = "playground-rustc-wrapper";
+    const RUSTC_FLAGS_ENV: &str = "PLAYGROUND_RUSTC_FLAGS";
+    const RUSTC_FLAGS_SEPARATOR: char = '\x1f';
+
+    /// Validates the options against the allow-list.
+    ///
+    /// `rustc_flags` may contain codegen options (`-C name=value` or
+    /// `-Cname=value`), `--cfg` options, and, on the nightly channel,
+    /// unstable options (`-Z name=value`).
+    pub fn new(
+        channel: Channel,
+        rustc_flags: impl IntoIterator<Item = String>,
+        features: impl IntoIterator<Item = String>,
+        envs: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<Self, ExtraOptionsError> {
+        use extra_options_error::*;
+
+        let mut validated_flags = Vec::new();
+        let mut rustc_flags = rustc_flags.into_iter();
+
+        while let Some(flag) = rustc_flags.next() {
+            let (kind, value) = match flag.strip_prefix("--cfg") {
+                Some("") => ("--cfg", None),
+                Some(v) => match v.strip_prefix('=') {
+                    Some(v) => ("--cfg", Some(v)),
+                    None => return RustcFlagNotAllowedSnafu { flag }.fail(),
+                },
+                None => match flag.get(..2) {
+                    Some(kind @ ("-C" | "-Z")) if flag.len() > 2 => (kind, Some(&flag[2..])),
+                    Some(kind @ ("-C" | "-Z")) => (kind, None),
+                    _ => return RustcFlagNotAllowedSnafu { flag }.fail(),
+                },
+            };
+
+            let value = match value {
+                Some(v) => v.to_owned(),
+                None => rustc_flags
+                    .next()
+                    .context(RustcFlagMissingValueSnafu { flag: &flag })?,
+            };
+
+            ensure!(
+                !value.is_empty() && !value.contains(['\n', Self::RUSTC_FLAGS_SEPARATOR]),
+                RustcFlagNotAllowedSnafu { flag: value },
+            );
+
+            let name = value.split('=').next().unwrap_or_default();
+            match kind {
+                "-C" => ensure!(
+                    Self::CODEGEN_OPTIONS.contains(&name),
+                    RustcFlagNotAllowedSnafu {
+                        flag: format!("-C {value}"),
+                    },
+                ),
+                "-Z" => {
+                    ensure!(
+                        Self::UNSTABLE_OPTIONS.contains(&name),
+                        RustcFlagNotAllowedSnafu {
+                            flag: format!("-Z {value}"),
+                        },
+                    );
+                    ensure!(
+                        channel == Channel::Nightly,
+                        RustcFlagRequiresNightlySnafu {
+                            flag: format!("-Z {value}"),
+                        },
+                    );
+                }
+                _ => {}
+            }
+
+            validated_flags.push(kind.to_owned());
+            validated_flags.push(value);
+        }
+
+        let features = features
+            .into_iter()
+            .map(|feature| {
+                let valid = !feature.is_empty()
+                    && !feature.starts_with('-')
+                    && feature
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || "-_/+.".contains(c));
+                ensure!(valid, InvalidFeatureSnafu { feature });
+                Ok(feature)
+            })
+            .collect::<Result<_, _>>()?;
+
+        let envs = envs
+            .into_iter()
+            .map(|(name, value)| {
+                ensure!(Self::ENVS.contains(&&*name), EnvNotAllowedSnafu { name });
+                Ok((name, value))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            rustc_flags: validated_flags,
+            features,
+            envs,
+        })
+    }
+
+    pub fn rustc_flags(&self) -> &[String] {
+        &self.rustc_flags
+    }
+
+    pub fn features(&self) -> &[String] {
+        &self.features
+    }
+
+    pub fn envs(&self) -> &BTreeMap<String, String> {
+        &self.envs
+    }
+
+    fn add_envs(&self, envs: &mut HashMap<String, String>) {
+        if !self.rustc_flags.is_empty() {
+            let separator = Self::RUSTC_FLAGS_SEPARATOR.to_string();
+            envs.extend(kvs! {
+                "RUSTC_WORKSPACE_WRAPPER" => Self::RUSTC_WRAPPER,
+                Self::RUSTC_FLAGS_ENV => self.rustc_flags.join(&separator),
+            });
+        }
+
+        envs.extend(self.envs.clone());
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+pub enum ExtraOptionsError {
+    #[snafu(display("The compiler flag `{flag}` is not allowed"))]
+    RustcFlagNotAllowed { flag: String },
+
+    #[snafu(display("The compiler flag `{flag}` is missing a value"))]
+    RustcFlagMissingValue { flag: String },
+
+    #[snafu(display("The compiler flag `{flag}` is only allowed on the nightly channel"))]
+    RustcFlagRequiresNightly { flag: String },
+
+    #[snafu(display("The feature name `{feature}` is not valid"))]
+    InvalidFeature { feature: String },
+
+    #[snafu(display("The environment variable `{name}` is not allowed"))]
+    EnvNotAllowed { name: String },
+}
+
+/// Makes Cargo report diagnostics as JSON messages, which are then
+/// returned as [`Diagnostic`][]s.
+const JSON_MESSAGE_FORMAT: &str = "--message-format=json-diagnostic-rendered-ansi";
+
+/// A compiler diagnostic, as reported by `rustc --error-format=json`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Diagnostic {
+    pub message: String,
+    pub code: Option<DiagnosticCode>,
+    pub level: DiagnosticLevel,
+    pub spans: Vec<DiagnosticSpan>,
+    pub children: Vec<Diagnostic>,
+    /// The diagnostic as it would have been printed, including ANSI
+    /// color codes. Only set for top-level diagnostics.
+    pub rendered: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DiagnosticCode {
+    pub code: String,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiagnosticLevel {
+    #[serde(rename = "error: internal compiler error")]
+    InternalCompilerError,
+    Error,
+    Warning,
+    Note,
+    Help,
+    FailureNote,
+    /// A level added to rustc after this list was written.
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DiagnosticSpan {
+    pub file_name: String,
+    pub byte_start: u32,
+    pub byte_end: u32,
+    /// 1-based
+    pub line_start: u32,
+    /// 1-based
+    pub line_end: u32,
+    /// 1-based, in characters
+    pub column_start: u32,
+    /// 1-based, in characters
+    pub column_end: u32,
+    pub is_primary: bool,
+    pub label: Option<String>,
+    pub suggested_replacement: Option<String>,
+    pub suggestion_applicability: Option<Applicability>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+pub enum Applicability {
+    MachineApplicable,
+    MaybeIncorrect,
+    HasPlaceholders,
+    Unspecified,
+}
+
 #[derive(Debug, Clone)]
 pub struct ExecuteRequest {
     pub channel: Channel,
@@ -372,9 +791,67 @@ pub struct ExecuteRequest {
     pub crate_type: CrateType,
     pub tests: bool,
     pub backtrace: bool,
+    /// Only used when `tests` is set.
+    pub test_options: TestOptions,
+    pub extra: ExtraOptions,
+    pub json_diagnostics: bool,
+    pub sanitizer: Option<Sanitizer>,
+    /// Report how many times each line of the playground crate ran.
+    pub coverage: bool,
     pub code: Code,
 }
 
+impl ExecuteRequest {
+    const COVERAGE_PATH: &str = "coverage.lcov";
+
+    fn read_coverage_request(&self) -> ReadFileRequest {
+        ReadFileRequest {
+            path: Self::COVERAGE_PATH.to_owned(),
+        }
+    }
+}
+
+/// Options passed to the test harness.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TestOptions {
+    /// Only run the tests whose names contain this string.
+    pub filter: Option<String>,
+    /// Only run the ignored tests.
+    pub ignored: bool,
+    /// Print the output of the tests as they run instead of capturing
+    /// it.
+    pub nocapture: bool,
+    pub test_threads: Option<NonZeroU32>,
+}
+
+impl TestOptions {
+    pub const NONE: Self = Self {
+        filter: None,
+        ignored: false,
+        nocapture: false,
+        test_threads: None,
+    };
+
+    pub fn validate(&self) -> Result<(), TestFilterError> {
+        validate_test_filter(self.filter.as_deref())
+    }
+}
+
+/// libtest reads any argument starting with `-` as an option, so such
+/// a filter would change how the tests are run instead.
+fn validate_test_filter(filter: Option<&str>) -> Result<(), TestFilterError> {
+    match filter {
+        Some(filter) if filter.starts_with('-') => TestFilterSnafu { filter }.fail(),
+        _ => Ok(()),
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("The test filter `{filter}` may not start with `-`"))]
+pub struct TestFilterError {
+    filter: String,
+}
+
 #[derive(Debug, Clone)]
 pub enum Code {
     Single(String),
@@ -421,7 +898,7 @@ impl Code {
         Self::Single(String::new())
     }
 
-    fn files(&self, crate_type: CrateType) -> Box<dyn Iterator<Item = (&str, &str)> + '_> {
+    fn files(&self, crate_type: CrateType) -> Box<dyn Iterator<Item = (&str, &str)> + Send + '_> {
         match self {
             Code::Single(code) => Box::new([(crate_type.primary_path(), &**code)].into_iter()),
             Code::Multiple(files) => Box::new(files.iter().map(|cf| (&*cf.name, &*cf.content))),
@@ -454,6 +931,44 @@ impl Code {
             })
     }
 
+    fn read_requests(
+        &self,
+        crate_type: CrateType,
+    ) -> impl Iterator<Item = ReadFileRequest> + use<'_> {
+        self.files(crate_type)
+            .map(|(path, _)| ReadFileRequest { path: path.into() })
+    }
+
+    /// Reads the files back after a tool has changed them, keeping
+    /// the shape of the submitted code.
+    async fn read_back(
+        &self,
+        commander: &Commander,
+        requests: impl Iterator<Item = ReadFileRequest> + Send,
+    ) -> Result<Self, ReadCodeError> {
+        use read_code_error::*;
+
+        let mut files = requests
+            .map(|read| async {
+                let name = read.path.clone();
+                let file = commander
+                    .read_file(read, Commander::MAX_FILE_LEN)
+                    .await
+                    .context(CouldNotReadSnafu { name: &name })?;
+                let content = String::from_utf8(file.0).context(NotUtf8Snafu { name: &name })?;
+
+                Ok(CodeFile { name, content })
+            })
+            .collect::<FuturesUnordered<_>>()
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        Ok(match self {
+            Code::Single(_) => Code::Single(files.pop().map(|cf| cf.content).unwrap_or_default()),
+            Code::Multiple(_) => Code::Multiple(files),
+        })
+    }
+
     #[cfg(test)]
     fn assume_single(&self) -> &str {
         match self {
@@ -471,6 +986,22 @@ impl Code {
     }
 }
 
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+pub enum ReadCodeError {
+    #[snafu(display("Could not read `{name}`"))]
+    CouldNotRead {
+        name: String,
+        source: CommanderError,
+    },
+
+    #[snafu(display("`{name}` was not UTF-8"))]
+    NotUtf8 {
+        name: String,
+        source: std::string::FromUtf8Error,
+    },
+}
+
 impl LowerRequest for ExecuteRequest {
     fn delete_files(&self) -> impl Iterator<Item = DeleteFileRequest> {
         self.code.delete_requests(self.crate_type)
@@ -483,6 +1014,18 @@ impl LowerRequest for ExecuteRequest {
     fn execute_cargo_request(&self) -> ExecuteCommandRequest {
         let mut args = vec![];
 
+        // The standard library and crates are prebuilt with the
+        // sanitizer by `cargo-sanitize`
+        if let Some(sanitizer) = self.sanitizer {
+            args.extend(["sanitize", sanitizer.to_str()]);
+        }
+
+        // The playground crate is instrumented and the report is
+        // written by `cargo-coverage`
+        if self.coverage {
+            args.push("coverage");
+        }
+
         let cmd = match (self.tests, self.crate_type.is_binary()) {
             (true, _) => "test",
             (_, true) => "run",
@@ -490,20 +1033,70 @@ impl LowerRequest for ExecuteRequest {
         };
         args.push(cmd);
 
+        if self.coverage {
+            args.extend(["-o", Self::COVERAGE_PATH]);
+        }
+
         if let Mode::Release = self.mode {
             args.push("--release");
         }
 
+        let features = self.extra.features().join(",");
+        if !features.is_empty() {
+            args.extend(["--features", &features]);
+        }
+
+        if self.json_diagnostics {
+            args.push(JSON_MESSAGE_FORMAT);
+        }
+
+        let test_threads;
+        if self.tests {
+            let TestOptions {
+                filter,
+                ignored,
+                nocapture,
+                test_threads: threads,
+            } = &self.test_options;
+
+            args.push("--");
+
+            if let Some(filter) = filter {
+                args.push(filter);
+            }
+
+            if *ignored {
+                args.push("--ignored");
+            }
+
+            if *nocapture {
+                args.push("--nocapture");
+            }
+
+            if let Some(threads) = threads {
+                test_threads = format!("--test-threads={threads}");
+                args.push(&test_threads);
+            }
+
+            // Adds the duration to each test result
+            if let Channel::Nightly = self.channel {
+                args.extend(["-Zunstable-options", "--report-time"]);
+            }
+        }
+
         let mut envs = HashMap::new();
         if self.backtrace {
             envs.extend(kvs!("RUST_BACKTRACE" => "1"));
         }
+        self.extra.add_envs(&mut envs);
 
         ExecuteCommandRequest {
             cmd: "cargo".to_owned(),
             args: args.into_iter().map(|s| s.to_owned()).collect(),
             envs,
             cwd: None,
+            output_limit: None,
+            deadline: None,
         }
     }
 }
@@ -542,46 +1135,155 @@ impl From<CommandStatistics> for ExecuteStatus {
 pub struct ExecuteResponse {
     pub success: bool,
     pub exit_detail: String,
+    pub diagnostics: Vec<Diagnostic>,
+    /// Only populated when running tests.
+    pub tests: Vec<TestResult>,
+    /// Only populated when running with a sanitizer.
+    pub sanitizer_reports: Vec<SanitizerReport>,
+    /// Only populated when coverage was requested.
+    pub coverage: Vec<FileCoverage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileCoverage {
+    /// Relative to the crate root, such as `src/main.rs`.
+    pub path: String,
+    /// Only the lines with code, in order.
+    pub lines: Vec<LineCoverage>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LineCoverage {
+    /// One-based.
+    pub line: u32,
+    pub count: u64,
+}
+
+impl FileCoverage {
+    /// Parses the LCOV report written by `llvm-cov export`.
+    fn parse_lcov(report: &str) -> Vec<Self> {
+        let mut files = vec![];
+        let mut current = None;
+
+        for line in report.lines() {
+            if let Some(path) = line.strip_prefix("SF:") {
+                current = Some(FileCoverage {
+                    path: path.to_owned(),
+                    lines: vec![],
+                });
+            } else if let Some(data) = line.strip_prefix("DA:") {
+                let mut fields = data.split(',');
+                let line = fields.next().and_then(|l| l.parse().ok());
+                let count = fields.next().and_then(|c| c.parse().ok());
+
+                if let (Some(file), Some(line), Some(count)) = (&mut current, line, count) {
+                    file.lines.push(LineCoverage { line, count });
+                }
+            } else if line == "end_of_record" {
+                files.extend(current.take());
+            }
+        }
+
+        for file in &mut files {
+            file.lines.sort_by_key(|l| l.line);
+        }
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        files
+    }
+}
+
+/// An error reported by a sanitizer on stderr.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SanitizerReport {
+    /// The sanitizer that reported the error. Leaks found while
+    /// running the address sanitizer are reported by the leak
+    /// sanitizer.
+    pub sanitizer: Sanitizer,
+    /// Such as `heap-use-after-free` or `data race`.
+    pub kind: String,
+    /// The `SUMMARY` line, usually with the location of the error.
+    pub summary: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestResult {
+    pub name: String,
+    pub outcome: TestOutcome,
+    /// Only reported on the nightly channel.
+    pub duration: Option<Duration>,
+    /// The captured output of a failed test.
+    pub output: String,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TestOutcome {
+    Passed,
+    Failed,
+    Ignored,
+    /// Only reported by `cargo bench`.
+    Benchmarked(BenchStats),
+}
+
+/// The timing of a single benchmark, as measured by libtest.
+///
+/// libtest only prints the median and the spread of the samples; the
+/// mean is not available.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BenchStats {
+    /// The median time of one iteration.
+    pub median: Duration,
+    /// The difference between the fastest and slowest samples, after
+    /// outliers have been removed. libtest prints this as `+/-`.
+    pub spread: Duration,
+    /// Only reported when the benchmark sets `Bencher::bytes`.
+    pub megabytes_per_second: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
-pub struct CompileRequest {
-    pub target: CompileTarget,
+pub struct BenchRequest {
     pub channel: Channel,
     pub crate_type: CrateType,
-    pub mode: Mode,
     pub edition: Edition,
-    // TODO: Remove `tests` and `backtrace` -- don't make sense for compiling.
-    pub tests: bool,
-    pub backtrace: bool,
+    /// Only run the benchmarks whose names contain this string.
+    pub filter: Option<String>,
+    /// Includes the time spent compiling. May not exceed
+    /// [`BenchRequest::MAX_TIME_LIMIT`][].
+    pub time_limit: Duration,
     pub code: Code,
 }
 
-impl CompileRequest {
-    const OUTPUT_PATH: &str = "compilation";
+impl BenchRequest {
+    pub const MAX_TIME_LIMIT: Duration = Duration::from_secs(60);
 
-    fn read_output_request(&self) -> ReadFileRequest {
-        ReadFileRequest {
-            path: Self::OUTPUT_PATH.to_owned(),
-        }
-    }
+    pub fn validate(&self) -> Result<(), BenchRequestError> {
+        use bench_request_error::*;
 
-    pub(crate) fn postprocess_result(&self, mut code: String) -> String {
-        if let CompileTarget::Assembly(_, demangle, process) = self.target {
-            if demangle == DemangleAssembly::Demangle {
-                code = asm_cleanup::demangle_asm(&code);
-            }
+        validate_test_filter(self.filter.as_deref()).context(FilterSnafu)?;
 
-            if process == ProcessAssembly::Filter {
-                code = asm_cleanup::filter_asm(&code);
-            }
-        }
+        let time_limit = self.time_limit;
+        ensure!(
+            !time_limit.is_zero() && time_limit <= Self::MAX_TIME_LIMIT,
+            TimeLimitSnafu { time_limit }
+        );
 
-        code
+        Ok(())
     }
 }
 
-impl LowerRequest for CompileRequest {
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+pub enum BenchRequestError {
+    #[snafu(display("The benchmark filter is not valid"))]
+    Filter { source: TestFilterError },
+
+    #[snafu(display(
+        "The time limit {time_limit:?} must be more than zero and at most {:?}",
+        BenchRequest::MAX_TIME_LIMIT,
+    ))]
+    TimeLimit { time_limit: Duration },
+}
+
+impl LowerRequest for BenchRequest {
     fn delete_files(&self) -> impl Iterator<Item = DeleteFileRequest> {
         self.code.delete_requests(self.crate_type)
     }
@@ -591,25 +1293,237 @@ impl LowerRequest for CompileRequest {
     }
 
     fn execute_cargo_request(&self) -> ExecuteCommandRequest {
-        use CompileTarget::*;
+        let mut args = vec!["bench".to_owned()];
 
-        let mut args = if let Wasm = self.target {
-            vec!["wasm"]
-        } else {
-            vec!["rustc"]
-        };
-        if let Mode::Release = self.mode {
-            args.push("--release");
+        if let Some(filter) = &self.filter {
+            args.extend(["--".to_owned(), filter.clone()]);
         }
 
-        match self.crate_type {
-            CrateType::Binary => args.extend(["--bin", "playground"]),
-            CrateType::Library(_) => args.push("--lib"),
+        ExecuteCommandRequest {
+            cmd: "cargo".to_owned(),
+            args,
+            envs: Default::default(),
+            cwd: None,
+            output_limit: None,
+            deadline: Some(self.time_limit.min(Self::MAX_TIME_LIMIT)),
         }
+    }
+}
 
-        match self.target {
+impl CargoTomlModifier for BenchRequest {
+    fn modify_cargo_toml(&self, mut cargo_toml: toml::Value) -> toml::Value {
+        cargo_toml = modify_cargo_toml::set_edition(cargo_toml, self.edition.to_cargo_toml_key());
+
+        if let Some(crate_type) = self.crate_type.to_library_cargo_toml_key() {
+            cargo_toml = modify_cargo_toml::set_crate_type(cargo_toml, crate_type);
+        }
+        cargo_toml
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BenchResponse {
+    pub success: bool,
+    pub exit_detail: String,
+    pub benchmarks: Vec<BenchResult>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchResult {
+    pub name: String,
+    pub stats: BenchStats,
+}
+
+impl BenchResult {
+    fn from_test_result(result: TestResult) -> Option<Self> {
+        match result.outcome {
+            TestOutcome::Benchmarked(stats) => Some(Self {
+                name: result.name,
+                stats,
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DocMode {
+    /// Generates the documentation with `cargo doc`.
+    Build,
+    /// Runs the documentation tests with `cargo test --doc`.
+    Test,
+}
+
+#[derive(Debug, Clone)]
+pub struct DocRequest {
+    pub channel: Channel,
+    pub crate_type: CrateType,
+    pub edition: Edition,
+    pub mode: DocMode,
+    pub code: Code,
+}
+
+impl DocRequest {
+    const DOC_DIR: &str = "target/doc";
+    const MANIFEST_PATH: &str = "doc-files";
+
+    fn read_manifest_request(&self) -> ReadFileRequest {
+        ReadFileRequest {
+            path: Self::MANIFEST_PATH.to_owned(),
+        }
+    }
+}
+
+impl LowerRequest for DocRequest {
+    fn delete_files(&self) -> impl Iterator<Item = DeleteFileRequest> {
+        self.code.delete_requests(self.crate_type)
+    }
+
+    fn write_files(&self) -> impl Iterator<Item = WriteFileRequest> {
+        self.code.write_requests(self.crate_type)
+    }
+
+    fn execute_cargo_request(&self) -> ExecuteCommandRequest {
+        let args = match self.mode {
+            DocMode::Build => &["doc-files", "--no-deps", "-o", Self::MANIFEST_PATH][..],
+            DocMode::Test => &["test", "--doc"],
+        };
+
+        ExecuteCommandRequest {
+            cmd: "cargo".to_owned(),
+            args: args.iter().map(|&s| s.to_owned()).collect(),
+            envs: Default::default(),
+            cwd: None,
+            output_limit: None,
+            deadline: None,
+        }
+    }
+
+    fn kill_on_output_limit(&self) -> bool {
+        false
+    }
+}
+
+impl CargoTomlModifier for DocRequest {
+    fn modify_cargo_toml(&self, mut cargo_toml: toml::Value) -> toml::Value {
+        cargo_toml = modify_cargo_toml::set_edition(cargo_toml, self.edition.to_cargo_toml_key());
+
+        if let Some(crate_type) = self.crate_type.to_library_cargo_toml_key() {
+            cargo_toml = modify_cargo_toml::set_crate_type(cargo_toml, crate_type);
+        }
+        cargo_toml
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DocResponse {
+    pub success: bool,
+    pub exit_detail: String,
+    /// The generated HTML, JavaScript, CSS, and SVG files, relative to
+    /// the documentation root. Only populated in [`DocMode::Build`][].
+    pub files: Vec<DocFile>,
+    /// Only populated in [`DocMode::Test`][].
+    pub tests: Vec<TestResult>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DocFile {
+    pub path: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct CompileRequest {
+    pub target: CompileTarget,
+    /// `None` compiles for the host. Not available for targets
+    /// produced by a Cargo subcommand, such as
+    /// [`CompileTarget::Wasm`][], or together with crate features.
+    pub target_triple: Option<TargetTriple>,
+    pub channel: Channel,
+    pub crate_type: CrateType,
+    pub mode: Mode,
+    pub edition: Edition,
+    // TODO: Remove `tests` and `backtrace` -- don't make sense for compiling.
+    pub tests: bool,
+    pub backtrace: bool,
+    pub extra: ExtraOptions,
+    /// Not used when compiling for another target.
+    pub json_diagnostics: bool,
+    /// Also return the compiled binary or library. Not available for
+    /// [`CompileTarget::Hir`][].
+    pub export_artifact: bool,
+    pub code: Code,
+}
+
+impl CompileRequest {
+    const OUTPUT_PATH: &str = "compilation";
+    const ARTIFACT_PATH: &str = "artifact";
+
+    fn read_output_request(&self) -> ReadFileRequest {
+        ReadFileRequest {
+            path: Self::OUTPUT_PATH.to_owned(),
+        }
+    }
+
+    /// The target to invoke `rustc` for directly, if any.
+    fn rustc_target_triple(&self) -> Option<TargetTriple> {
+        use CompileTarget::*;
+
+        match self.target {
+            OptimizedMir(_, Some(_)) | Disassembly | Wasm => None,
+            _ => self.target_triple,
+        }
+    }
+
+    fn artifact_name(&self) -> &'static str {
+        use {CrateType::*, LibraryType::*};
+
+        let wasm = self.target == CompileTarget::Wasm;
+
+        match self.crate_type {
+            Binary | Library(Cdylib) if wasm => "playground.wasm",
+            Binary => "playground",
+            Library(Lib | Rlib) => "libplayground.rlib",
+            Library(Staticlib) => "libplayground.a",
+            Library(Dylib | Cdylib | ProcMacro) => "libplayground.so",
+        }
+    }
+
+    /// Where the compiled binary or library ends up.
+    fn artifact_path(&self) -> Option<String> {
+        let profile = self.mode.profile_dir();
+        let name = self.artifact_name();
+
+        match self.target {
+            // Stops before code generation
+            CompileTarget::Hir => None,
+            CompileTarget::Wasm => Some(format!("target/wasm32-unknown-unknown/{profile}/{name}")),
+            _ if self.rustc_target_triple().is_some() => Some(Self::ARTIFACT_PATH.to_owned()),
+            _ => Some(format!("target/{profile}/{name}")),
+        }
+    }
+
+    pub(crate) fn postprocess_result(&self, mut code: String) -> String {
+        if let CompileTarget::Assembly(_, demangle, process) = self.target {
+            if demangle == DemangleAssembly::Demangle {
+                code = asm_cleanup::demangle_asm(&code);
+            }
+
+            if process == ProcessAssembly::Filter {
+                code = asm_cleanup::filter_asm(&code);
+            }
+        }
+
+        code
+    }
+
+    /// Adds the `rustc` arguments that write the requested output.
+    fn add_output_args(&self, args: &mut Vec<&str>, x86: bool) {
+        use CompileTarget::*;
+
+        match self.target {
             Assembly(flavor, _, _) => {
-                args.extend(&["--", "--emit", "asm=compilation"]);
+                args.extend(&["--emit", "asm=compilation"]);
 
                 // Enable extra assembly comments for nightly builds
                 if let Channel::Nightly = self.channel {
@@ -617,72 +1531,730 @@ impl LowerRequest for CompileRequest {
                     args.push("verbose-asm");
                 }
 
-                args.push("-C");
-                match flavor {
-                    AssemblyFlavor::Att => args.push("llvm-args=-x86-asm-syntax=att"),
-                    AssemblyFlavor::Intel => args.push("llvm-args=-x86-asm-syntax=intel"),
+                if x86 {
+                    args.push("-C");
+                    match flavor {
+                        AssemblyFlavor::Att => args.push("llvm-args=-x86-asm-syntax=att"),
+                        AssemblyFlavor::Intel => args.push("llvm-args=-x86-asm-syntax=intel"),
+                    }
                 }
             }
-            LlvmIr => args.extend(&["--", "--emit", "llvm-ir=compilation"]),
-            Mir => args.extend(&["--", "--emit", "mir=compilation"]),
-            Hir => args.extend(&["--", "-Zunpretty=hir", "-o", Self::OUTPUT_PATH]),
-            Wasm => args.extend(&["-o", Self::OUTPUT_PATH]),
+            LlvmIr => args.extend(&["--emit", "llvm-ir=compilation"]),
+            Mir => args.extend(&["--emit", "mir=compilation"]),
+            OptimizedMir(level, None) => {
+                args.extend(&["--emit", "mir=compilation", level.to_flag()]);
+            }
+            LlvmMachineIr => args.extend(&[
+                "--emit",
+                "asm=compilation",
+                "-C",
+                // Stopping the code generator right before the
+                // assembly printer writes the machine IR instead
+                "llvm-args=-stop-after=machine-opt-remark-emitter",
+            ]),
+            Hir => args.extend(&["-Zunpretty=hir", "-o", Self::OUTPUT_PATH]),
+            // Produced by a Cargo subcommand
+            OptimizedMir(_, Some(_)) | Disassembly | Wasm => {}
+        }
+    }
+
+    /// Invokes `rustc` directly when compiling for another target.
+    /// Going through Cargo would rebuild every dependency for that
+    /// target, so crates are not available.
+    fn execute_rustc_request(&self, target_triple: TargetTriple) -> ExecuteCommandRequest {
+        let mut args = vec![
+            self.crate_type.primary_path(),
+            "--crate-name",
+            "playground",
+            "--crate-type",
+            self.crate_type.to_cargo_toml_key(),
+            "--edition",
+            self.edition.to_str(),
+            "--target",
+            target_triple.to_str(),
+        ];
+
+        // Match Cargo's `dev` and `release` profiles
+        match self.mode {
+            Mode::Debug => args.extend(["-C", "debuginfo=2"]),
+            Mode::Release => args.extend(["-C", "opt-level=3"]),
+        }
+
+        self.add_output_args(&mut args, target_triple.is_x86());
+        args.extend(self.extra.rustc_flags().iter().map(String::as_str));
+
+        // Cargo always links, but `rustc` only produces what is requested
+        let link_artifact = format!("link={}", Self::ARTIFACT_PATH);
+        if self.export_artifact {
+            args.extend(["--emit", &link_artifact]);
+        }
+
+        let mut envs = HashMap::new();
+        if self.backtrace {
+            envs.extend(kvs!("RUST_BACKTRACE" => "1"));
+        }
+        envs.extend(self.extra.envs().clone());
+
+        ExecuteCommandRequest {
+            cmd: "rustc".to_owned(),
+            args: args.into_iter().map(|s| s.to_owned()).collect(),
+            envs,
+            cwd: None,
+            output_limit: None,
+            deadline: None,
+        }
+    }
+}
+
+impl LowerRequest for CompileRequest {
+    fn delete_files(&self) -> impl Iterator<Item = DeleteFileRequest> {
+        self.code.delete_requests(self.crate_type)
+    }
+
+    fn write_files(&self) -> impl Iterator<Item = WriteFileRequest> {
+        self.code.write_requests(self.crate_type)
+    }
+
+    fn execute_cargo_request(&self) -> ExecuteCommandRequest {
+        use CompileTarget::*;
+
+        let subcommand = match self.target {
+            Wasm => "wasm",
+            OptimizedMir(_, Some(_)) => "mir-dump",
+            Disassembly => "disassemble",
+            _ => "rustc",
+        };
+
+        if let Some(target_triple) = self.rustc_target_triple() {
+            return self.execute_rustc_request(target_triple);
+        }
+
+        let mut args = vec![subcommand];
+        if let Mode::Release = self.mode {
+            args.push("--release");
+        }
+
+        match self.crate_type {
+            CrateType::Binary => args.extend(["--bin", "playground"]),
+            CrateType::Library(_) => args.push("--lib"),
+        }
+
+        let features = self.extra.features().join(",");
+        if !features.is_empty() {
+            args.extend(["--features", &features]);
+        }
+
+        if self.json_diagnostics {
+            args.push(JSON_MESSAGE_FORMAT);
+        }
+
+        match self.target {
+            Wasm | Disassembly => args.extend(&["-o", Self::OUTPUT_PATH]),
+            OptimizedMir(level, Some(pass)) => args.extend(&[
+                "-o",
+                Self::OUTPUT_PATH,
+                "--",
+                level.to_flag(),
+                pass.to_flag(),
+            ]),
+            _ => {
+                args.push("--");
+
+                // The container matches the host's architecture
+                let x86 = cfg!(target_arch = "x86_64");
+                self.add_output_args(&mut args, x86);
+            }
+        }
+        let mut envs = HashMap::new();
+        if self.backtrace {
+            envs.extend(kvs!("RUST_BACKTRACE" => "1"));
+        }
+        self.extra.add_envs(&mut envs);
+
+        ExecuteCommandRequest {
+            cmd: "cargo".to_owned(),
+            args: args.into_iter().map(|s| s.to_owned()).collect(),
+            envs,
+            cwd: None,
+            output_limit: None,
+            deadline: None,
+        }
+    }
+
+    fn kill_on_output_limit(&self) -> bool {
+        false
+    }
+}
+
+impl CargoTomlModifier for CompileRequest {
+    fn modify_cargo_toml(&self, mut cargo_toml: toml::Value) -> toml::Value {
+        cargo_toml = modify_cargo_toml::set_edition(cargo_toml, self.edition.to_cargo_toml_key());
+
+        if let Some(crate_type) = self.crate_type.to_library_cargo_toml_key() {
+            cargo_toml = modify_cargo_toml::set_crate_type(cargo_toml, crate_type);
+        }
+
+        if CompileTarget::Wasm == self.target {
+            cargo_toml = modify_cargo_toml::remove_dependencies(cargo_toml);
+            cargo_toml = modify_cargo_toml::set_release_lto(cargo_toml, true);
+        }
+
+        cargo_toml
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CompileResponse {
+    pub success: bool,
+    pub exit_detail: String,
+    pub code: String,
+    pub diagnostics: Vec<Diagnostic>,
+    /// Only present when requested and the compilation succeeded.
+    pub artifact: Option<Artifact>,
+    /// Why a requested artifact is missing despite a successful
+    /// compilation, such as when rustc ignores the crate type for
+    /// the target.
+    pub artifact_error: Option<String>,
+}
+
+/// A compiled binary or library.
+#[derive(Clone)]
+pub struct Artifact {
+    /// The file name Cargo gives the artifact.
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+impl fmt::Debug for Artifact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Artifact")
+            .field("name", &self.name)
+            .field("data", &format_args!("<{} bytes>", self.data.len()))
+            .finish()
+    }
+}
+
+impl Artifact {
+    pub const MAX_SIZE: u64 = 64 * 1024 * 1024;
+
+    async fn read(
+        commander: &Commander,
+        path: String,
+        name: &str,
+    ) -> Result<Self, ReadArtifactError> {
+        use read_artifact_error::*;
+
+        let stat = StatFileRequest { path: path.clone() };
+        let stat = commander.one(stat).await.context(CouldNotStatSnafu)?;
+        let stat = stat
+            .0
+            .filter(|s| s.kind == FileKind::File)
+            .context(MissingSnafu { path: &path })?;
+
+        let max = Self::MAX_SIZE;
+        ensure!(
+            stat.size <= max,
+            TooLargeSnafu {
+                size: stat.size,
+                max
+            }
+        );
+
+        let data = commander
+            .read_file(ReadFileRequest { path }, Self::MAX_SIZE)
+            .await
+            .context(CouldNotReadSnafu)?
+            .0;
+        let name = name.to_owned();
+
+        Ok(Self { name, data })
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+pub enum ReadArtifactError {
+    #[snafu(display("Could not get information about the artifact"))]
+    CouldNotStat { source: CommanderError },
+
+    #[snafu(display("The artifact `{path}` was not produced"))]
+    Missing { path: String },
+
+    #[snafu(display("The artifact is {size} bytes but only {max} bytes are allowed"))]
+    TooLarge { size: u64, max: u64 },
+
+    #[snafu(display("Could not read the artifact"))]
+    CouldNotRead { source: CommanderError },
+}
+
+#[derive(Debug, Clone)]
+pub struct FormatRequest {
+    pub channel: Channel,
+    pub crate_type: CrateType,
+    pub edition: Edition,
+    pub rustfmt_options: RustfmtOptions,
+    pub code: Code,
+}
+
+/// Options written to `rustfmt.toml`. When the code already contains
+/// a configuration file, these take precedence over its contents.
+///
+/// Unstable options are only applied on the nightly channel; rustfmt
+/// warns about and ignores them otherwise.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RustfmtOptions {
+    pub max_width: Option<u32>,
+    /// Unstable.
+    pub imports_granularity: Option<ImportsGranularity>,
+    /// Unstable.
+    pub group_imports: Option<GroupImports>,
+    pub style_edition: Option<Edition>,
+    /// Any other option, keyed by its name in `rustfmt.toml`.
+    pub other: BTreeMap<String, toml::Value>,
+}
+
+impl RustfmtOptions {
+    pub const NONE: Self = Self {
+        max_width: None,
+        imports_granularity: None,
+        group_imports: None,
+        style_edition: None,
+        other: BTreeMap::new(),
+    };
+
+    fn to_table(&self) -> toml::Table {
+        let Self {
+            max_width,
+            imports_granularity,
+            group_imports,
+            style_edition,
+            other,
+        } = self;
+
+        let mut table = toml::Table::new();
+
+        if let Some(max_width) = max_width {
+            table.insert("max_width".into(), i64::from(*max_width).into());
+        }
+
+        if let Some(imports_granularity) = imports_granularity {
+            table.insert(
+                "imports_granularity".into(),
+                imports_granularity.to_str().into(),
+            );
+        }
+
+        if let Some(group_imports) = group_imports {
+            table.insert("group_imports".into(), group_imports.to_str().into());
+        }
+
+        if let Some(style_edition) = style_edition {
+            table.insert("style_edition".into(), style_edition.to_str().into());
+        }
+
+        table.extend(other.clone());
+
+        table
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ImportsGranularity {
+    Preserve,
+    Crate,
+    Module,
+    Item,
+    One,
+}
+
+impl ImportsGranularity {
+    fn to_str(self) -> &'static str {
+        match self {
+            ImportsGranularity::Preserve => "Preserve",
+            ImportsGranularity::Crate => "Crate",
+            ImportsGranularity::Module => "Module",
+            ImportsGranularity::Item => "Item",
+            ImportsGranularity::One => "One",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GroupImports {
+    Preserve,
+    StdExternalCrate,
+    One,
+}
+
+impl GroupImports {
+    fn to_str(self) -> &'static str {
+        match self {
+            GroupImports::Preserve => "Preserve",
+            GroupImports::StdExternalCrate => "StdExternalCrate",
+            GroupImports::One => "One",
+        }
+    }
+}
+
+/// A tool's configuration file, such as `rustfmt.toml`. The user's
+/// copy of the file is written with the request's options merged
+/// in; when there isn't one, the file is generated from the options
+/// alone.
+struct ConfigFile {
+    /// The first name is used when generating the file.
+    names: [&'static str; 2],
+}
+
+impl ConfigFile {
+    const RUSTFMT: Self = Self {
+        names: ["rustfmt.toml", ".rustfmt.toml"],
+    };
+
+    const CLIPPY: Self = Self {
+        names: ["clippy.toml", ".clippy.toml"],
+    };
+
+    fn matches(&self, path: &str) -> bool {
+        self.names.contains(&path)
+    }
+
+    /// The user's configuration files, as submitted.
+    fn user_files<'a>(
+        &'a self,
+        code: &'a Code,
+        crate_type: CrateType,
+    ) -> impl Iterator<Item = CodeFile> + use<'a> {
+        code.files(crate_type)
+            .filter(|(name, _)| self.matches(name))
+            .map(|(name, content)| CodeFile {
+                name: name.to_owned(),
+                content: content.to_owned(),
+            })
+    }
+
+    fn generates(&self, code: &Code, crate_type: CrateType, options: &toml::Table) -> bool {
+        self.user_files(code, crate_type).next().is_none() && !options.is_empty()
+    }
+
+    /// Don't let the configuration from a previous request leak in.
+    fn delete_requests<'a>(
+        &'a self,
+        code: &'a Code,
+        crate_type: CrateType,
+        options: &toml::Table,
+    ) -> impl Iterator<Item = DeleteFileRequest> + use<'a> {
+        let generated = self
+            .generates(code, crate_type, options)
+            .then_some(self.names[0]);
+
+        self.names
+            .into_iter()
+            .filter(move |&path| !self.user_files(code, crate_type).any(|cf| cf.name == path))
+            .filter(move |&path| Some(path) != generated)
+            .map(|path| DeleteFileRequest { path: path.into() })
+    }
+
+    /// Replaces the user's configuration files in `write_requests`
+    /// with the merged versions, generating one if needed.
+    fn write_requests<'a>(
+        &'a self,
+        write_requests: impl Iterator<Item = WriteFileRequest> + 'a,
+        code: &'a Code,
+        crate_type: CrateType,
+        options: toml::Table,
+    ) -> impl Iterator<Item = WriteFileRequest> + 'a {
+        let generated = self
+            .generates(code, crate_type, &options)
+            .then(|| WriteFileRequest {
+                path: self.names[0].into(),
+                content: Self::merge(vec![], &options),
+            });
+
+        write_requests
+            .map(move |mut req| {
+                if self.matches(&req.path) {
+                    req.content = Self::merge(req.content, &options);
+                }
+                req
+            })
+            .chain(generated)
+    }
+
+    /// Configuration files that cannot be parsed are left untouched
+    /// so that the tool can report the problem.
+    fn merge(config: Vec<u8>, options: &toml::Table) -> Vec<u8> {
+        if options.is_empty() {
+            return config;
+        }
+
+        let table = str::from_utf8(&config).ok().and_then(|c| c.parse().ok());
+        let Some(mut table): Option<toml::Table> = table else {
+            return config;
+        };
+
+        table.extend(options.clone());
+        table.to_string().into_bytes()
+    }
+}
+
+impl FormatRequest {
+    fn read_output_requests(&self) -> impl Iterator<Item = ReadFileRequest> + use<'_> {
+        self.code
+            .read_requests(self.crate_type)
+            .filter(|req| !ConfigFile::RUSTFMT.matches(&req.path))
+    }
+
+    fn config_files(&self) -> impl Iterator<Item = CodeFile> + use<'_> {
+        ConfigFile::RUSTFMT.user_files(&self.code, self.crate_type)
+    }
+}
+
+impl LowerRequest for FormatRequest {
+    fn delete_files(&self) -> impl Iterator<Item = DeleteFileRequest> {
+        let options = self.rustfmt_options.to_table();
+        let stale_config_files =
+            ConfigFile::RUSTFMT.delete_requests(&self.code, self.crate_type, &options);
+
+        self.code
+            .delete_requests(self.crate_type)
+            .chain(stale_config_files)
+    }
+
+    fn write_files(&self) -> impl Iterator<Item = WriteFileRequest> {
+        let options = self.rustfmt_options.to_table();
+        ConfigFile::RUSTFMT.write_requests(
+            self.code.write_requests(self.crate_type),
+            &self.code,
+            self.crate_type,
+            options,
+        )
+    }
+
+    fn execute_cargo_request(&self) -> ExecuteCommandRequest {
+        ExecuteCommandRequest {
+            cmd: "cargo".to_owned(),
+            args: vec!["fmt".to_owned()],
+            envs: Default::default(),
+            cwd: None,
+            output_limit: None,
+            deadline: None,
+        }
+    }
+
+    fn kill_on_output_limit(&self) -> bool {
+        false
+    }
+}
+
+impl CargoTomlModifier for FormatRequest {
+    fn modify_cargo_toml(&self, mut cargo_toml: toml::Value) -> toml::Value {
+        cargo_toml = modify_cargo_toml::set_edition(cargo_toml, self.edition.to_cargo_toml_key());
+
+        if let Some(crate_type) = self.crate_type.to_library_cargo_toml_key() {
+            cargo_toml = modify_cargo_toml::set_crate_type(cargo_toml, crate_type);
+        }
+        cargo_toml
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FormatResponse {
+    pub success: bool,
+    pub exit_detail: String,
+    pub code: Code,
+}
+
+#[derive(Debug, Clone)]
+pub struct ClippyRequest {
+    pub channel: Channel,
+    pub crate_type: CrateType,
+    pub edition: Edition,
+    pub json_diagnostics: bool,
+    pub lints: ClippyLints,
+    pub code: Code,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClippyLints {
+    /// Lint groups that are not enabled by default, enabled at the
+    /// warn level.
+    pub groups: ClippyLintGroups,
+    /// Individual lints, such as `clippy::unwrap_used`. These are
+    /// applied after the groups and take precedence over them.
+    pub levels: BTreeMap<String, LintLevel>,
+    /// Written to `clippy.toml`. When the code already contains a
+    /// configuration file, these take precedence over its contents.
+    pub config: BTreeMap<String, toml::Value>,
+}
+
+impl ClippyLints {
+    pub const NONE: Self = Self {
+        groups: ClippyLintGroups::NONE,
+        levels: BTreeMap::new(),
+        config: BTreeMap::new(),
+    };
+
+    fn config_table(&self) -> toml::Table {
+        self.config.clone().into_iter().collect()
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct ClippyLintGroups {
+    pub pedantic: bool,
+    pub nursery: bool,
+    pub restriction: bool,
+    pub cargo: bool,
+}
+
+impl ClippyLintGroups {
+    pub const NONE: Self = Self {
+        pedantic: false,
+        nursery: false,
+        restriction: false,
+        cargo: false,
+    };
+
+    fn enabled(self) -> impl Iterator<Item = &'static str> {
+        let Self {
+            pedantic,
+            nursery,
+            restriction,
+            cargo,
+        } = self;
+
+        [
+            (pedantic, "clippy::pedantic"),
+            (nursery, "clippy::nursery"),
+            (restriction, "clippy::restriction"),
+            (cargo, "clippy::cargo"),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, group)| enabled.then_some(group))
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+    Forbid,
+}
+
+impl LintLevel {
+    fn to_flag(self) -> &'static str {
+        match self {
+            LintLevel::Allow => "-A",
+            LintLevel::Warn => "-W",
+            LintLevel::Deny => "-D",
+            LintLevel::Forbid => "-F",
+        }
+    }
+}
+
+impl LowerRequest for ClippyRequest {
+    fn delete_files(&self) -> impl Iterator<Item = DeleteFileRequest> {
+        let config = self.lints.config_table();
+        let stale_config_files =
+            ConfigFile::CLIPPY.delete_requests(&self.code, self.crate_type, &config);
+
+        self.code
+            .delete_requests(self.crate_type)
+            .chain(stale_config_files)
+    }
+
+    fn write_files(&self) -> impl Iterator<Item = WriteFileRequest> {
+        let config = self.lints.config_table();
+        ConfigFile::CLIPPY.write_requests(
+            self.code.write_requests(self.crate_type),
+            &self.code,
+            self.crate_type,
+            config,
+        )
+    }
+
+    fn execute_cargo_request(&self) -> ExecuteCommandRequest {
+        let mut args = vec!["clippy"];
+        if self.json_diagnostics {
+            args.push(JSON_MESSAGE_FORMAT);
+        }
+
+        let ClippyLints {
+            groups,
+            levels,
+            config: _,
+        } = &self.lints;
+
+        let mut lint_args = vec![];
+        for group in groups.enabled() {
+            lint_args.extend(["-W", group]);
         }
-        let mut envs = HashMap::new();
-        if self.backtrace {
-            envs.extend(kvs!("RUST_BACKTRACE" => "1"));
+        for (lint, level) in levels {
+            lint_args.extend([level.to_flag(), lint]);
+        }
+
+        if !lint_args.is_empty() {
+            args.push("--");
+            args.extend(lint_args);
         }
 
         ExecuteCommandRequest {
             cmd: "cargo".to_owned(),
             args: args.into_iter().map(|s| s.to_owned()).collect(),
-            envs,
+            envs: Default::default(),
             cwd: None,
+            output_limit: None,
+            deadline: None,
         }
     }
+
+    fn kill_on_output_limit(&self) -> bool {
+        false
+    }
 }
 
-impl CargoTomlModifier for CompileRequest {
+impl CargoTomlModifier for ClippyRequest {
     fn modify_cargo_toml(&self, mut cargo_toml: toml::Value) -> toml::Value {
         cargo_toml = modify_cargo_toml::set_edition(cargo_toml, self.edition.to_cargo_toml_key());
 
         if let Some(crate_type) = self.crate_type.to_library_cargo_toml_key() {
             cargo_toml = modify_cargo_toml::set_crate_type(cargo_toml, crate_type);
         }
-
-        if CompileTarget::Wasm == self.target {
-            cargo_toml = modify_cargo_toml::remove_dependencies(cargo_toml);
-            cargo_toml = modify_cargo_toml::set_release_lto(cargo_toml, true);
-        }
-
         cargo_toml
     }
 }
 
 #[derive(Debug, Clone)]
-pub struct CompileResponse {
+pub struct ClippyResponse {
     pub success: bool,
     pub exit_detail: String,
-    pub code: String,
+    pub diagnostics: Vec<Diagnostic>,
 }
 
 #[derive(Debug, Clone)]
-pub struct FormatRequest {
+pub struct MigrateRequest {
     pub channel: Channel,
     pub crate_type: CrateType,
+    /// The edition the code is currently written for; it is migrated
+    /// to [`Edition::next`][].
     pub edition: Edition,
     pub code: Code,
 }
 
-impl FormatRequest {
+impl MigrateRequest {
     fn read_output_requests(&self) -> impl Iterator<Item = ReadFileRequest> + use<'_> {
-        self.code
-            .files(self.crate_type)
-            .map(|(path, _)| ReadFileRequest { path: path.into() })
+        self.code.read_requests(self.crate_type)
+    }
+
+    /// The manifest once the code has been migrated to `next`.
+    fn migrated_cargo_toml(&self, cargo_toml: toml::Value, next: Edition) -> toml::Value {
+        let cargo_toml = self.modify_cargo_toml(cargo_toml);
+        modify_cargo_toml::set_edition(cargo_toml, next.to_cargo_toml_key())
     }
 }
 
-impl LowerRequest for FormatRequest {
+impl LowerRequest for MigrateRequest {
     fn delete_files(&self) -> impl Iterator<Item = DeleteFileRequest> {
         self.code.delete_requests(self.crate_type)
     }
@@ -692,16 +2264,29 @@ impl LowerRequest for FormatRequest {
     }
 
     fn execute_cargo_request(&self) -> ExecuteCommandRequest {
+        let mut args = vec!["migrate".to_owned(), JSON_MESSAGE_FORMAT.to_owned()];
+
+        if let Some(next) = self.edition.next() {
+            args.push("--lints".to_owned());
+            args.push(format!("rust-{}-compatibility", next.to_str()));
+        }
+
         ExecuteCommandRequest {
             cmd: "cargo".to_owned(),
-            args: vec!["fmt".to_owned()],
+            args,
             envs: Default::default(),
             cwd: None,
+            output_limit: None,
+            deadline: None,
         }
     }
+
+    fn kill_on_output_limit(&self) -> bool {
+        false
+    }
 }
 
-impl CargoTomlModifier for FormatRequest {
+impl CargoTomlModifier for MigrateRequest {
     fn modify_cargo_toml(&self, mut cargo_toml: toml::Value) -> toml::Value {
         cargo_toml = modify_cargo_toml::set_edition(cargo_toml, self.edition.to_cargo_toml_key());
 
@@ -713,21 +2298,42 @@ impl CargoTomlModifier for FormatRequest {
 }
 
 #[derive(Debug, Clone)]
-pub struct FormatResponse {
+pub struct MigrateResponse {
     pub success: bool,
     pub exit_detail: String,
+    /// The edition the code was migrated to.
+    pub edition: Edition,
     pub code: Code,
+    /// The edition compatibility lints that fired before the fixes
+    /// were applied.
+    pub lints: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FixTool {
+    /// Applies the compiler's suggestions with `cargo fix`.
+    Rustc,
+    /// Applies the compiler's and Clippy's suggestions with `cargo
+    /// clippy --fix`.
+    Clippy,
 }
 
 #[derive(Debug, Clone)]
-pub struct ClippyRequest {
+pub struct FixRequest {
     pub channel: Channel,
     pub crate_type: CrateType,
     pub edition: Edition,
+    pub tool: FixTool,
     pub code: Code,
 }
 
-impl LowerRequest for ClippyRequest {
+impl FixRequest {
+    fn read_output_requests(&self) -> impl Iterator<Item = ReadFileRequest> + use<'_> {
+        self.code.read_requests(self.crate_type)
+    }
+}
+
+impl LowerRequest for FixRequest {
     fn delete_files(&self) -> impl Iterator<Item = DeleteFileRequest> {
         self.code.delete_requests(self.crate_type)
     }
@@ -737,16 +2343,29 @@ impl LowerRequest for ClippyRequest {
     }
 
     fn execute_cargo_request(&self) -> ExecuteCommandRequest {
+        let mut args = match self.tool {
+            FixTool::Rustc => vec!["fix"],
+            FixTool::Clippy => vec!["clippy", "--fix"],
+        };
+        // The project is not under version control
+        args.push("--allow-no-vcs");
+
         ExecuteCommandRequest {
             cmd: "cargo".to_owned(),
-            args: vec!["clippy".to_owned()],
+            args: args.into_iter().map(|s| s.to_owned()).collect(),
             envs: Default::default(),
             cwd: None,
+            output_limit: None,
+            deadline: None,
         }
     }
+
+    fn kill_on_output_limit(&self) -> bool {
+        false
+    }
 }
 
-impl CargoTomlModifier for ClippyRequest {
+impl CargoTomlModifier for FixRequest {
     fn modify_cargo_toml(&self, mut cargo_toml: toml::Value) -> toml::Value {
         cargo_toml = modify_cargo_toml::set_edition(cargo_toml, self.edition.to_cargo_toml_key());
 
@@ -758,9 +2377,10 @@ impl CargoTomlModifier for ClippyRequest {
 }
 
 #[derive(Debug, Clone)]
-pub struct ClippyResponse {
+pub struct FixResponse {
     pub success: bool,
     pub exit_detail: String,
+    pub code: Code,
 }
 
 #[derive(Debug, Clone)]
@@ -770,9 +2390,121 @@ pub struct MiriRequest {
     pub edition: Edition,
     pub tests: bool,
     pub aliasing_model: AliasingModel,
+    pub options: MiriOptions,
     pub code: Code,
 }
 
+/// Miri flags beyond the aliasing model. Use
+/// [`MiriOptions::validate`] before running a request.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MiriOptions {
+    /// The seed for Miri's random number generator.
+    pub seed: Option<u64>,
+    /// Run the program once for each seed in the range.
+    pub many_seeds: Option<ops::Range<u64>>,
+    pub strict_provenance: bool,
+    pub ignore_leaks: bool,
+    /// The probability of preempting the active thread at the end of
+    /// each basic block, from 0 to 1. Zero makes scheduling
+    /// deterministic.
+    pub preemption_rate: Option<f64>,
+    /// Keep the program isolated from the host, such as the file
+    /// system and the clock.
+    pub isolation: bool,
+    pub symbolic_alignment_check: bool,
+}
+
+impl MiriOptions {
+    pub const NONE: Self = Self {
+        seed: None,
+        many_seeds: None,
+        strict_provenance: false,
+        ignore_leaks: false,
+        preemption_rate: None,
+        isolation: false,
+        symbolic_alignment_check: false,
+    };
+
+    /// Each seed runs the entire program, so the range is kept small.
+    pub const MAX_MANY_SEEDS: u64 = 64;
+
+    pub fn validate(&self) -> Result<(), MiriOptionsError> {
+        use miri_options_error::*;
+
+        ensure!(
+            self.seed.is_none() || self.many_seeds.is_none(),
+            SeedConflictSnafu
+        );
+
+        if let Some(seeds) = &self.many_seeds {
+            let count = seeds.end.saturating_sub(seeds.start);
+            ensure!(
+                (1..=Self::MAX_MANY_SEEDS).contains(&count),
+                ManySeedsRangeSnafu {
+                    start: seeds.start,
+                    end: seeds.end,
+                }
+            );
+        }
+
+        if let Some(rate) = self.preemption_rate {
+            ensure!((0.0..=1.0).contains(&rate), PreemptionRateSnafu { rate });
+        }
+
+        Ok(())
+    }
+
+    fn miriflags(&self) -> Vec<String> {
+        let mut flags = Vec::new();
+
+        if let Some(seed) = self.seed {
+            flags.push(format!("-Zmiri-seed={seed}"));
+        }
+
+        if let Some(seeds) = &self.many_seeds {
+            flags.push(format!("-Zmiri-many-seeds={}..{}", seeds.start, seeds.end));
+        }
+
+        if self.strict_provenance {
+            flags.push("-Zmiri-strict-provenance".to_owned());
+        }
+
+        if self.ignore_leaks {
+            flags.push("-Zmiri-ignore-leaks".to_owned());
+        }
+
+        if let Some(rate) = self.preemption_rate {
+            flags.push(format!("-Zmiri-preemption-rate={rate}"));
+        }
+
+        if !self.isolation {
+            flags.push("-Zmiri-disable-isolation".to_owned());
+        }
+
+        if self.symbolic_alignment_check {
+            flags.push("-Zmiri-symbolic-alignment-check".to_owned());
+        }
+
+        flags
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+pub enum MiriOptionsError {
+    #[snafu(display("A single seed and a range of seeds cannot both be used"))]
+    SeedConflict,
+
+    #[snafu(display(
+        "The seed range {start}..{end} must contain between 1 and {} seeds",
+        MiriOptions::MAX_MANY_SEEDS,
+    ))]
+    ManySeedsRange { start: u64, end: u64 },
+
+    #[snafu(display("The preemption rate {rate} must be between 0 and 1"))]
+    PreemptionRate { rate: f64 },
+}
+
 impl LowerRequest for MiriRequest {
     fn delete_files(&self) -> impl Iterator<Item = DeleteFileRequest> {
         self.code.delete_requests(self.crate_type)
@@ -786,10 +2518,10 @@ impl LowerRequest for MiriRequest {
         let mut miriflags = Vec::new();
 
         if matches!(self.aliasing_model, AliasingModel::Tree) {
-            miriflags.push("-Zmiri-tree-borrows");
+            miriflags.push("-Zmiri-tree-borrows".to_owned());
         }
 
-        miriflags.push("-Zmiri-disable-isolation");
+        miriflags.extend(self.options.miriflags());
 
         let miriflags = miriflags.join(" ");
 
@@ -809,6 +2541,8 @@ impl LowerRequest for MiriRequest {
             }
             .collect(),
             cwd: None,
+            output_limit: None,
+            deadline: None,
         }
     }
 }
@@ -862,8 +2596,14 @@ impl LowerRequest for MacroExpansionRequest {
             args: args.into_iter().map(str::to_owned).collect(),
             envs: Default::default(),
             cwd: None,
+            output_limit: None,
+            deadline: None,
         }
     }
+
+    fn kill_on_output_limit(&self) -> bool {
+        false
+    }
 }
 
 impl CargoTomlModifier for MacroExpansionRequest {
@@ -883,6 +2623,61 @@ pub struct MacroExpansionResponse {
     pub exit_detail: String,
 }
 
+/// Lists the files left behind by previous builds.
+#[derive(Debug, Clone)]
+pub struct BuildOutputsRequest {
+    pub channel: Channel,
+    pub mode: Mode,
+    /// `None` for builds for the host.
+    pub target: Option<TargetTriple>,
+}
+
+impl BuildOutputsRequest {
+    fn directory(&self) -> String {
+        let profile = self.mode.profile_dir();
+
+        match self.target {
+            Some(target) => format!("target/{}/{profile}", target.to_str()),
+            None => format!("target/{profile}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BuildOutputsResponse {
+    /// Sorted by path.
+    pub outputs: Vec<BuildOutput>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BuildOutput {
+    /// Relative to the project directory.
+    pub path: String,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+impl BuildOutput {
+    /// Dependency information and lock files are bookkeeping for
+    /// Cargo, not outputs.
+    fn is_output(name: &str) -> bool {
+        !name.starts_with('.') && !name.ends_with(".d")
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+pub enum BuildOutputsError {
+    #[snafu(display("Could not start the container"))]
+    CouldNotStartContainer { source: Error },
+
+    #[snafu(display("Could not get information about the build directory"))]
+    CouldNotStatDirectory { source: CommanderError },
+
+    #[snafu(display("Could not list the build directory"))]
+    CouldNotListDirectory { source: CommanderError },
+}
+
 #[derive(Debug, Clone)]
 pub struct WithOutput<T> {
     pub response: T,
@@ -973,15 +2768,78 @@ pub trait ContainerPermit: Send + Sync + fmt::Debug + fmt::Display + 'static {
 /// Represents one allowed process.
 pub trait ProcessPermit: Send + Sync + fmt::Debug + 'static {}
 
+/// The resources that each worker and the processes it runs may
+/// consume.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourceProfile {
+    /// Maximum amount of memory, in bytes.
+    pub memory_bytes: u64,
+
+    /// Maximum amount of memory plus swap, in bytes.
+    pub memory_swap_bytes: u64,
+
+    /// How many CPUs may be used. `None` means no limit.
+    pub cpus: Option<f64>,
+
+    /// Maximum number of processes and threads.
+    pub pids: u64,
+
+    /// How long a single request may run before it's abandoned.
+    pub timeout: Duration,
+
+    /// How long a process started for a request may run before the
+    /// worker kills it. Unlike `timeout`, this also applies to
+    /// streaming requests, which are otherwise only bounded by their
+    /// session. `None` means no limit.
+    pub process_timeout: Option<Duration>,
+
+    /// Maximum number of bytes a process may write to stdout and
+    /// stderr combined before the rest is discarded. Processes
+    /// running the user's code are also killed.
+    pub output_bytes: usize,
+}
+
+impl Default for ResourceProfile {
+    fn default() -> Self {
+        Self {
+            memory_bytes: 512 * 1024 * 1024,
+            memory_swap_bytes: 640 * 1024 * 1024,
+            cpus: None,
+            pids: 512,
+            timeout: Duration::from_secs(10),
+            process_timeout: None,
+            output_bytes: 640 * 1024,
+        }
+    }
+}
+
 /// Enforces a limited number of concurrent `Coordinator`s.
 #[derive(Debug)]
 pub struct CoordinatorFactory {
     limits: Arc<dyn ResourceLimits>,
+    backend: BackendConfig,
+    resource_profile: Arc<ResourceProfile>,
 }
 
 impl CoordinatorFactory {
     pub fn new(limits: Arc<dyn ResourceLimits>) -> Self {
-        Self { limits }
+        Self::with_config(limits, Default::default(), Default::default())
+    }
+
+    pub fn with_config(
+        limits: Arc<dyn ResourceLimits>,
+        backend: BackendConfig,
+        resource_profile: ResourceProfile,
+    ) -> Self {
+        Self {
+            limits,
+            backend,
+            resource_profile: Arc::new(resource_profile),
+        }
+    }
+
+    pub fn resource_profile(&self) -> &ResourceProfile {
+        &self.resource_profile
     }
 
     pub fn build<B>(&self) -> Coordinator<B>
@@ -989,10 +2847,21 @@ impl CoordinatorFactory {
         B: Backend + Default,
     {
         let limits = self.limits.clone();
+        let resource_profile = self.resource_profile.clone();
 
         let backend = B::default();
 
-        Coordinator::new(limits, backend)
+        Coordinator::new(limits, resource_profile, backend)
+    }
+
+    /// Builds a `Coordinator` that uses the configured backend.
+    pub fn build_configured(&self) -> Coordinator<ConfiguredBackend> {
+        let limits = self.limits.clone();
+        let resource_profile = self.resource_profile.clone();
+
+        let backend = ConfiguredBackend::new(&self.backend);
+
+        Coordinator::new(limits, resource_profile, backend)
     }
 
     pub async fn container_requested(&self) {
@@ -1003,6 +2872,7 @@ impl CoordinatorFactory {
 #[derive(Debug)]
 pub struct Coordinator<B> {
     limits: Arc<dyn ResourceLimits>,
+    resource_profile: Arc<ResourceProfile>,
     backend: B,
     stable: OnceCell<Container>,
     beta: OnceCell<Container>,
@@ -1023,9 +2893,14 @@ impl<B> Coordinator<B>
 where
     B: Backend,
 {
-    fn new(limits: Arc<dyn ResourceLimits>, backend: B) -> Self {
+    fn new(
+        limits: Arc<dyn ResourceLimits>,
+        resource_profile: Arc<ResourceProfile>,
+        backend: B,
+    ) -> Self {
         Self {
             limits,
+            resource_profile,
             backend,
             stable: OnceCell::new(),
             beta: OnceCell::new(),
@@ -1064,6 +2939,19 @@ where
             .map_err(Into::into)
     }
 
+    pub async fn build_outputs(
+        &self,
+        request: BuildOutputsRequest,
+    ) -> Result<BuildOutputsResponse, BuildOutputsError> {
+        use build_outputs_error::*;
+
+        self.select_channel(request.channel)
+            .await
+            .context(CouldNotStartContainerSnafu)?
+            .build_outputs(request)
+            .await
+    }
+
     pub async fn execute(
         &self,
         request: ExecuteRequest,
@@ -1145,6 +3033,57 @@ where
             .await
     }
 
+    pub async fn fix(&self, request: FixRequest) -> Result<WithOutput<FixResponse>, FixError> {
+        use fix_error::*;
+
+        self.select_channel(request.channel)
+            .await
+            .context(CouldNotStartContainerSnafu)?
+            .fix(request)
+            .await
+    }
+
+    pub async fn begin_fix(
+        &self,
+        token: CancellationToken,
+        request: FixRequest,
+    ) -> Result<ActiveFix, FixError> {
+        use fix_error::*;
+
+        self.select_channel(request.channel)
+            .await
+            .context(CouldNotStartContainerSnafu)?
+            .begin_fix(token, request)
+            .await
+    }
+
+    pub async fn migrate(
+        &self,
+        request: MigrateRequest,
+    ) -> Result<WithOutput<MigrateResponse>, MigrateError> {
+        use migrate_error::*;
+
+        self.select_channel(request.channel)
+            .await
+            .context(CouldNotStartContainerSnafu)?
+            .migrate(request)
+            .await
+    }
+
+    pub async fn begin_migrate(
+        &self,
+        token: CancellationToken,
+        request: MigrateRequest,
+    ) -> Result<ActiveMigration, MigrateError> {
+        use migrate_error::*;
+
+        self.select_channel(request.channel)
+            .await
+            .context(CouldNotStartContainerSnafu)?
+            .begin_migrate(token, request)
+            .await
+    }
+
     pub async fn clippy(
         &self,
         request: ClippyRequest,
@@ -1172,6 +3111,57 @@ where
             .await
     }
 
+    pub async fn doc(&self, request: DocRequest) -> Result<WithOutput<DocResponse>, DocError> {
+        use doc_error::*;
+
+        self.select_channel(request.channel)
+            .await
+            .context(CouldNotStartContainerSnafu)?
+            .doc(request)
+            .await
+    }
+
+    pub async fn begin_doc(
+        &self,
+        token: CancellationToken,
+        request: DocRequest,
+    ) -> Result<ActiveDoc, DocError> {
+        use doc_error::*;
+
+        self.select_channel(request.channel)
+            .await
+            .context(CouldNotStartContainerSnafu)?
+            .begin_doc(token, request)
+            .await
+    }
+
+    pub async fn bench(
+        &self,
+        request: BenchRequest,
+    ) -> Result<WithOutput<BenchResponse>, BenchError> {
+        use bench_error::*;
+
+        self.select_channel(request.channel)
+            .await
+            .context(CouldNotStartContainerSnafu)?
+            .bench(request)
+            .await
+    }
+
+    pub async fn begin_bench(
+        &self,
+        token: CancellationToken,
+        request: BenchRequest,
+    ) -> Result<ActiveBench, BenchError> {
+        use bench_error::*;
+
+        self.select_channel(request.channel)
+            .await
+            .context(CouldNotStartContainerSnafu)?
+            .begin_bench(token, request)
+            .await
+    }
+
     pub async fn miri(&self, request: MiriRequest) -> Result<WithOutput<MiriResponse>, MiriError> {
         use miri_error::*;
 
@@ -1264,7 +3254,13 @@ where
             .get_or_try_init(|| {
                 let limits = self.limits.clone();
                 let token = self.token.0.clone();
-                Container::new(channel, limits, token, &self.backend)
+                Container::new(
+                    channel,
+                    limits,
+                    token,
+                    &self.resource_profile,
+                    &self.backend,
+                )
             })
             .await
     }
@@ -1292,6 +3288,8 @@ struct Container {
     kill_child: TerminateContainer,
     modify_cargo_toml: ModifyCargoToml,
     commander: Commander,
+    process_timeout: Option<Duration>,
+    output_bytes: usize,
 }
 
 impl Container {
@@ -1299,12 +3297,14 @@ impl Container {
         channel: Channel,
         limits: Arc<dyn ResourceLimits>,
         token: CancellationToken,
+        resource_profile: &ResourceProfile,
         backend: &impl Backend,
     ) -> Result<Self> {
         let permit = limits.next_container().await.context(AcquirePermitSnafu)?;
 
+        backend.prepare_worker(channel).await?;
         let (mut child, kill_child, stdin, stdout) =
-            backend.run_worker_in_background(channel, &permit)?;
+            backend.run_worker_in_background(channel, &permit, resource_profile)?;
         let IoQueue {
             mut tasks,
             to_worker_tx,
@@ -1346,12 +3346,31 @@ impl Container {
         )
         .abort_on_drop();
 
-        let commander = Commander {
+        let mut commander = Commander {
             to_worker_tx,
             to_demultiplexer_tx: command_tx,
             id: Default::default(),
+            capabilities: None,
         };
 
+        // A worker that speaks a different protocol may not even be
+        // able to decode this, in which case it exits.
+        let capabilities = commander.one(Hello::new()).await.context(HandshakeSnafu)?;
+        ensure!(
+            capabilities.protocol_version >= MIN_PROTOCOL_VERSION,
+            ProtocolMismatchSnafu {
+                worker_build: &capabilities.worker_build,
+                worker: capabilities.protocol_version,
+            }
+        );
+        trace!(
+            worker_build = capabilities.worker_build,
+            protocol_version = capabilities.protocol_version,
+            message_kinds = ?capabilities.message_kinds,
+            "worker is ready",
+        );
+        commander.capabilities = Some(Arc::new(capabilities));
+
         let modify_cargo_toml = ModifyCargoToml::new(commander.clone())
             .await
             .context(CouldNotLoadCargoTomlSnafu)?;
@@ -1362,6 +3381,8 @@ impl Container {
             kill_child,
             modify_cargo_toml,
             commander,
+            process_timeout: resource_profile.process_timeout,
+            output_bytes: resource_profile.output_bytes,
         })
     }
 
@@ -1447,6 +3468,8 @@ impl Container {
             stdout_rx,
             stderr_rx,
             status_rx,
+            diagnostic_rx: _,
+            test_rx: _,
         } = self.spawn_cargo_task(token, cmd).await?;
 
         drop(stdin_tx);
@@ -1461,11 +3484,58 @@ impl Container {
         let read = ReadFileRequest {
             path: "crate-information.json".into(),
         };
-        let read = self.commander.one(read).await?;
+        let read = self
+            .commander
+            .read_file(read, Commander::MAX_FILE_LEN)
+            .await?;
         let crates = serde_json::from_slice::<Vec<InternalCrate>>(&read.0)?;
         Ok(crates.into_iter().map(Into::into).collect())
     }
 
+    async fn build_outputs(
+        &self,
+        request: BuildOutputsRequest,
+    ) -> Result<BuildOutputsResponse, BuildOutputsError> {
+        use build_outputs_error::*;
+
+        let directory = request.directory();
+
+        // Nothing has been built for this configuration yet
+        let stat = StatFileRequest {
+            path: directory.clone(),
+        };
+        let stat = self
+            .commander
+            .one(stat)
+            .await
+            .context(CouldNotStatDirectorySnafu)?;
+        if !stat.0.is_some_and(|s| s.kind == FileKind::Directory) {
+            return Ok(BuildOutputsResponse { outputs: vec![] });
+        }
+
+        let list = ListDirectoryRequest {
+            path: directory.clone(),
+        };
+        let list = self
+            .commander
+            .one(list)
+            .await
+            .context(CouldNotListDirectorySnafu)?;
+
+        let outputs = list
+            .0
+            .into_iter()
+            .filter(|e| e.stat.kind == FileKind::File && BuildOutput::is_output(&e.name))
+            .map(|e| BuildOutput {
+                path: format!("{directory}/{}", e.name),
+                size: e.stat.size,
+                modified: e.stat.modified,
+            })
+            .collect();
+
+        Ok(BuildOutputsResponse { outputs })
+    }
+
     async fn execute(
         &self,
         request: ExecuteRequest,
@@ -1479,10 +3549,12 @@ impl Container {
             stdout_rx,
             stderr_rx,
             status_rx,
+            test_rx,
         } = self.begin_execute(token, request).await?;
 
         drop(stdin_tx);
         drop(status_rx);
+        drop(test_rx);
 
         WithOutput::try_absorb(task, stdout_rx, stderr_rx).await
     }
@@ -1495,6 +3567,23 @@ impl Container {
     ) -> Result<ActiveExecution, ExecuteError> {
         use execute_error::*;
 
+        ensure!(
+            request.sanitizer.is_none() || request.channel == Channel::Nightly,
+            SanitizerRequiresNightlySnafu
+        );
+        ensure!(
+            request.sanitizer.is_none() || !request.coverage,
+            CoverageWithSanitizerSnafu
+        );
+        if request.tests {
+            request
+                .test_options
+                .validate()
+                .context(InvalidTestOptionsSnafu)?;
+        }
+        let sanitizer = request.sanitizer;
+        let read_coverage = request.coverage.then(|| request.read_coverage_request());
+
         let SpawnCargo {
             permit,
             task,
@@ -1502,19 +3591,79 @@ impl Container {
             stdout_rx,
             stderr_rx,
             status_rx,
+            diagnostic_rx,
+            test_rx,
         } = self.do_request(request, token).await?;
 
+        // Test results are both streamed and collected into the response
+        let (test_tx, streamed_test_rx) = mpsc::channel(8);
+
+        let commander = self.commander.clone();
+
+        // Sanitizer reports are parsed from stderr as it is forwarded
+        let (stderr_rx, sanitized_stderr) = match sanitizer {
+            Some(_) => {
+                let (tx, rx) = mpsc::channel(8);
+                (rx, Some((stderr_rx, tx)))
+            }
+            None => (stderr_rx, None),
+        };
+
         let task = async move {
+            let diagnostics = ReceiverStream::new(diagnostic_rx).collect::<Vec<_>>();
+            let tests = ReceiverStream::new(test_rx)
+                .then(|result| {
+                    let test_tx = test_tx.clone();
+                    async move {
+                        test_tx.send(result.clone()).await.ok(/* Receiver gone, that's OK */);
+                        result
+                    }
+                })
+                .collect::<Vec<_>>();
+            let sanitizer_reports = async move {
+                let mut reports = vec![];
+
+                if let Some((mut stderr_rx, stderr_tx)) = sanitized_stderr {
+                    let mut parser = SanitizerReportParser::default();
+
+                    while let Some(packet) = stderr_rx.recv().await {
+                        reports.extend(parser.push(&packet));
+                        stderr_tx.send(packet).await.ok(/* Receiver gone, that's OK */);
+                    }
+
+                    reports.extend(parser.finish());
+                }
+
+                reports
+            };
+            let (response, diagnostics, tests, sanitizer_reports) =
+                join!(task, diagnostics, tests, sanitizer_reports);
+
             let ExecuteCommandResponse {
                 success,
                 exit_detail,
-            } = task
-                .await
+            } = response
                 .context(CargoTaskPanickedSnafu)?
                 .context(CargoFailedSnafu)?;
+
+            // Failing programs and tests still have coverage
+            let mut coverage = vec![];
+            if let Some(read_coverage) = read_coverage {
+                let report = commander
+                    .read_file(read_coverage, Commander::MAX_FILE_LEN)
+                    .await
+                    .context(CouldNotReadCoverageSnafu)?;
+                let report = String::from_utf8(report.0).context(CoverageNotUtf8Snafu)?;
+                coverage = FileCoverage::parse_lcov(&report);
+            }
+
             Ok(ExecuteResponse {
                 success,
                 exit_detail,
+                diagnostics,
+                tests,
+                sanitizer_reports,
+                coverage,
             })
         }
         .boxed();
@@ -1539,6 +3688,7 @@ impl Container {
             stdout_rx,
             stderr_rx,
             status_rx,
+            test_rx: ReceiverStream::new(streamed_test_rx).boxed(),
         })
     }
 
@@ -1566,6 +3716,27 @@ impl Container {
     ) -> Result<ActiveCompilation, CompileError> {
         use compile_error::*;
 
+        ensure!(
+            !request.target.requires_nightly() || request.channel == Channel::Nightly,
+            TargetRequiresNightlySnafu
+        );
+
+        if let Some(target_triple) = request.target_triple {
+            ensure!(
+                request.rustc_target_triple().is_some(),
+                TargetTripleNotAvailableSnafu { target_triple }
+            );
+            ensure!(
+                request.extra.features().is_empty(),
+                FeaturesWithTargetTripleSnafu { target_triple }
+            );
+        }
+
+        ensure!(
+            !request.export_artifact || request.artifact_path().is_some(),
+            ArtifactNotAvailableSnafu
+        );
+
         let SpawnCargo {
             permit,
             task,
@@ -1573,6 +3744,8 @@ impl Container {
             stdout_rx,
             stderr_rx,
             status_rx,
+            diagnostic_rx,
+            test_rx: _,
         } = self.do_request(&request, token).await?;
 
         drop(stdin_tx);
@@ -1580,11 +3753,13 @@ impl Container {
 
         let commander = self.commander.clone();
         let task = async move {
+            let diagnostics = ReceiverStream::new(diagnostic_rx).collect::<Vec<_>>();
+            let (response, diagnostics) = join!(task, diagnostics);
+
             let ExecuteCommandResponse {
                 success,
                 exit_detail,
-            } = task
-                .await
+            } = response
                 .context(CargoTaskPanickedSnafu)?
                 .context(CargoFailedSnafu)?;
 
@@ -1592,7 +3767,7 @@ impl Container {
                 let read_output = request.read_output_request();
 
                 let file: ReadFileResponse = commander
-                    .one(read_output)
+                    .read_file(read_output, Commander::MAX_FILE_LEN)
                     .await
                     .context(CouldNotReadCodeSnafu)?;
                 String::from_utf8(file.0).context(CodeNotUtf8Snafu)?
@@ -1603,10 +3778,28 @@ impl Container {
             // TODO: This is synchronous...
             let code = request.postprocess_result(code);
 
+            // The compiled code is still useful without the artifact
+            let (artifact, artifact_error) = match request.artifact_path() {
+                Some(path) if success && request.export_artifact => {
+                    let name = request.artifact_name();
+                    match Artifact::read(&commander, path, name).await {
+                        Ok(artifact) => (Some(artifact), None),
+                        Err(e) => {
+                            warn!(error = %snafu::Report::from_error(&e), "could not read the artifact");
+                            (None, Some(e.to_string()))
+                        }
+                    }
+                }
+                _ => (None, None),
+            };
+
             Ok(CompileResponse {
                 success,
                 exit_detail,
                 code,
+                diagnostics,
+                artifact,
+                artifact_error,
             })
         }
         .boxed();
@@ -1649,6 +3842,8 @@ impl Container {
             stdout_rx,
             stderr_rx,
             status_rx,
+            diagnostic_rx: _,
+            test_rx: _,
         } = self.do_request(&request, token).await?;
 
         drop(stdin_tx);
@@ -1664,27 +3859,14 @@ impl Container {
                 .context(CargoTaskPanickedSnafu)?
                 .context(CargoFailedSnafu)?;
 
-            let mut files = request
-                .read_output_requests()
-                .map(|read_output| async {
-                    let name = read_output.path.clone();
-                    let file = commander
-                        .one(read_output)
-                        .await
-                        .context(CouldNotReadCodeSnafu)?;
-                    let content = String::from_utf8(file.0).context(CodeNotUtf8Snafu)?;
-
-                    Ok::<_, FormatError>(CodeFile { name, content })
-                })
-                .collect::<FuturesUnordered<_>>()
-                .try_collect::<Vec<_>>()
-                .await?;
-
-            let code = if matches!(request.code, Code::Single(..)) {
-                Code::Single(files.pop().map(|cf| cf.content).unwrap_or_default())
-            } else {
-                Code::Multiple(files)
-            };
+            let mut code = request
+                .code
+                .read_back(&commander, request.read_output_requests())
+                .await
+                .context(CouldNotReadCodeSnafu)?;
+            if let Code::Multiple(files) = &mut code {
+                files.extend(request.config_files());
+            }
 
             Ok(FormatResponse {
                 success,
@@ -1702,6 +3884,158 @@ impl Container {
         })
     }
 
+    async fn fix(&self, request: FixRequest) -> Result<WithOutput<FixResponse>, FixError> {
+        let token = Default::default();
+
+        let ActiveFix {
+            permit: _permit,
+            task,
+            stdout_rx,
+            stderr_rx,
+        } = self.begin_fix(token, request).await?;
+
+        WithOutput::try_absorb(task, stdout_rx, stderr_rx).await
+    }
+
+    async fn begin_fix(
+        &self,
+        token: CancellationToken,
+        request: FixRequest,
+    ) -> Result<ActiveFix, FixError> {
+        use fix_error::*;
+
+        let SpawnCargo {
+            permit,
+            task,
+            stdin_tx,
+            stdout_rx,
+            stderr_rx,
+            status_rx,
+            diagnostic_rx: _,
+            test_rx: _,
+        } = self.do_request(&request, token).await?;
+
+        drop(stdin_tx);
+        drop(status_rx);
+
+        let commander = self.commander.clone();
+        let task = async move {
+            let ExecuteCommandResponse {
+                success,
+                exit_detail,
+            } = task
+                .await
+                .context(CargoTaskPanickedSnafu)?
+                .context(CargoFailedSnafu)?;
+
+            let code = request
+                .code
+                .read_back(&commander, request.read_output_requests())
+                .await
+                .context(CouldNotReadCodeSnafu)?;
+
+            Ok(FixResponse {
+                success,
+                exit_detail,
+                code,
+            })
+        }
+        .boxed();
+
+        Ok(ActiveFix {
+            permit,
+            task,
+            stdout_rx,
+            stderr_rx,
+        })
+    }
+
+    async fn migrate(
+        &self,
+        request: MigrateRequest,
+    ) -> Result<WithOutput<MigrateResponse>, MigrateError> {
+        let token = Default::default();
+
+        let ActiveMigration {
+            permit: _permit,
+            task,
+            stdout_rx,
+            stderr_rx,
+        } = self.begin_migrate(token, request).await?;
+
+        WithOutput::try_absorb(task, stdout_rx, stderr_rx).await
+    }
+
+    async fn begin_migrate(
+        &self,
+        token: CancellationToken,
+        request: MigrateRequest,
+    ) -> Result<ActiveMigration, MigrateError> {
+        use migrate_error::*;
+
+        let edition = request.edition;
+        let next_edition = edition
+            .next()
+            .context(AlreadyLatestEditionSnafu { edition })?;
+
+        let SpawnCargo {
+            permit,
+            task,
+            stdin_tx,
+            stdout_rx,
+            stderr_rx,
+            status_rx,
+            diagnostic_rx,
+            test_rx: _,
+        } = self.do_request(&request, token).await?;
+
+        drop(stdin_tx);
+        drop(status_rx);
+
+        let commander = self.commander.clone();
+        let cargo_toml = self.modify_cargo_toml.cargo_toml.clone();
+        let task = async move {
+            let lints = ReceiverStream::new(diagnostic_rx).collect::<Vec<_>>();
+            let (response, lints) = join!(task, lints);
+
+            let ExecuteCommandResponse {
+                success,
+                exit_detail,
+            } = response
+                .context(CargoTaskPanickedSnafu)?
+                .context(CargoFailedSnafu)?;
+
+            if success {
+                let cargo_toml = request.migrated_cargo_toml(cargo_toml, next_edition);
+                ModifyCargoToml::write(&commander, cargo_toml)
+                    .await
+                    .context(CouldNotUpdateEditionSnafu)?;
+            }
+
+            let code = request
+                .code
+                .read_back(&commander, request.read_output_requests())
+                .await
+                .context(CouldNotReadCodeSnafu)?;
+
+            Ok(MigrateResponse {
+                success,
+                exit_detail,
+                edition: next_edition,
+                code,
+                lints,
+            })
+        }
+        .boxed();
+
+        Ok(ActiveMigration {
+            permit,
+            task,
+            stdout_rx,
+            stderr_rx,
+        })
+    }
+
     async fn clippy(
         &self,
         request: ClippyRequest,
@@ -1732,32 +4066,207 @@ impl Container {
             stdout_rx,
             stderr_rx,
             status_rx,
+            diagnostic_rx,
+            test_rx: _,
+        } = self.do_request(request, token).await?;
+
+        drop(stdin_tx);
+        drop(status_rx);
+
+        let task = async move {
+            let diagnostics = ReceiverStream::new(diagnostic_rx).collect::<Vec<_>>();
+            let (response, diagnostics) = join!(task, diagnostics);
+
+            let ExecuteCommandResponse {
+                success,
+                exit_detail,
+            } = response
+                .context(CargoTaskPanickedSnafu)?
+                .context(CargoFailedSnafu)?;
+
+            Ok(ClippyResponse {
+                success,
+                exit_detail,
+                diagnostics,
+            })
+        }
+        .boxed();
+
+        Ok(ActiveClippy {
+            permit,
+            task,
+            stdout_rx,
+            stderr_rx,
+        })
+    }
+
+    async fn doc(&self, request: DocRequest) -> Result<WithOutput<DocResponse>, DocError> {
+        let token = Default::default();
+
+        let ActiveDoc {
+            permit: _permit,
+            task,
+            stdout_rx,
+            stderr_rx,
+        } = self.begin_doc(token, request).await?;
+
+        WithOutput::try_absorb(task, stdout_rx, stderr_rx).await
+    }
+
+    async fn begin_doc(
+        &self,
+        token: CancellationToken,
+        request: DocRequest,
+    ) -> Result<ActiveDoc, DocError> {
+        use doc_error::*;
+
+        let SpawnCargo {
+            permit,
+            task,
+            stdin_tx,
+            stdout_rx,
+            stderr_rx,
+            status_rx,
+            diagnostic_rx: _,
+            test_rx,
+        } = self.do_request(&request, token).await?;
+
+        drop(stdin_tx);
+        drop(status_rx);
+
+        let commander = self.commander.clone();
+        let task = async move {
+            let tests = ReceiverStream::new(test_rx).collect::<Vec<_>>();
+            let (response, tests) = join!(task, tests);
+
+            let ExecuteCommandResponse {
+                success,
+                exit_detail,
+            } = response
+                .context(CargoTaskPanickedSnafu)?
+                .context(CargoFailedSnafu)?;
+
+            let mut files = vec![];
+            if success && request.mode == DocMode::Build {
+                let manifest = commander
+                    .read_file(request.read_manifest_request(), Commander::MAX_FILE_LEN)
+                    .await
+                    .context(CouldNotReadManifestSnafu)?;
+                let manifest = String::from_utf8(manifest.0).context(FileNotUtf8Snafu)?;
+
+                files = manifest
+                    .lines()
+                    .map(str::to_owned)
+                    .map(|path| async {
+                        let read = ReadFileRequest {
+                            path: format!("{}/{path}", DocRequest::DOC_DIR),
+                        };
+                        let file = commander
+                            .read_file(read, Commander::MAX_FILE_LEN)
+                            .await
+                            .context(CouldNotReadFileSnafu)?;
+                        let content = String::from_utf8(file.0).context(FileNotUtf8Snafu)?;
+
+                        Ok::<_, DocError>(DocFile { path, content })
+                    })
+                    .collect::<FuturesUnordered<_>>()
+                    .try_collect::<Vec<_>>()
+                    .await?;
+
+                files.sort_by(|a, b| a.path.cmp(&b.path));
+            }
+
+            Ok(DocResponse {
+                success,
+                exit_detail,
+                files,
+                tests,
+            })
+        }
+        .boxed();
+
+        Ok(ActiveDoc {
+            permit,
+            task,
+            stdout_rx,
+            stderr_rx,
+        })
+    }
+
+    async fn bench(&self, request: BenchRequest) -> Result<WithOutput<BenchResponse>, BenchError> {
+        let token = Default::default();
+
+        let ActiveBench {
+            permit: _permit,
+            task,
+            stdout_rx,
+            stderr_rx,
+            bench_rx: _,
+        } = self.begin_bench(token, request).await?;
+
+        WithOutput::try_absorb(task, stdout_rx, stderr_rx).await
+    }
+
+    async fn begin_bench(
+        &self,
+        token: CancellationToken,
+        request: BenchRequest,
+    ) -> Result<ActiveBench, BenchError> {
+        use bench_error::*;
+
+        request.validate().context(InvalidRequestSnafu)?;
+
+        let SpawnCargo {
+            permit,
+            task,
+            stdin_tx,
+            stdout_rx,
+            stderr_rx,
+            status_rx,
+            diagnostic_rx: _,
+            test_rx,
         } = self.do_request(request, token).await?;
 
         drop(stdin_tx);
         drop(status_rx);
 
-        let task = async move {
+        // Benchmark results are both streamed and collected into the response
+        let (bench_tx, streamed_bench_rx) = mpsc::channel(8);
+
+        let task = async move {
+            let benchmarks = ReceiverStream::new(test_rx)
+                .filter_map(|result| async { BenchResult::from_test_result(result) })
+                .then(|result| {
+                    let bench_tx = bench_tx.clone();
+                    async move {
+                        bench_tx.send(result.clone()).await.ok(/* Receiver gone, that's OK */);
+                        result
+                    }
+                })
+                .collect::<Vec<_>>();
+            let (response, benchmarks) = join!(task, benchmarks);
+
             let ExecuteCommandResponse {
                 success,
                 exit_detail,
-            } = task
-                .await
+            } = response
                 .context(CargoTaskPanickedSnafu)?
                 .context(CargoFailedSnafu)?;
 
-            Ok(ClippyResponse {
+            Ok(BenchResponse {
                 success,
                 exit_detail,
+                benchmarks,
             })
         }
         .boxed();
 
-        Ok(ActiveClippy {
+        Ok(ActiveBench {
             permit,
             task,
             stdout_rx,
             stderr_rx,
+            bench_rx: ReceiverStream::new(streamed_bench_rx).boxed(),
         })
     }
 
@@ -1781,6 +4290,8 @@ impl Container {
     ) -> Result<ActiveMiri, MiriError> {
         use miri_error::*;
 
+        request.options.validate().context(InvalidOptionsSnafu)?;
+
         let SpawnCargo {
             permit,
             task,
@@ -1788,6 +4299,8 @@ impl Container {
             stdout_rx,
             stderr_rx,
             status_rx,
+            diagnostic_rx: _,
+            test_rx: _,
         } = self.do_request(request, token).await?;
 
         drop(stdin_tx);
@@ -1847,6 +4360,8 @@ impl Container {
             stdout_rx,
             stderr_rx,
             status_rx,
+            diagnostic_rx: _,
+            test_rx: _,
         } = self.do_request(request, token).await?;
 
         drop(stdin_tx);
@@ -1899,7 +4414,7 @@ impl Container {
             .write_files()
             .map(|req| async {
                 self.commander
-                    .one(req)
+                    .write_file(req)
                     .await
                     .context(CouldNotWriteCodeSnafu)
                     .map(drop::<crate::message::WriteFileResponse>)
@@ -1917,7 +4432,13 @@ impl Container {
         let (d, w, m) = try_join!(delete_files, write_files, modify_cargo_toml)?;
         let _: [(); 3] = [d, w, m];
 
-        let execute_cargo = request.execute_cargo_request();
+        let execute_cargo = ExecuteCommandRequest {
+            output_limit: Some(OutputLimit {
+                bytes: self.output_bytes,
+                kill: request.kill_on_output_limit(),
+            }),
+            ..request.execute_cargo_request()
+        };
         self.spawn_cargo_task(token, execute_cargo)
             .await
             .context(CouldNotStartCargoSnafu)
@@ -1930,6 +4451,14 @@ impl Container {
     ) -> Result<SpawnCargo, SpawnCargoError> {
         use spawn_cargo_error::*;
 
+        let execute_cargo = ExecuteCommandRequest {
+            deadline: [execute_cargo.deadline, self.process_timeout]
+                .into_iter()
+                .flatten()
+                .min(),
+            ..execute_cargo
+        };
+
         let permit = self
             .permit
             .next_process()
@@ -1942,6 +4471,18 @@ impl Container {
         let (stdout_tx, stdout_rx) = mpsc::channel(8);
         let (stderr_tx, stderr_rx) = mpsc::channel(8);
         let (status_tx, status_rx) = mpsc::channel(8);
+        let (diagnostic_tx, diagnostic_rx) = mpsc::channel(8);
+        let (test_tx, test_rx) = mpsc::channel(8);
+
+        let mut messages = execute_cargo
+            .args
+            .iter()
+            .any(|a| a == JSON_MESSAGE_FORMAT)
+            .then(CargoMessageFilter::default);
+
+        let mut tests = (execute_cargo.cmd == "cargo"
+            && cargo_subcommand(&execute_cargo.args).is_some_and(|a| a == "test" || a == "bench"))
+        .then(TestOutputParser::default);
 
         let (to_worker_tx, mut from_worker_rx) = self
             .commander
@@ -2001,11 +4542,48 @@ impl Container {
 
                             match container_msg {
                                 WorkerMessage::ExecuteCommand(resp) => {
+                                    if let Some(rest) = messages.as_mut().and_then(CargoMessageFilter::finish) {
+                                        if let Some(tests) = &mut tests {
+                                            for result in tests.push(&rest) {
+                                                test_tx.send(result).await.ok(/* Receiver gone, that's OK */);
+                                            }
+                                        }
+                                        stdout_tx.send(rest).await.ok(/* Receiver gone, that's OK */);
+                                    }
+
+                                    if let Some(tests) = &mut tests {
+                                        for result in tests.finish() {
+                                            test_tx.send(result).await.ok(/* Receiver gone, that's OK */);
+                                        }
+                                    }
+
                                     return Ok(resp);
                                 }
 
                                 WorkerMessage::StdoutPacket(packet) => {
-                                    stdout_tx.send(packet).await.ok(/* Receiver gone, that's OK */);
+                                    let outputs = match &mut messages {
+                                        Some(messages) => messages.push(packet),
+                                        None => vec![CargoOutput::Stdout(packet)],
+                                    };
+
+                                    for output in outputs {
+                                        match output {
+                                            CargoOutput::Stdout(packet) => {
+                                                if let Some(tests) = &mut tests {
+                                                    for result in tests.push(&packet) {
+                                                        test_tx.send(result).await.ok(/* Receiver gone, that's OK */);
+                                                    }
+                                                }
+                                                stdout_tx.send(packet).await.ok(/* Receiver gone, that's OK */);
+                                            }
+                                            CargoOutput::Stderr(packet) => {
+                                                stderr_tx.send(packet).await.ok(/* Receiver gone, that's OK */);
+                                            }
+                                            CargoOutput::Diagnostic(diagnostic) => {
+                                                diagnostic_tx.send(diagnostic).await.ok(/* Receiver gone, that's OK */);
+                                            }
+                                        }
+                                    }
                                 }
 
                                 WorkerMessage::StderrPacket(packet) => {
@@ -2016,6 +4594,12 @@ impl Container {
                                     status_tx.send(stats).await.ok(/* Receiver gone, that's OK */);
                                 }
 
+                                WorkerMessage::OutputTruncated(truncated) => {
+                                    let bytes = truncated.output_limit.bytes;
+                                    let notice = format!("\nOutput truncated after {bytes} bytes. If this was not an accident, tell us more at {BYTE_LIMIT_URL}\n");
+                                    stderr_tx.send(notice).await.ok(/* Receiver gone, that's OK */);
+                                }
+
                                 WorkerMessage::Error(e) => {
                                     return Err(SerializedError2::adapt(e)).context(WorkerSnafu);
                                 }
@@ -2042,6 +4626,8 @@ impl Container {
             stdout_rx,
             stderr_rx,
             status_rx,
+            diagnostic_rx,
+            test_rx,
         })
     }
 
@@ -2052,6 +4638,8 @@ impl Container {
             mut kill_child,
             modify_cargo_toml,
             commander,
+            process_timeout: _,
+            output_bytes: _,
         } = self;
         drop(commander);
         drop(modify_cargo_toml);
@@ -2072,6 +4660,7 @@ pub struct ActiveExecution {
     pub stdout_rx: mpsc::Receiver<String>,
     pub stderr_rx: mpsc::Receiver<String>,
     pub status_rx: BoxStream<'static, ExecuteStatus>,
+    pub test_rx: BoxStream<'static, TestResult>,
 }
 
 impl fmt::Debug for ActiveExecution {
@@ -2088,6 +4677,15 @@ impl fmt::Debug for ActiveExecution {
 #[derive(Debug, Snafu)]
 #[snafu(module)]
 pub enum ExecuteError {
+    #[snafu(display("Sanitizers are only available on the nightly channel"))]
+    SanitizerRequiresNightly,
+
+    #[snafu(display("Coverage cannot be collected while using a sanitizer"))]
+    CoverageWithSanitizer,
+
+    #[snafu(display("The test options are not valid"))]
+    InvalidTestOptions { source: TestFilterError },
+
     #[snafu(display("Could not start the container"))]
     CouldNotStartContainer { source: Error },
 
@@ -2099,6 +4697,12 @@ pub enum ExecuteError {
 
     #[snafu(display("Cargo task failed"))]
     CargoFailed { source: SpawnCargoError },
+
+    #[snafu(display("Could not read the coverage report"))]
+    CouldNotReadCoverage { source: CommanderError },
+
+    #[snafu(display("The coverage report was not UTF-8"))]
+    CoverageNotUtf8 { source: std::string::FromUtf8Error },
 }
 
 pub struct ActiveCompilation {
@@ -2138,6 +4742,18 @@ pub enum CompileError {
 
     #[snafu(display("The compilation output was not UTF-8"))]
     CodeNotUtf8 { source: std::string::FromUtf8Error },
+
+    #[snafu(display("This compilation target does not produce an artifact"))]
+    ArtifactNotAvailable,
+
+    #[snafu(display("This compilation target is not available for {}", target_triple.to_str()))]
+    TargetTripleNotAvailable { target_triple: TargetTriple },
+
+    #[snafu(display("Crate features cannot be used when compiling for {}", target_triple.to_str()))]
+    FeaturesWithTargetTriple { target_triple: TargetTriple },
+
+    #[snafu(display("This compilation target is only available on the nightly channel"))]
+    TargetRequiresNightly,
 }
 
 pub struct ActiveFormatting {
@@ -2172,11 +4788,86 @@ pub enum FormatError {
     #[snafu(display("Cargo task failed"))]
     CargoFailed { source: SpawnCargoError },
 
-    #[snafu(display("Could not read the compilation output"))]
-    CouldNotReadCode { source: CommanderError },
+    #[snafu(display("Could not read the formatted code"))]
+    CouldNotReadCode { source: ReadCodeError },
+}
 
-    #[snafu(display("The compilation output was not UTF-8"))]
-    CodeNotUtf8 { source: std::string::FromUtf8Error },
+pub struct ActiveMigration {
+    pub permit: Box<dyn ProcessPermit>,
+    pub task: BoxFuture<'static, Result<MigrateResponse, MigrateError>>,
+    pub stdout_rx: mpsc::Receiver<String>,
+    pub stderr_rx: mpsc::Receiver<String>,
+}
+
+impl fmt::Debug for ActiveMigration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ActiveMigration")
+            .field("task", &"<future>")
+            .field("stdout_rx", &self.stdout_rx)
+            .field("stderr_rx", &self.stderr_rx)
+            .finish()
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+pub enum MigrateError {
+    #[snafu(display("Could not start the container"))]
+    CouldNotStartContainer { source: Error },
+
+    #[snafu(display("The {edition:?} edition is already the latest edition"))]
+    AlreadyLatestEdition { edition: Edition },
+
+    #[snafu(transparent)]
+    DoRequest { source: DoRequestError },
+
+    #[snafu(display("The Cargo task panicked"))]
+    CargoTaskPanicked { source: tokio::task::JoinError },
+
+    #[snafu(display("Cargo task failed"))]
+    CargoFailed { source: SpawnCargoError },
+
+    #[snafu(display("Could not read the migrated code"))]
+    CouldNotReadCode { source: ReadCodeError },
+
+    #[snafu(display("Could not update the edition in Cargo.toml"))]
+    CouldNotUpdateEdition { source: ModifyCargoTomlError },
+}
+
+pub struct ActiveFix {
+    pub permit: Box<dyn ProcessPermit>,
+    pub task: BoxFuture<'static, Result<FixResponse, FixError>>,
+    pub stdout_rx: mpsc::Receiver<String>,
+    pub stderr_rx: mpsc::Receiver<String>,
+}
+
+impl fmt::Debug for ActiveFix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ActiveFix")
+            .field("task", &"<future>")
+            .field("stdout_rx", &self.stdout_rx)
+            .field("stderr_rx", &self.stderr_rx)
+            .finish()
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+pub enum FixError {
+    #[snafu(display("Could not start the container"))]
+    CouldNotStartContainer { source: Error },
+
+    #[snafu(transparent)]
+    DoRequest { source: DoRequestError },
+
+    #[snafu(display("The Cargo task panicked"))]
+    CargoTaskPanicked { source: tokio::task::JoinError },
+
+    #[snafu(display("Cargo task failed"))]
+    CargoFailed { source: SpawnCargoError },
+
+    #[snafu(display("Could not read the fixed code"))]
+    CouldNotReadCode { source: ReadCodeError },
 }
 
 pub struct ActiveClippy {
@@ -2212,6 +4903,85 @@ pub enum ClippyError {
     CargoFailed { source: SpawnCargoError },
 }
 
+pub struct ActiveDoc {
+    pub permit: Box<dyn ProcessPermit>,
+    pub task: BoxFuture<'static, Result<DocResponse, DocError>>,
+    pub stdout_rx: mpsc::Receiver<String>,
+    pub stderr_rx: mpsc::Receiver<String>,
+}
+
+impl fmt::Debug for ActiveDoc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ActiveDoc")
+            .field("task", &"<future>")
+            .field("stdout_rx", &self.stdout_rx)
+            .field("stderr_rx", &self.stderr_rx)
+            .finish()
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+pub enum DocError {
+    #[snafu(display("Could not start the container"))]
+    CouldNotStartContainer { source: Error },
+
+    #[snafu(transparent)]
+    DoRequest { source: DoRequestError },
+
+    #[snafu(display("The Cargo task panicked"))]
+    CargoTaskPanicked { source: tokio::task::JoinError },
+
+    #[snafu(display("Cargo task failed"))]
+    CargoFailed { source: SpawnCargoError },
+
+    #[snafu(display("Could not read the list of documentation files"))]
+    CouldNotReadManifest { source: CommanderError },
+
+    #[snafu(display("Could not read a documentation file"))]
+    CouldNotReadFile { source: CommanderError },
+
+    #[snafu(display("A documentation file was not UTF-8"))]
+    FileNotUtf8 { source: std::string::FromUtf8Error },
+}
+
+pub struct ActiveBench {
+    pub permit: Box<dyn ProcessPermit>,
+    pub task: BoxFuture<'static, Result<BenchResponse, BenchError>>,
+    pub stdout_rx: mpsc::Receiver<String>,
+    pub stderr_rx: mpsc::Receiver<String>,
+    pub bench_rx: BoxStream<'static, BenchResult>,
+}
+
+impl fmt::Debug for ActiveBench {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ActiveBench")
+            .field("task", &"<future>")
+            .field("stdout_rx", &self.stdout_rx)
+            .field("stderr_rx", &self.stderr_rx)
+            .finish()
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+pub enum BenchError {
+    #[snafu(display("The benchmark request is not valid"))]
+    InvalidRequest { source: BenchRequestError },
+
+    #[snafu(display("Could not start the container"))]
+    CouldNotStartContainer { source: Error },
+
+    #[snafu(transparent)]
+    DoRequest { source: DoRequestError },
+
+    #[snafu(display("The Cargo task panicked"))]
+    CargoTaskPanicked { source: tokio::task::JoinError },
+
+    #[snafu(display("Cargo task failed"))]
+    CargoFailed { source: SpawnCargoError },
+}
+
 pub struct ActiveMiri {
     pub permit: Box<dyn ProcessPermit>,
     pub task: BoxFuture<'static, Result<MiriResponse, MiriError>>,
@@ -2232,6 +5002,9 @@ impl fmt::Debug for ActiveMiri {
 #[derive(Debug, Snafu)]
 #[snafu(module)]
 pub enum MiriError {
+    #[snafu(display("The Miri options are not valid"))]
+    InvalidOptions { source: MiriOptionsError },
+
     #[snafu(display("Could not start the container"))]
     CouldNotStartContainer { source: Error },
 
@@ -2333,15 +5106,304 @@ impl<T> CancelOnDropFutureExt for T {
     {
         CancelOnDropFuture { token, fut: self }
     }
-}
+}
+
+const BYTE_LIMIT_URL: &str = "https://github.com/rust-lang/rust-playground/discussions/1027";
+
+struct SpawnCargo {
+    permit: Box<dyn ProcessPermit>,
+    task: CancelOnDropFuture<JoinHandle<Result<ExecuteCommandResponse, SpawnCargoError>>>,
+    stdin_tx: mpsc::Sender<String>,
+    stdout_rx: mpsc::Receiver<String>,
+    stderr_rx: mpsc::Receiver<String>,
+    status_rx: mpsc::Receiver<CommandStatistics>,
+    diagnostic_rx: mpsc::Receiver<Diagnostic>,
+    test_rx: mpsc::Receiver<TestResult>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "reason", rename_all = "kebab-case")]
+enum CargoMessage {
+    CompilerMessage {
+        message: Diagnostic,
+    },
+    BuildFinished,
+    #[serde(other)]
+    Other,
+}
+
+enum CargoOutput {
+    Stdout(String),
+    Stderr(String),
+    Diagnostic(Diagnostic),
+}
+
+/// Separates Cargo's JSON messages from the rest of stdout. Cargo
+/// stops emitting messages once the build has finished, so anything
+/// after that (such as the output of `cargo run`) is passed through.
+/// Before then, only lines that are not JSON are passed through; JSON
+/// messages that cannot be parsed are dropped.
+#[derive(Debug, Default)]
+struct CargoMessageFilter {
+    buffer: String,
+    build_finished: bool,
+}
+
+impl CargoMessageFilter {
+    fn push(&mut self, packet: String) -> Vec<CargoOutput> {
+        use CargoOutput::*;
+
+        if self.build_finished {
+            return vec![Stdout(packet)];
+        }
+
+        self.buffer.push_str(&packet);
+
+        let mut output = vec![];
+        while let Some(idx) = self.buffer.find('\n') {
+            let line = self.buffer.drain(..=idx).collect::<String>();
+
+            if self.build_finished {
+                output.push(Stdout(line));
+                continue;
+            }
+
+            match serde_json::from_str(&line) {
+                Ok(CargoMessage::CompilerMessage { message }) => {
+                    // Cargo no longer prints the diagnostic itself
+                    if let Some(rendered) = &message.rendered {
+                        output.push(Stderr(rendered.clone()));
+                    }
+                    output.push(Diagnostic(message));
+                }
+                Ok(CargoMessage::BuildFinished) => self.build_finished = true,
+                Ok(CargoMessage::Other) => {}
+                Err(e) if serde_json::from_str::<IgnoredAny>(&line).is_ok() => {
+                    warn!(error = %e, "could not parse a Cargo message");
+                }
+                Err(_) => output.push(Stdout(line)),
+            }
+        }
+
+        if self.build_finished {
+            output.extend(self.finish().map(Stdout));
+        }
+
+        output
+    }
+
+    fn finish(&mut self) -> Option<String> {
+        Some(mem::take(&mut self.buffer)).filter(|b| !b.is_empty())
+    }
+}
+
+/// The Cargo subcommand, looking through `cargo-sanitize` and
+/// `cargo-coverage`.
+fn cargo_subcommand(args: &[String]) -> Option<&str> {
+    match args {
+        [sanitize, _, args @ ..] if sanitize == "sanitize" => args.first(),
+        [coverage, args @ ..] if coverage == "coverage" => args.first(),
+        args => args.first(),
+    }
+    .map(String::as_str)
+}
+
+/// Finds sanitizer reports in stderr.
+///
+/// A report starts with a line such as `==1==ERROR: AddressSanitizer:
+/// heap-use-after-free on address ...` and ends with a `SUMMARY:`
+/// line.
+#[derive(Debug, Default)]
+struct SanitizerReportParser {
+    buffer: String,
+    current: Option<SanitizerReport>,
+}
+
+impl SanitizerReportParser {
+    fn push(&mut self, packet: &str) -> Vec<SanitizerReport> {
+        self.buffer.push_str(packet);
+
+        let mut reports = vec![];
+        while let Some(idx) = self.buffer.find('\n') {
+            let line = self.buffer.drain(..=idx).collect::<String>();
+            self.line(line.trim_end_matches(['\r', '\n']), &mut reports);
+        }
+
+        reports
+    }
+
+    fn finish(&mut self) -> Vec<SanitizerReport> {
+        let rest = mem::take(&mut self.buffer);
+
+        let mut reports = vec![];
+        self.line(&rest, &mut reports);
+        reports.extend(self.current.take());
+        reports
+    }
+
+    fn line(&mut self, line: &str, reports: &mut Vec<SanitizerReport>) {
+        if let Some(summary) = line.strip_prefix("SUMMARY: ") {
+            let summary = summary
+                .split_once(": ")
+                .map_or(summary, |(_, summary)| summary);
+
+            if let Some(mut report) = self.current.take() {
+                report.summary = Some(summary.to_owned());
+                reports.push(report);
+            }
+        } else if let Some(report) = Self::parse_headline(line) {
+            reports.extend(self.current.replace(report));
+        }
+    }
+
+    fn parse_headline(line: &str) -> Option<SanitizerReport> {
+        let (_, rest) = line
+            .split_once("ERROR: ")
+            .or_else(|| line.split_once("WARNING: "))?;
+        let (name, description) = rest.split_once(": ")?;
+        let sanitizer = Sanitizer::from_report_name(name)?;
+
+        // Drop details such as the address or process ID
+        let kind = [" on ", " ("]
+            .into_iter()
+            .filter_map(|sep| description.find(sep))
+            .min()
+            .map_or(description, |idx| &description[..idx]);
+
+        Some(SanitizerReport {
+            sanitizer,
+            kind: kind.trim().to_owned(),
+            summary: None,
+        })
+    }
+}
+
+/// Parses the results out of libtest's human-readable output.
+///
+/// Passing and ignored tests are reported as soon as they finish. A
+/// failing test is reported once its captured output has been printed
+/// or when the test binary has finished.
+#[derive(Debug, Default)]
+struct TestOutputParser {
+    buffer: String,
+    failed: Vec<TestResult>,
+    capturing: Option<(String, String)>,
+}
+
+impl TestOutputParser {
+    fn push(&mut self, packet: &str) -> Vec<TestResult> {
+        self.buffer.push_str(packet);
+
+        let mut results = vec![];
+        while let Some(idx) = self.buffer.find('\n') {
+            let line = self.buffer.drain(..=idx).collect::<String>();
+            self.line(line.trim_end_matches(['\r', '\n']), &mut results);
+        }
+
+        results
+    }
+
+    fn line(&mut self, line: &str, results: &mut Vec<TestResult>) {
+        let header = line
+            .strip_prefix("---- ")
+            .and_then(|l| l.strip_suffix(" stdout ----"));
+
+        if let Some((_, output)) = &mut self.capturing {
+            if header.is_none() && !matches!(line, "failures:" | "successes:") {
+                output.push_str(line);
+                output.push('\n');
+                return;
+            }
+
+            self.end_capture(results);
+        }
+
+        if let Some(name) = header {
+            self.capturing = Some((name.to_owned(), String::new()));
+        } else if line.starts_with("test result: ") {
+            results.append(&mut self.failed);
+        } else if let Some(result) = Self::parse_result(line) {
+            match result.outcome {
+                TestOutcome::Failed => self.failed.push(result),
+                _ => results.push(result),
+            }
+        }
+    }
+
+    fn parse_result(line: &str) -> Option<TestResult> {
+        let (name, status) = line.strip_prefix("test ")?.split_once(" ... ")?;
+
+        let (status, duration) = match status.rsplit_once(" <") {
+            Some((status, time)) => {
+                let secs = time.strip_suffix("s>")?.parse().ok()?;
+                (status, Some(Duration::from_secs_f64(secs)))
+            }
+            None => (status, None),
+        };
+
+        let outcome = match status {
+            "ok" => TestOutcome::Passed,
+            "FAILED" => TestOutcome::Failed,
+            s if s == "ignored" || s.starts_with("ignored, ") => TestOutcome::Ignored,
+            s => TestOutcome::Benchmarked(Self::parse_bench_stats(s)?),
+        };
+
+        Some(TestResult {
+            // Benchmark names are padded so that the results line up
+            name: name.trim_end().to_owned(),
+            outcome,
+            duration,
+            output: String::new(),
+        })
+    }
+
+    /// Parses `bench: 1,234.56 ns/iter (+/- 7.89) = 100 MB/s`
+    fn parse_bench_stats(status: &str) -> Option<BenchStats> {
+        fn nanoseconds(s: &str) -> Option<Duration> {
+            let ns = s.replace(',', "").parse::<f64>().ok()?;
+            Some(Duration::from_secs_f64(ns / 1e9))
+        }
+
+        let status = status.strip_prefix("bench:")?.trim_start();
+        let (median, rest) = status.split_once(" ns/iter (+/- ")?;
+        let (spread, rest) = rest.split_once(')')?;
+
+        let megabytes_per_second = match rest.trim().strip_prefix("= ") {
+            Some(throughput) => Some(throughput.strip_suffix(" MB/s")?.parse().ok()?),
+            None => None,
+        };
+
+        Some(BenchStats {
+            median: nanoseconds(median.trim())?,
+            spread: nanoseconds(spread)?,
+            megabytes_per_second,
+        })
+    }
+
+    fn end_capture(&mut self, results: &mut Vec<TestResult>) {
+        let Some((name, output)) = self.capturing.take() else {
+            return;
+        };
+
+        if let Some(idx) = self.failed.iter().position(|r| r.name == name) {
+            let mut result = self.failed.remove(idx);
+            result.output = output.trim_end().to_owned();
+            results.push(result);
+        }
+    }
 
-struct SpawnCargo {
-    permit: Box<dyn ProcessPermit>,
-    task: CancelOnDropFuture<JoinHandle<Result<ExecuteCommandResponse, SpawnCargoError>>>,
-    stdin_tx: mpsc::Sender<String>,
-    stdout_rx: mpsc::Receiver<String>,
-    stderr_rx: mpsc::Receiver<String>,
-    status_rx: mpsc::Receiver<CommandStatistics>,
+    fn finish(&mut self) -> Vec<TestResult> {
+        let mut results = vec![];
+
+        let rest = mem::take(&mut self.buffer);
+        if !rest.is_empty() {
+            self.line(&rest, &mut results);
+        }
+
+        self.end_capture(&mut results);
+        results.append(&mut self.failed);
+        results
+    }
 }
 
 #[derive(Debug, Snafu)]
@@ -2374,6 +5436,8 @@ struct Commander {
     to_worker_tx: mpsc::Sender<Multiplexed<CoordinatorMessage>>,
     to_demultiplexer_tx: mpsc::Sender<(oneshot::Sender<()>, DemultiplexCommand)>,
     id: Arc<AtomicU64>,
+    /// `None` until the handshake has completed.
+    capabilities: Option<Arc<Capabilities>>,
 }
 
 /// The set of files returned by `delete_files` should have no overlap
@@ -2384,6 +5448,13 @@ trait LowerRequest {
     fn write_files(&self) -> impl Iterator<Item = WriteFileRequest>;
 
     fn execute_cargo_request(&self) -> ExecuteCommandRequest;
+
+    /// Requests that run the user's code stop it once it has
+    /// produced too much output. Builds are left to finish so that
+    /// their result is still available.
+    fn kill_on_output_limit(&self) -> bool {
+        true
+    }
 }
 
 impl<S> LowerRequest for &S
@@ -2401,6 +5472,10 @@ where
     fn execute_cargo_request(&self) -> ExecuteCommandRequest {
         S::execute_cargo_request(self)
     }
+
+    fn kill_on_output_limit(&self) -> bool {
+        S::kill_on_output_limit(self)
+    }
 }
 
 trait CargoTomlModifier {
@@ -2447,7 +5522,7 @@ impl ModifyCargoToml {
 
         let path = Self::PATH.to_owned();
         let cargo_toml = commander
-            .one(ReadFileRequest { path })
+            .read_file(ReadFileRequest { path }, Commander::MAX_FILE_LEN)
             .await
             .context(CouldNotReadSnafu)?;
 
@@ -2468,7 +5543,7 @@ impl ModifyCargoToml {
 
         let path = Self::PATH.to_owned();
         commander
-            .one(WriteFileRequest { path, content })
+            .write_file(WriteFileRequest { path, content })
             .await
             .context(CouldNotWriteSnafu)?;
 
@@ -2530,6 +5605,11 @@ pub struct MultiplexedSenderError {
 
 impl Commander {
     const GC_PERIOD: Duration = Duration::from_secs(30);
+    const CHUNKS_IN_FLIGHT: usize = 4;
+
+    /// Generous enough for any source code or generated output, such
+    /// as LLVM IR or documentation.
+    const MAX_FILE_LEN: u64 = 64 * 1024 * 1024;
 
     #[instrument(skip_all)]
     async fn demultiplex(
@@ -2632,6 +5712,21 @@ impl Commander {
         ack_rx.await.context(DemultiplexerDidNotRespondSnafu)
     }
 
+    /// Messages added after [`MIN_PROTOCOL_VERSION`][] may not be
+    /// handled by the worker.
+    fn supports(&self, kind: &str) -> bool {
+        self.capabilities.as_ref().is_none_or(|c| c.supports(kind))
+    }
+
+    fn ensure_supported(&self, message: &CoordinatorMessage) -> Result<(), CommanderError> {
+        let kind = message.as_ref();
+        ensure!(
+            self.supports(kind),
+            commander_error::UnsupportedMessageSnafu { kind }
+        );
+        Ok(())
+    }
+
     fn build_multiplexed_sender(&self, job_id: JobId) -> MultiplexedSender {
         let to_worker_tx = self.to_worker_tx.clone();
         MultiplexedSender {
@@ -2648,6 +5743,9 @@ impl Commander {
     {
         use commander_error::*;
 
+        let message = message.into();
+        self.ensure_supported(&message)?;
+
         let id = self.next_id();
         let to_worker_tx = self.build_multiplexed_sender(id);
         let (from_demultiplexer_tx, from_demultiplexer_rx) = oneshot::channel();
@@ -2668,6 +5766,107 @@ impl Commander {
         }
     }
 
+    /// Reads the file a chunk at a time so that messages for other
+    /// jobs are not stuck behind it. Once the file is known to be
+    /// larger than one chunk, up to [`Self::CHUNKS_IN_FLIGHT`][]
+    /// chunks are requested at once. Fails once more than `max_len`
+    /// bytes have been read.
+    async fn read_file(
+        &self,
+        req: ReadFileRequest,
+        max_len: u64,
+    ) -> Result<ReadFileResponse, CommanderError> {
+        use commander_error::*;
+
+        if !self.supports("ReadFileChunk") {
+            let path = req.path.clone();
+            let file = self.one(req).await?;
+            ensure!(
+                file.0.len() as u64 <= max_len,
+                FileTooLargeSnafu { path, max_len }
+            );
+            return Ok(file);
+        }
+
+        let ReadFileRequest { path } = req;
+
+        let mut offsets = (0..).map(|i| i * u64::from(MAX_CHUNK_LEN));
+        let read_chunk = |offset| {
+            self.one(ReadFileChunkRequest {
+                path: path.clone(),
+                offset,
+                len: MAX_CHUNK_LEN,
+            })
+        };
+
+        let mut pending = FuturesOrdered::new();
+        pending.extend(offsets.next().map(read_chunk));
+
+        let mut data = Vec::new();
+        while let Some(chunk) = pending.next().await {
+            let chunk = chunk?;
+            data.extend(chunk.data);
+            ensure!(
+                data.len() as u64 <= max_len,
+                FileTooLargeSnafu { path, max_len }
+            );
+
+            if chunk.last {
+                break;
+            }
+
+            let refill = Self::CHUNKS_IN_FLIGHT - pending.len();
+            pending.extend(offsets.by_ref().take(refill).map(read_chunk));
+        }
+
+        Ok(ReadFileResponse(data))
+    }
+
+    /// Writes files larger than one chunk a chunk at a time so that
+    /// messages for other jobs are not stuck behind it. Up to
+    /// [`Self::CHUNKS_IN_FLIGHT`][] chunks are sent at once.
+    async fn write_file(&self, req: WriteFileRequest) -> Result<WriteFileResponse, CommanderError> {
+        let chunk_len = MAX_CHUNK_LEN as usize;
+
+        if req.content.len() <= chunk_len || !self.supports("WriteFileChunk") {
+            return self.one(req).await;
+        }
+
+        let WriteFileRequest { path, content } = req;
+
+        let n_chunks = content.len().div_ceil(chunk_len);
+        let mut chunks =
+            content
+                .chunks(chunk_len)
+                .enumerate()
+                .map(|(i, data)| WriteFileChunkRequest {
+                    path: path.clone(),
+                    offset: (i * chunk_len) as u64,
+                    data: data.to_vec(),
+                    last: i + 1 == n_chunks,
+                });
+        let (first, last) = match (chunks.next(), chunks.next_back()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => unreachable!("The content is larger than one chunk"),
+        };
+
+        // The first chunk creates the file and the last puts it in place
+        self.one(first).await?;
+
+        let mut pending = FuturesUnordered::new();
+        for chunk in chunks {
+            if pending.len() >= Self::CHUNKS_IN_FLIGHT {
+                pending.try_next().await?;
+            }
+            pending.push(self.one(chunk));
+        }
+        while pending.try_next().await?.is_some() {}
+
+        self.one(last).await?;
+
+        Ok(WriteFileResponse(()))
+    }
+
     async fn many<M>(
         &self,
         message: M,
@@ -2677,6 +5876,9 @@ impl Commander {
     {
         use commander_error::*;
 
+        let message = message.into();
+        self.ensure_supported(&message)?;
+
         let id = self.next_id();
         let to_worker_tx = self.build_multiplexed_sender(id);
         let (from_worker_tx, from_worker_rx) = mpsc::channel(8);
@@ -2713,6 +5915,14 @@ pub enum CommanderError {
     #[snafu(display("Could not start continuous interaction"))]
     UnableToStartMany { source: MultiplexedSenderError },
 
+    #[snafu(display(
+        "The worker does not handle {kind} messages; it may be from an older container image"
+    ))]
+    UnsupportedMessage { kind: String },
+
+    #[snafu(display("The file `{path}` is larger than {max_len} bytes"))]
+    FileTooLarge { path: String, max_len: u64 },
+
     #[snafu(display("Did not receive the expected response type from the worker"))]
     UnexpectedResponseType,
 
@@ -2816,19 +6026,26 @@ impl Drop for TerminateContainer {
 
 #[derive(Debug, Snafu)]
 #[snafu(module)]
-#[snafu(display("Unable to kill the Docker container {name}"))]
+#[snafu(display("Unable to kill the container {name}"))]
 pub struct TerminateContainerError {
     name: String,
     source: std::io::Error,
 }
 
 pub trait Backend {
+    /// Runs before each worker is started, for setup that would
+    /// otherwise block the async runtime.
+    fn prepare_worker(&self, _channel: Channel) -> BoxFuture<'static, Result<()>> {
+        async { Ok(()) }.boxed()
+    }
+
     fn run_worker_in_background(
         &self,
         channel: Channel,
         id: impl fmt::Display,
+        resource_profile: &ResourceProfile,
     ) -> Result<(Child, TerminateContainer, ChildStdin, ChildStdout)> {
-        let (mut start, kill) = self.prepare_worker_command(channel, id);
+        let (mut start, kill) = self.prepare_worker_command(channel, id, resource_profile)?;
 
         let mut child = start
             .stdin(Stdio::piped())
@@ -2845,25 +6062,31 @@ pub trait Backend {
         &self,
         channel: Channel,
         id: impl fmt::Display,
-    ) -> (Command, TerminateContainer);
+        resource_profile: &ResourceProfile,
+    ) -> Result<(Command, TerminateContainer)>;
 }
 
 impl<B> Backend for &B
 where
     B: Backend,
 {
+    fn prepare_worker(&self, channel: Channel) -> BoxFuture<'static, Result<()>> {
+        B::prepare_worker(self, channel)
+    }
+
     fn prepare_worker_command(
         &self,
         channel: Channel,
         id: impl fmt::Display,
-    ) -> (Command, TerminateContainer) {
-        B::prepare_worker_command(self, channel, id)
+        resource_profile: &ResourceProfile,
+    ) -> Result<(Command, TerminateContainer)> {
+        B::prepare_worker_command(self, channel, id, resource_profile)
     }
 }
 
-macro_rules! docker_command {
-    ($($arg:expr),* $(,)?) => ({
-        let mut cmd = Command::new("docker");
+macro_rules! container_command {
+    ($runtime:expr, $($arg:expr),* $(,)?) => ({
+        let mut cmd = Command::new($runtime.program());
         $( cmd.arg($arg); )*
         cmd
     });
@@ -2888,8 +6111,12 @@ const DOCKER_ARCH: &str = docker_target_arch! {
     aarch64: "linux/arm64",
 };
 
-fn basic_secure_docker_command() -> Command {
-    docker_command!(
+fn basic_secure_container_command(
+    runtime: ContainerRuntime,
+    resource_profile: &ResourceProfile,
+) -> Command {
+    let mut command = container_command!(
+        runtime,
         "run",
         "--platform",
         DOCKER_ARCH,
@@ -2897,28 +6124,63 @@ fn basic_secure_docker_command() -> Command {
         "--net",
         "none",
         "--memory",
-        "512m",
+        resource_profile.memory_bytes.to_string(),
         "--memory-swap",
-        "640m",
+        resource_profile.memory_swap_bytes.to_string(),
         "--pids-limit",
-        "512",
+        resource_profile.pids.to_string(),
         "--oom-score-adj",
         "1000",
-    )
+    );
+
+    if let Some(cpus) = resource_profile.cpus {
+        command.args(["--cpus", &cpus.to_string()]);
+    }
+
+    command
+}
+
+/// The command line tool used to manage containers.
+///
+/// Podman accepts the same arguments as Docker for everything we use,
+/// which allows running the workers without a root daemon.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum ContainerRuntime {
+    #[default]
+    Docker,
+    Podman,
+}
+
+impl ContainerRuntime {
+    fn program(self) -> &'static str {
+        match self {
+            ContainerRuntime::Docker => "docker",
+            ContainerRuntime::Podman => "podman",
+        }
+    }
 }
 
-#[derive(Default)]
-pub struct DockerBackend(());
+#[derive(Debug, Default)]
+pub struct ContainerBackend {
+    runtime: ContainerRuntime,
+}
+
+impl ContainerBackend {
+    pub fn new(runtime: ContainerRuntime) -> Self {
+        Self { runtime }
+    }
+}
 
-impl Backend for DockerBackend {
+impl Backend for ContainerBackend {
     fn prepare_worker_command(
         &self,
         channel: Channel,
         id: impl fmt::Display,
-    ) -> (Command, TerminateContainer) {
+        resource_profile: &ResourceProfile,
+    ) -> Result<(Command, TerminateContainer)> {
         let name = format!("playground-{id}");
 
-        let mut command = basic_secure_docker_command();
+        let mut command = basic_secure_container_command(self.runtime, resource_profile);
         command
             .args(["--name", &name])
             .arg("-i")
@@ -2926,13 +6188,178 @@ impl Backend for DockerBackend {
             .arg("--rm")
             .arg(channel.to_container_name())
             .arg("worker")
-            .arg("/playground");
+            .arg("/playground")
+            .arg(resource_profile.output_bytes.to_string());
 
-        let mut kill = Command::new("docker");
-        kill.arg("kill").args(["--signal", "KILL"]).arg(&name);
+        let mut kill = container_command!(self.runtime, "kill");
+        kill.args(["--signal", "KILL"]).arg(&name);
         let kill = TerminateContainer::new(name, kill);
 
-        (command, kill)
+        Ok((command, kill))
+    }
+}
+
+/// Where to find what's needed to run the worker on the host.
+#[derive(Debug, Clone)]
+pub struct LocalBackendConfig {
+    /// The `worker` binary.
+    pub worker: PathBuf,
+
+    /// A Cargo project named `playground`. Each channel gets a fresh
+    /// copy of it.
+    pub template: PathBuf,
+}
+
+/// Runs the worker directly on the host, using the `rustup`
+/// toolchain that matches each channel.
+///
+/// There's no isolation between the submitted code and the host, so
+/// this is only suitable for development and CI. Only the output
+/// limit of the [`ResourceProfile`][] is enforced.
+///
+/// Requests with extra compiler flags need `playground-rustc-wrapper`
+/// (from `compiler/base`) to be on the `PATH`.
+#[derive(Debug)]
+pub struct LocalBackend {
+    config: Arc<LocalBackendConfig>,
+    project_dir: Arc<Mutex<Option<TempDir>>>,
+}
+
+impl LocalBackend {
+    pub fn new(config: Arc<LocalBackendConfig>) -> Self {
+        Self {
+            config,
+            project_dir: Default::default(),
+        }
+    }
+
+    fn create_channel_dir(
+        config: &LocalBackendConfig,
+        project_dir: &Mutex<Option<TempDir>>,
+        channel: Channel,
+    ) -> std::io::Result<()> {
+        let mut project_dir = project_dir.lock().unwrap_or_else(|e| e.into_inner());
+
+        let project_dir = match &mut *project_dir {
+            Some(d) => d,
+            None => project_dir.insert(TempDir::with_prefix("playground")?),
+        };
+
+        // A channel's worker may have been idled and restarted;
+        // don't let it see the files from the previous session.
+        let channel_dir = project_dir.path().join(channel.to_str());
+        if channel_dir.exists() {
+            std::fs::remove_dir_all(&channel_dir)?;
+        }
+        copy_dir_all(&config.template, &channel_dir)
+    }
+
+    fn channel_dir(&self, channel: Channel) -> Option<PathBuf> {
+        let project_dir = self.project_dir.lock().unwrap_or_else(|e| e.into_inner());
+        let project_dir = project_dir.as_ref()?;
+        Some(project_dir.path().join(channel.to_str()))
+    }
+}
+
+impl Backend for LocalBackend {
+    fn prepare_worker(&self, channel: Channel) -> BoxFuture<'static, Result<()>> {
+        let config = self.config.clone();
+        let project_dir = self.project_dir.clone();
+
+        async move {
+            tokio::task::spawn_blocking(move || {
+                Self::create_channel_dir(&config, &project_dir, channel)
+            })
+            .await
+            .context(CreateProjectDirectoryPanickedSnafu)?
+            .context(CreateProjectDirectorySnafu)
+        }
+        .boxed()
+    }
+
+    fn prepare_worker_command(
+        &self,
+        channel: Channel,
+        _id: impl fmt::Display,
+        resource_profile: &ResourceProfile,
+    ) -> Result<(Command, TerminateContainer)> {
+        let channel_dir = self
+            .channel_dir(channel)
+            .context(ProjectDirectoryNotPreparedSnafu)?;
+
+        let mut command = Command::new(&self.config.worker);
+        command.env("RUSTUP_TOOLCHAIN", channel.to_str());
+        command.arg(channel_dir);
+        command.arg(resource_profile.output_bytes.to_string());
+
+        Ok((command, TerminateContainer::none()))
+    }
+}
+
+fn copy_dir_all(src: &Path, dst: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dst)?;
+
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let dst = dst.join(entry.file_name());
+
+        if entry.file_type()?.is_dir() {
+            copy_dir_all(&entry.path(), &dst)?;
+        } else {
+            std::fs::copy(entry.path(), dst)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Chooses how the workers are started.
+#[derive(Debug, Clone)]
+pub enum BackendConfig {
+    Container(ContainerRuntime),
+    Local(Arc<LocalBackendConfig>),
+}
+
+impl Default for BackendConfig {
+    fn default() -> Self {
+        Self::Container(Default::default())
+    }
+}
+
+/// The backend selected by a [`BackendConfig`][].
+#[derive(Debug)]
+pub enum ConfiguredBackend {
+    Container(ContainerBackend),
+    Local(LocalBackend),
+}
+
+impl ConfiguredBackend {
+    fn new(config: &BackendConfig) -> Self {
+        match config {
+            BackendConfig::Container(runtime) => Self::Container(ContainerBackend::new(*runtime)),
+            BackendConfig::Local(config) => Self::Local(LocalBackend::new(config.clone())),
+        }
+    }
+}
+
+impl Backend for ConfiguredBackend {
+    fn prepare_worker(&self, channel: Channel) -> BoxFuture<'static, Result<()>> {
+        match self {
+            Self::Container(b) => b.prepare_worker(channel),
+            Self::Local(b) => b.prepare_worker(channel),
+        }
+    }
+
+    fn prepare_worker_command(
+        &self,
+        channel: Channel,
+        id: impl fmt::Display,
+        resource_profile: &ResourceProfile,
+    ) -> Result<(Command, TerminateContainer)> {
+        match self {
+            Self::Container(b) => b.prepare_worker_command(channel, id, resource_profile),
+            Self::Local(b) => b.prepare_worker_command(channel, id, resource_profile),
+        }
     }
 }
 
@@ -2953,6 +6380,15 @@ pub enum Error {
     #[snafu(display("Reached system process limit"))]
     SpawnWorker { source: std::io::Error },
 
+    #[snafu(display("Unable to create the worker's project directory"))]
+    CreateProjectDirectory { source: std::io::Error },
+
+    #[snafu(display("The task creating the worker's project directory panicked"))]
+    CreateProjectDirectoryPanicked { source: tokio::task::JoinError },
+
+    #[snafu(display("The worker's project directory was not created before starting it"))]
+    ProjectDirectoryNotPrepared,
+
     #[snafu(display("Unable to join child process"))]
     JoinWorker { source: std::io::Error },
 
@@ -2989,6 +6425,16 @@ pub enum Error {
     #[snafu(display("Failed to send worker message through channel"))]
     UnableToSendWorkerMessage { source: mpsc::error::SendError<()> },
 
+    #[snafu(display(
+        "The worker did not complete the handshake; it may be from an incompatible container image"
+    ))]
+    Handshake { source: CommanderError },
+
+    #[snafu(display(
+        "The worker ({worker_build}) uses protocol version {worker} but version {MIN_PROTOCOL_VERSION} or newer is required"
+    ))]
+    ProtocolMismatch { worker_build: String, worker: u32 },
+
     #[snafu(display("Unable to load original Cargo.toml"))]
     CouldNotLoadCargoToml { source: ModifyCargoTomlError },
 
@@ -3074,6 +6520,7 @@ mod tests {
         env,
         sync::{LazyLock, Once},
     };
+    use strum::VariantNames as _;
     use tempfile::TempDir;
 
     use super::*;
@@ -3096,17 +6543,21 @@ mod tests {
         project_dir: TempDir,
     }
 
+    fn build_worker() {
+        static COMPILE_WORKER_ONCE: Once = Once::new();
+
+        COMPILE_WORKER_ONCE.call_once(|| {
+            let output = std::process::Command::new("cargo")
+                .arg("build")
+                .output()
+                .expect("Build failed");
+            assert!(output.status.success(), "Build failed");
+        });
+    }
+
     impl Default for TestBackend {
         fn default() -> Self {
-            static COMPILE_WORKER_ONCE: Once = Once::new();
-
-            COMPILE_WORKER_ONCE.call_once(|| {
-                let output = std::process::Command::new("cargo")
-                    .arg("build")
-                    .output()
-                    .expect("Build failed");
-                assert!(output.status.success(), "Build failed");
-            });
+            build_worker();
 
             let project_dir = TempDir::with_prefix("playground")
                 .expect("Failed to create temporary project directory");
@@ -3137,14 +6588,23 @@ mod tests {
             &self,
             channel: Channel,
             _id: impl fmt::Display,
-        ) -> (Command, TerminateContainer) {
+            resource_profile: &ResourceProfile,
+        ) -> Result<(Command, TerminateContainer)> {
             let channel_dir = self.project_dir.path().join(channel.to_str());
 
+            // The container image has our helper scripts on the `PATH`
+            let base_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+            let path = env::var_os("PATH").unwrap_or_default();
+            let path = env::split_paths(&path).chain([base_dir]);
+            let path = env::join_paths(path).expect("Unable to build the PATH");
+
             let mut command = Command::new("./target/debug/worker");
             command.env("RUSTUP_TOOLCHAIN", channel.to_str());
+            command.env("PATH", path);
             command.arg(channel_dir);
+            command.arg(resource_profile.output_bytes.to_string());
 
-            (command, TerminateContainer::none())
+            Ok((command, TerminateContainer::none()))
         }
     }
 
@@ -3165,7 +6625,7 @@ mod tests {
         TEST_COORDINATOR_FACTORY.build()
     }
 
-    fn new_coordinator_docker() -> Coordinator<DockerBackend> {
+    fn new_coordinator_docker() -> Coordinator<ContainerBackend> {
         TEST_COORDINATOR_FACTORY.build()
     }
 
@@ -3181,14 +6641,207 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    #[snafu::report]
+    async fn worker_handshake() -> Result<()> {
+        let coordinator = new_coordinator();
+
+        let container = coordinator.select_channel(Channel::Stable).await?;
+        let capabilities = container
+            .commander
+            .one(Hello::new())
+            .with_timeout()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            capabilities.protocol_version,
+            crate::message::PROTOCOL_VERSION
+        );
+        assert!(!capabilities.worker_build.is_empty());
+        for kind in CoordinatorMessage::VARIANTS {
+            assert!(capabilities.supports(kind), "{kind} is not supported");
+        }
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
     #[tokio::test]
     #[snafu::report]
     async fn versions() -> Result<()> {
         let coordinator = new_coordinator();
 
-        let versions = coordinator.versions().with_timeout().await.unwrap();
+        let versions = coordinator.versions().with_timeout().await.unwrap();
+
+        assert_starts_with!(versions.stable.rustc.release, "1.");
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn large_files_are_transferred_in_chunks() -> Result<()> {
+        let coordinator = new_coordinator();
+        let container = coordinator.select_channel(Channel::Stable).await?;
+        let commander = &container.commander;
+
+        let len = MAX_CHUNK_LEN as usize * 5 + 123;
+        let content = (0..len).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+
+        let write = WriteFileRequest {
+            path: "large/file.bin".to_owned(),
+            content: content.clone(),
+        };
+        commander.write_file(write).with_timeout().await.unwrap();
+
+        let read = |path: &str| {
+            let path = path.to_owned();
+            async move {
+                let read = ReadFileRequest { path: path.clone() };
+                (
+                    path,
+                    commander.read_file(read, Commander::MAX_FILE_LEN).await,
+                )
+            }
+        };
+
+        // Small files are not stuck behind large ones
+        let mut reads = [read("large/file.bin"), read("Cargo.toml")]
+            .into_iter()
+            .collect::<FuturesUnordered<_>>();
+
+        let (path, small) = reads.next().with_timeout().await.unwrap();
+        assert_eq!(path, "Cargo.toml");
+        assert_contains!(String::from_utf8_lossy(&small.unwrap().0), "[package]");
+
+        let (path, large) = reads.next().with_timeout().await.unwrap();
+        assert_eq!(path, "large/file.bin");
+        assert_eq!(large.unwrap().0, content);
+        drop(reads);
+
+        let read = ReadFileRequest {
+            path: "large/file.bin".to_owned(),
+        };
+        let read = commander
+            .read_file(read, MAX_CHUNK_LEN.into())
+            .with_timeout()
+            .await;
+        assert!(
+            matches!(read, Err(CommanderError::FileTooLarge { .. })),
+            "{read:?}"
+        );
+
+        let stat = StatFileRequest {
+            path: "large/.file.bin.partial".to_owned(),
+        };
+        let stat = commander.one(stat).with_timeout().await.unwrap();
+        assert!(stat.0.is_none());
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn older_workers_are_only_sent_known_messages() -> Result<()> {
+        let coordinator = new_coordinator();
+        let container = coordinator.select_channel(Channel::Stable).await?;
+
+        // As reported by a worker from before files were chunked
+        let mut commander = container.commander.clone();
+        commander.capabilities = Some(Arc::new(Capabilities {
+            protocol_version: MIN_PROTOCOL_VERSION,
+            worker_build: "old".to_owned(),
+            message_kinds: [
+                "WriteFile",
+                "DeleteFile",
+                "ReadFile",
+                "ExecuteCommand",
+                "StdinPacket",
+                "StdinClose",
+                "Kill",
+                "Hello",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+        }));
+
+        let content = vec![42; MAX_CHUNK_LEN as usize * 2];
+        let write = WriteFileRequest {
+            path: "old/file.bin".to_owned(),
+            content: content.clone(),
+        };
+        commander.write_file(write).with_timeout().await.unwrap();
+
+        let read = ReadFileRequest {
+            path: "old/file.bin".to_owned(),
+        };
+        let read = commander
+            .read_file(read, Commander::MAX_FILE_LEN)
+            .with_timeout()
+            .await
+            .unwrap();
+        assert_eq!(read.0, content);
+
+        let list = ListDirectoryRequest {
+            path: "old".to_owned(),
+        };
+        let list = commander.one(list).with_timeout().await;
+        assert!(
+            matches!(list, Err(CommanderError::UnsupportedMessage { .. })),
+            "{list:?}"
+        );
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn build_outputs() -> Result<()> {
+        let coordinator = new_coordinator();
+
+        let req = BuildOutputsRequest {
+            channel: Channel::Stable,
+            mode: Mode::Debug,
+            target: None,
+        };
+
+        let response = coordinator
+            .build_outputs(req.clone())
+            .with_timeout()
+            .await
+            .unwrap();
+        assert!(
+            !response
+                .outputs
+                .iter()
+                .any(|o| o.path == "target/debug/playground"),
+            "{response:?}"
+        );
+
+        let response = coordinator
+            .execute(new_execute_request())
+            .with_timeout()
+            .await
+            .unwrap();
+        assert!(response.success, "stderr: {}", response.stderr);
 
-        assert_starts_with!(versions.stable.rustc.release, "1.");
+        let response = coordinator.build_outputs(req).with_timeout().await.unwrap();
+        let output = response
+            .outputs
+            .iter()
+            .find(|o| o.path == "target/debug/playground")
+            .unwrap_or_else(|| panic!("{response:?}"));
+        assert!(output.size > 0);
+        assert!(output.modified.is_some());
+        assert!(response.outputs.iter().all(|o| !o.path.ends_with(".d")));
 
         coordinator.shutdown().await?;
 
@@ -3202,6 +6855,11 @@ mod tests {
         crate_type: CrateType::Binary,
         tests: false,
         backtrace: false,
+        test_options: TestOptions::NONE,
+        extra: ExtraOptions::NONE,
+        json_diagnostics: false,
+        sanitizer: None,
+        coverage: false,
         code: Code::new(),
     };
 
@@ -3234,6 +6892,138 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    #[snafu::report]
+    async fn execute_json_diagnostics() -> Result<()> {
+        let coordinator = new_coordinator();
+
+        let req = ExecuteRequest {
+            code: r#"fn main() { let unused = 1; println!("Still running"); }"#.into(),
+            json_diagnostics: true,
+            ..ARBITRARY_EXECUTE_REQUEST
+        };
+
+        let response = coordinator.execute(req).with_timeout().await.unwrap();
+
+        assert!(response.success, "stderr: {}", response.stderr);
+        assert_eq!(response.stdout, "Still running\n");
+        assert_contains!(response.stderr, "unused variable");
+
+        let [diagnostic] = &response.diagnostics[..] else {
+            panic!("Expected one diagnostic, got {:?}", response.diagnostics);
+        };
+        assert_eq!(diagnostic.level, DiagnosticLevel::Warning);
+        assert_contains!(diagnostic.message, "unused variable");
+
+        let span = diagnostic.spans.iter().find(|s| s.is_primary).unwrap();
+        assert_eq!(span.file_name, "src/main.rs");
+        assert_eq!((span.line_start, span.column_start), (1, 17));
+        assert_eq!((span.line_end, span.column_end), (1, 23));
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn local_backend() -> Result<()> {
+        build_worker();
+
+        let template = TempDir::with_prefix("playground-template")
+            .expect("Failed to create temporary template directory");
+
+        let output = std::process::Command::new("cargo")
+            .arg("+stable")
+            .arg("new")
+            .args(["--name", "playground"])
+            .arg(template.path().join("playground"))
+            .output()
+            .expect("Cargo new failed");
+        assert!(output.status.success(), "Cargo new failed");
+
+        let config = LocalBackendConfig {
+            worker: "./target/debug/worker".into(),
+            template: template.path().join("playground"),
+        };
+        let factory = CoordinatorFactory::with_config(
+            TEST_COORDINATOR_ID_PROVIDER.clone(),
+            BackendConfig::Local(Arc::new(config)),
+            Default::default(),
+        );
+        let coordinator = factory.build_configured();
+
+        let response = coordinator
+            .execute(new_execute_request())
+            .with_timeout()
+            .await
+            .unwrap();
+
+        assert!(response.success, "stderr: {}", response.stderr);
+        assert_contains!(response.stdout, "Hello, coordinator!");
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn execute_process_timeout() -> Result<()> {
+        let factory = CoordinatorFactory::with_config(
+            TEST_COORDINATOR_ID_PROVIDER.clone(),
+            Default::default(),
+            ResourceProfile {
+                process_timeout: Some(Duration::from_secs(3)),
+                ..Default::default()
+            },
+        );
+        let coordinator = factory.build::<TestBackend>();
+
+        let req = ExecuteRequest {
+            code: r#"fn main() { loop { std::thread::park() } }"#.into(),
+            ..new_execute_request()
+        };
+
+        let response = coordinator.execute(req).with_timeout().await.unwrap();
+
+        assert!(!response.success, "stderr: {}", response.stderr);
+        assert_eq!(response.exit_detail, "Timed out after 3 s");
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn execute_output_limit_kills_the_process() -> Result<()> {
+        let factory = CoordinatorFactory::with_config(
+            TEST_COORDINATOR_ID_PROVIDER.clone(),
+            Default::default(),
+            ResourceProfile {
+                output_bytes: 1024,
+                ..Default::default()
+            },
+        );
+        let coordinator = factory.build::<TestBackend>();
+
+        let req = ExecuteRequest {
+            code: r#"fn main() { loop { println!("hello") } }"#.into(),
+            ..new_execute_request()
+        };
+
+        let response = coordinator.execute(req).with_timeout().await.unwrap();
+
+        assert!(!response.success, "stderr: {}", response.stderr);
+        assert!(response.stdout.len() <= 1024);
+        assert_contains!(response.stderr, "Output truncated after 1024 bytes");
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
     #[tokio::test]
     #[snafu::report]
     async fn execute_mode() -> Result<()> {
@@ -3362,15 +7152,469 @@ mod tests {
             };
             let response = coordinator.execute(request).await.unwrap();
 
-            assert!(response.success, "({tests:?}), stderr: {}", response.stderr);
-            assert_contains!(response.stderr, expected);
+            assert!(response.success, "({tests:?}), stderr: {}", response.stderr);
+            assert_contains!(response.stderr, expected);
+
+            coordinator.shutdown().await?;
+
+            Ok::<_, Error>(())
+        });
+
+        try_join_all(tests).with_timeout().await?;
+
+        Ok(())
+    }
+
+    const TEST_RESULTS_CODE: &str = r#"
+        #[test] fn passes() {}
+        #[test] fn fails() { println!("Some output"); panic!("Oh no"); }
+        #[test] #[ignore] fn ignored() {}
+        #[test] #[ignore] fn ignored_fails() { panic!() }
+    "#;
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn execute_test_results() -> Result<()> {
+        let coordinator = new_coordinator();
+
+        let request = ExecuteRequest {
+            code: TEST_RESULTS_CODE.into(),
+            crate_type: CrateType::Library(LibraryType::Lib),
+            tests: true,
+            ..ARBITRARY_EXECUTE_REQUEST
+        };
+        let response = coordinator.execute(request).with_timeout().await.unwrap();
+
+        assert!(!response.success, "stderr: {}", response.stderr);
+
+        let mut tests = response.tests.clone();
+        tests.sort_by(|a, b| a.name.cmp(&b.name));
+        let outcomes = tests
+            .iter()
+            .map(|t| (&*t.name, t.outcome))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outcomes,
+            [
+                ("fails", TestOutcome::Failed),
+                ("ignored", TestOutcome::Ignored),
+                ("ignored_fails", TestOutcome::Ignored),
+                ("passes", TestOutcome::Passed),
+            ],
+        );
+
+        assert_starts_with!(tests[0].output, "Some output");
+        assert_contains!(tests[0].output, "Oh no");
+        assert_eq!(tests[3].output, "");
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn execute_test_options() -> Result<()> {
+        let coordinator = new_coordinator();
+
+        let request = ExecuteRequest {
+            code: TEST_RESULTS_CODE.into(),
+            crate_type: CrateType::Library(LibraryType::Lib),
+            tests: true,
+            test_options: TestOptions {
+                filter: Some("ignored".into()),
+                ignored: true,
+                nocapture: true,
+                test_threads: NonZeroU32::new(1),
+            },
+            ..ARBITRARY_EXECUTE_REQUEST
+        };
+        let response = coordinator.execute(request).with_timeout().await.unwrap();
+
+        assert!(!response.success, "stderr: {}", response.stderr);
+
+        let outcomes = response
+            .tests
+            .iter()
+            .map(|t| (&*t.name, t.outcome))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outcomes,
+            [
+                ("ignored", TestOutcome::Passed),
+                ("ignored_fails", TestOutcome::Failed),
+            ],
+        );
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_output_parser() {
+        let mut parser = TestOutputParser::default();
+
+        let results = parser.push("\nrunning 3 tests\ntest a ... ok <0.250s>\ntest b ... FAI");
+        assert_eq!(
+            results,
+            [TestResult {
+                name: "a".into(),
+                outcome: TestOutcome::Passed,
+                duration: Some(Duration::from_millis(250)),
+                output: String::new(),
+            }],
+        );
+
+        // Failures are held until their output has been seen
+        let results = parser.push("LED\ntest c ... ignored, slow\n\nfailures:\n\n");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].outcome, TestOutcome::Ignored);
+
+        let results = parser.push("---- b stdout ----\nboom\n\n\nfailures:\n    b\n\n");
+        assert_eq!(
+            results,
+            [TestResult {
+                name: "b".into(),
+                outcome: TestOutcome::Failed,
+                duration: None,
+                output: "boom".into(),
+            }],
+        );
+
+        assert_eq!(parser.push("test result: FAILED. 1 passed; 1 failed"), []);
+        assert_eq!(parser.finish(), []);
+    }
+
+    #[test]
+    fn sanitizer_report_parser() {
+        let mut parser = SanitizerReportParser::default();
+
+        let reports = parser.push(concat!(
+            "=================================================================\n",
+            "==42==ERROR: AddressSanitizer: heap-use-after-free on address 0x6020 at pc 0x55\n",
+            "READ of size 4 at 0x6020 thread T0\n",
+            "    #0 0x55 in playground::main src/main.rs:4:20\n",
+            "SUMMARY: AddressSanitizer: heap-use-after-free src/main.rs:4:20 in playground::main\n",
+            "WARNING: ThreadSanitizer: data race (pid=7)\n",
+        ));
+        assert_eq!(
+            reports,
+            [SanitizerReport {
+                sanitizer: Sanitizer::Address,
+                kind: "heap-use-after-free".into(),
+                summary: Some("heap-use-after-free src/main.rs:4:20 in playground::main".into()),
+            }],
+        );
+
+        // A report without a summary is returned when the next starts
+        let reports = parser.push("==7==ERROR: LeakSanitizer: detected memory leaks\n");
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].sanitizer, Sanitizer::Thread);
+        assert_eq!(reports[0].kind, "data race");
+        assert_eq!(reports[0].summary, None);
+
+        let reports =
+            parser.push("SUMMARY: AddressSanitizer: 40 byte(s) leaked in 1 allocation(s).");
+        assert_eq!(reports, []);
+
+        assert_eq!(
+            parser.finish(),
+            [SanitizerReport {
+                sanitizer: Sanitizer::Leak,
+                kind: "detected memory leaks".into(),
+                summary: Some("40 byte(s) leaked in 1 allocation(s).".into()),
+            }],
+        );
+    }
+
+    #[test]
+    fn cargo_message_filter() {
+        let mut filter = CargoMessageFilter::default();
+
+        let output = filter.push(
+            concat!(
+                r#"{"reason":"compiler-message","message":{"message":"m","code":null,"level":"new-level","spans":[],"children":[],"rendered":"r"}}"#,
+                "\n",
+                r#"{"reason":"compiler-message","message":{"unexpected":true}}"#,
+                "\n",
+                "not json\n",
+                r#"{"reason":"build-finished","success":true}"#,
+                "\n",
+                r#"{"printed":"by the program"}"#,
+                "\n",
+            )
+            .into(),
+        );
+
+        let [CargoOutput::Stderr(rendered), CargoOutput::Diagnostic(diagnostic), CargoOutput::Stdout(not_json), CargoOutput::Stdout(program)] =
+            &output[..]
+        else {
+            panic!("Unexpected number of outputs: {}", output.len());
+        };
+
+        assert_eq!(rendered, "r");
+        assert_eq!(diagnostic.level, DiagnosticLevel::Unknown);
+        assert_eq!(not_json, "not json\n");
+        assert_eq!(program, "{\"printed\":\"by the program\"}\n");
+        assert_eq!(filter.finish(), None);
+    }
+
+    #[test]
+    fn execute_with_sanitizer() {
+        let req = ExecuteRequest {
+            channel: Channel::Nightly,
+            tests: true,
+            sanitizer: Some(Sanitizer::Thread),
+            ..ARBITRARY_EXECUTE_REQUEST
+        };
+
+        let ExecuteCommandRequest { cmd, args, .. } = req.execute_cargo_request();
+
+        assert_eq!(cmd, "cargo");
+        assert_eq!(args[..3], ["sanitize", "thread", "test"]);
+        assert_eq!(cargo_subcommand(&args), Some("test"));
+    }
+
+    #[test]
+    fn lcov_parser() {
+        let report = concat!(
+            "SF:src/main.rs\n",
+            "FN:1,_RNvCs_10playground4main\n",
+            "DA:3,0\n",
+            "DA:1,1\n",
+            "DA:2,10\n",
+            "LF:3\n",
+            "end_of_record\n",
+            "SF:src/lib.rs\n",
+            "DA:1,2,checksum\n",
+            "end_of_record\n",
+        );
+
+        assert_eq!(
+            FileCoverage::parse_lcov(report),
+            [
+                FileCoverage {
+                    path: "src/lib.rs".into(),
+                    lines: vec![LineCoverage { line: 1, count: 2 }],
+                },
+                FileCoverage {
+                    path: "src/main.rs".into(),
+                    lines: vec![
+                        LineCoverage { line: 1, count: 1 },
+                        LineCoverage { line: 2, count: 10 },
+                        LineCoverage { line: 3, count: 0 },
+                    ],
+                },
+            ],
+        );
+    }
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn execute_coverage() -> Result<()> {
+        let coordinator = new_coordinator();
+
+        let code = r#"
+            fn check(v: u8) -> bool {
+                if v > 10 {
+                    return true;
+                }
+                false
+            }
+
+            #[test]
+            fn small() {
+                assert!(!check(1));
+            }
+        "#;
+
+        for tests in [false, true] {
+            let req = ExecuteRequest {
+                tests,
+                coverage: true,
+                code: format!("{code}\nfn main() {{ check(2); check(3); }}").into(),
+                ..ARBITRARY_EXECUTE_REQUEST
+            };
+
+            let response = coordinator.execute(req).with_timeout().await.unwrap();
+
+            assert!(response.success, "stderr: {}", response.stderr);
+
+            let [file] = &response.coverage[..] else {
+                panic!("Expected one file, got {:?}", response.coverage);
+            };
+            assert_eq!(file.path, "src/main.rs");
+
+            let count = |line| file.lines.iter().find(|l| l.line == line).map(|l| l.count);
+            assert_eq!(count(3), Some(if tests { 1 } else { 2 }));
+            assert_eq!(count(4), Some(0));
+        }
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn bench_output_parser() {
+        let mut parser = TestOutputParser::default();
+
+        let results = parser.push(concat!(
+            "test add   ... bench:           1.50 ns/iter (+/- 0.25)\n",
+            "test copy  ... bench:      12,345 ns/iter (+/- 678) = 512 MB/s\n",
+            "test works ... ignored\n",
+        ));
+
+        let benchmarks = results
+            .into_iter()
+            .filter_map(BenchResult::from_test_result)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            benchmarks,
+            [
+                BenchResult {
+                    name: "add".into(),
+                    stats: BenchStats {
+                        median: Duration::from_nanos(1) + Duration::from_nanos(1) / 2,
+                        spread: Duration::from_secs_f64(0.25e-9),
+                        megabytes_per_second: None,
+                    },
+                },
+                BenchResult {
+                    name: "copy".into(),
+                    stats: BenchStats {
+                        median: Duration::from_nanos(12_345),
+                        spread: Duration::from_nanos(678),
+                        megabytes_per_second: Some(512),
+                    },
+                },
+            ],
+        );
+    }
+
+    const DOC_CODE: &str = r#"
+        /// Adds one.
+        ///
+        /// ```
+        /// assert_eq!(playground::add_one(1), 2);
+        /// ```
+        pub fn add_one(x: u8) -> u8 { x + 1 }
+
+        /// ```
+        /// assert_eq!(playground::add_one(1), 3);
+        /// ```
+        pub struct Wrong;
+    "#;
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn doc() -> Result<()> {
+        let coordinator = new_coordinator();
+
+        let req = DocRequest {
+            channel: Channel::Stable,
+            crate_type: CrateType::Library(LibraryType::Lib),
+            edition: Edition::Rust2021,
+            mode: DocMode::Build,
+            code: DOC_CODE.into(),
+        };
+
+        let response = coordinator.doc(req).with_timeout().await.unwrap();
+
+        assert!(response.success, "stderr: {}", response.stderr);
+
+        let page = response
+            .files
+            .iter()
+            .find(|f| f.path == "playground/fn.add_one.html")
+            .expect("The function page was not generated");
+        assert_contains!(page.content, "Adds one.");
+        assert!(response.files.iter().all(|f| !f.path.ends_with(".woff2")));
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn doc_tests() -> Result<()> {
+        let coordinator = new_coordinator();
+
+        let req = DocRequest {
+            channel: Channel::Stable,
+            crate_type: CrateType::Library(LibraryType::Lib),
+            edition: Edition::Rust2021,
+            mode: DocMode::Test,
+            code: DOC_CODE.into(),
+        };
+
+        let response = coordinator.doc(req).with_timeout().await.unwrap();
+
+        assert!(!response.success, "stderr: {}", response.stderr);
+        assert!(response.files.is_empty());
+
+        let outcomes = response
+            .tests
+            .iter()
+            .map(|t| (&*t.name, t.outcome))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outcomes,
+            [
+                ("src/lib.rs - add_one (line 4)", TestOutcome::Passed),
+                ("src/lib.rs - Wrong (line 9)", TestOutcome::Failed),
+            ],
+        );
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn bench() -> Result<()> {
+        let coordinator = new_coordinator();
+
+        let code = r#"
+            #![feature(test)]
+            extern crate test;
+
+            #[bench]
+            fn fast(b: &mut test::Bencher) {
+                b.iter(|| test::black_box(1) + 1);
+            }
+
+            #[bench]
+            fn skipped(b: &mut test::Bencher) {
+                b.iter(|| ());
+            }
+        "#;
 
-            coordinator.shutdown().await?;
+        let req = BenchRequest {
+            channel: Channel::Nightly,
+            crate_type: CrateType::Library(LibraryType::Lib),
+            edition: Edition::Rust2021,
+            filter: Some("fast".into()),
+            time_limit: BenchRequest::MAX_TIME_LIMIT,
+            code: code.into(),
+        };
 
-            Ok::<_, Error>(())
-        });
+        let response = coordinator.bench(req).with_timeout().await.unwrap();
 
-        try_join_all(tests).with_timeout().await?;
+        assert!(response.success, "stderr: {}", response.stderr);
+
+        let names = response
+            .benchmarks
+            .iter()
+            .map(|b| &*b.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["fast"]);
+        assert_contains!(response.stdout, "ns/iter");
+
+        coordinator.shutdown().await?;
 
         Ok(())
     }
@@ -3462,6 +7706,7 @@ mod tests {
             stdout_rx,
             stderr_rx,
             status_rx: _status_rx,
+            test_rx: _test_rx,
         } = coordinator.begin_execute(token, request).await.unwrap();
 
         stdin_tx.send("this is stdin\n".into()).await.unwrap();
@@ -3515,6 +7760,7 @@ mod tests {
             stdout_rx,
             stderr_rx,
             status_rx: _,
+            test_rx: _,
         } = coordinator.begin_execute(token, request).await.unwrap();
 
         for i in 0..3 {
@@ -3571,6 +7817,7 @@ mod tests {
             stdout_rx,
             stderr_rx,
             status_rx: _,
+            test_rx: _,
         } = coordinator
             .begin_execute(token.clone(), request)
             .await
@@ -3635,6 +7882,7 @@ mod tests {
             stdout_rx,
             stderr_rx,
             status_rx,
+            test_rx: _,
         } = coordinator
             .begin_execute(token.clone(), request)
             .await
@@ -3669,12 +7917,16 @@ mod tests {
 
     const ARBITRARY_COMPILE_REQUEST: CompileRequest = CompileRequest {
         target: CompileTarget::Mir,
+        target_triple: None,
         channel: Channel::Stable,
         crate_type: CrateType::Binary,
         mode: Mode::Release,
         edition: Edition::Rust2021,
         tests: false,
         backtrace: false,
+        extra: ExtraOptions::NONE,
+        json_diagnostics: false,
+        export_artifact: false,
         code: Code::new(),
     };
 
@@ -3699,6 +7951,35 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    #[snafu::report]
+    async fn compile_output_limit_lets_the_build_finish() -> Result<()> {
+        let factory = CoordinatorFactory::with_config(
+            TEST_COORDINATOR_ID_PROVIDER.clone(),
+            Default::default(),
+            ResourceProfile {
+                output_bytes: 16,
+                ..Default::default()
+            },
+        );
+        let coordinator = factory.build::<TestBackend>();
+
+        let req = CompileRequest {
+            code: HELLO_WORLD_CODE.into(),
+            ..ARBITRARY_COMPILE_REQUEST
+        };
+
+        let response = coordinator.compile(req).with_timeout().await.unwrap();
+
+        assert!(response.success, "stderr: {}", response.stderr);
+        assert_contains!(response.stderr, "Output truncated after 16 bytes");
+        assert_contains!(response.code, "fn main()");
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
     #[tokio::test]
     #[snafu::report]
     async fn compile_streaming() -> Result<()> {
@@ -3803,12 +8084,16 @@ mod tests {
             DEFAULT_ASSEMBLY_DEMANGLE,
             DEFAULT_ASSEMBLY_PROCESS,
         ),
+        target_triple: None,
         channel: Channel::Beta,
         crate_type: CrateType::Library(LibraryType::Lib),
         mode: Mode::Release,
         edition: Edition::Rust2018,
         tests: false,
         backtrace: false,
+        extra: ExtraOptions::NONE,
+        json_diagnostics: false,
+        export_artifact: false,
         code: Code::new(),
     };
 
@@ -3822,19 +8107,268 @@ mod tests {
         let coordinator = new_coordinator();
 
         let req = CompileRequest {
-            code: ADD_CODE.into(),
-            ..ARBITRARY_ASSEMBLY_REQUEST
+            code: ADD_CODE.into(),
+            ..ARBITRARY_ASSEMBLY_REQUEST
+        };
+
+        let response = coordinator.compile(req).with_timeout().await.unwrap();
+
+        let asm = docker_target_arch! {
+            x86_64: "eax, [rsi + rdi]",
+            aarch64: "w0, w1, w0",
+        };
+
+        assert!(response.success, "stderr: {}", response.stderr);
+        assert_contains!(response.code, asm);
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[snafu::report]
+    // Assembly flavor only makes sense when targeting x86(_64): this
+    // test will always fail on aarch64.
+    async fn compile_assembly_flavor() -> Result<()> {
+        let cases = [
+            (AssemblyFlavor::Att, "(%rsi,%rdi), %eax"),
+            (AssemblyFlavor::Intel, "eax, [rsi + rdi]"),
+        ];
+
+        for (flavor, expected) in cases {
+            let coordinator = new_coordinator();
+
+            let req = CompileRequest {
+                target: CompileTarget::Assembly(
+                    flavor,
+                    DEFAULT_ASSEMBLY_DEMANGLE,
+                    DEFAULT_ASSEMBLY_PROCESS,
+                ),
+                code: ADD_CODE.into(),
+                ..ARBITRARY_ASSEMBLY_REQUEST
+            };
+
+            let response = coordinator.compile(req).with_timeout().await.unwrap();
+
+            assert!(response.success, "stderr: {}", response.stderr);
+            assert_contains!(response.code, expected);
+
+            coordinator.shutdown().await?;
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[snafu::report]
+    // The demangling expects Linux-style symbols, not macOS: this
+    // test will always fail on macOS.
+    async fn compile_assembly_demangle() -> Result<()> {
+        let cases = [
+            (DemangleAssembly::Mangle, "10playground3add"),
+            (DemangleAssembly::Demangle, "playground::add"),
+        ];
+
+        for (mangle, expected) in cases {
+            let coordinator = new_coordinator();
+
+            let req = CompileRequest {
+                target: CompileTarget::Assembly(
+                    DEFAULT_ASSEMBLY_FLAVOR,
+                    mangle,
+                    DEFAULT_ASSEMBLY_PROCESS,
+                ),
+                code: ADD_CODE.into(),
+                ..ARBITRARY_ASSEMBLY_REQUEST
+            };
+
+            let response = coordinator.compile(req).with_timeout().await.unwrap();
+
+            assert!(response.success, "stderr: {}", response.stderr);
+            assert_contains!(response.code, expected);
+
+            coordinator.shutdown().await?;
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn compile_assembly_process() -> Result<()> {
+        let cases = [
+            (ProcessAssembly::Raw, true),
+            (ProcessAssembly::Filter, false),
+        ];
+
+        for (process, expected) in cases {
+            let coordinator = new_coordinator();
+
+            let req = CompileRequest {
+                target: CompileTarget::Assembly(
+                    DEFAULT_ASSEMBLY_FLAVOR,
+                    DEFAULT_ASSEMBLY_DEMANGLE,
+                    process,
+                ),
+                code: ADD_CODE.into(),
+                ..ARBITRARY_ASSEMBLY_REQUEST
+            };
+
+            let response = coordinator.compile(req).with_timeout().await.unwrap();
+
+            assert!(response.success, "stderr: {}", response.stderr);
+            if expected {
+                assert_contains!(response.code, ".cfi_startproc");
+            } else {
+                assert_not_contains!(response.code, ".cfi_startproc");
+            }
+
+            coordinator.shutdown().await?;
+        }
+
+        Ok(())
+    }
+
+    const SUBTRACT_CODE: &str = r#"pub fn sub(a: u8, b: u8) -> u8 { a - b }"#;
+
+    const ARBITRARY_HIR_REQUEST: CompileRequest = CompileRequest {
+        target: CompileTarget::Hir,
+        target_triple: None,
+        channel: Channel::Nightly,
+        crate_type: CrateType::Library(LibraryType::Lib),
+        mode: Mode::Release,
+        edition: Edition::Rust2021,
+        tests: false,
+        backtrace: false,
+        extra: ExtraOptions::NONE,
+        json_diagnostics: false,
+        export_artifact: false,
+        code: Code::new(),
+    };
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn compile_hir() -> Result<()> {
+        let coordinator = new_coordinator();
+
+        let req = CompileRequest {
+            code: SUBTRACT_CODE.into(),
+            ..ARBITRARY_HIR_REQUEST
+        };
+
+        let response = coordinator.compile(req).with_timeout().await.unwrap();
+
+        assert!(response.success, "stderr: {}", response.stderr);
+        assert_contains!(response.code, "extern crate std");
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
+    const INLINE_CODE: &str = r#"
+        #[inline]
+        fn double(a: u8) -> u8 { a.wrapping_mul(2) }
+
+        pub fn quadruple(a: u8) -> u8 { double(double(a)) }
+    "#;
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn compile_optimized_mir() -> Result<()> {
+        let coordinator = new_coordinator();
+
+        let cases = [
+            (None, "fn quadruple("),
+            (Some(MirPass::Inline), "after Inline"),
+        ];
+
+        for (pass, expected) in cases {
+            let req = CompileRequest {
+                target: CompileTarget::OptimizedMir(MirOptLevel::L2, pass),
+                code: INLINE_CODE.into(),
+                ..ARBITRARY_HIR_REQUEST
+            };
+
+            let response = coordinator.compile(req).with_timeout().await.unwrap();
+
+            assert!(response.success, "stderr: {}", response.stderr);
+            assert_contains!(response.code, expected);
+        }
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn compile_llvm_machine_ir() -> Result<()> {
+        let coordinator = new_coordinator();
+
+        let req = CompileRequest {
+            target: CompileTarget::LlvmMachineIr,
+            code: ADD_CODE.into(),
+            ..ARBITRARY_HIR_REQUEST
+        };
+
+        let response = coordinator.compile(req).with_timeout().await.unwrap();
+
+        assert!(response.success, "stderr: {}", response.stderr);
+        assert_contains!(response.code, "body:");
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn compile_disassembly() -> Result<()> {
+        let coordinator = new_coordinator();
+
+        let req = CompileRequest {
+            target: CompileTarget::Disassembly,
+            code: ADD_CODE.into(),
+            ..ARBITRARY_HIR_REQUEST
+        };
+
+        let response = coordinator.compile(req).with_timeout().await.unwrap();
+
+        assert!(response.success, "stderr: {}", response.stderr);
+        assert_contains!(response.code, "Symbol sizes");
+        assert_contains!(response.code, "playground::add");
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn compile_llvm_ir() -> Result<()> {
+        let coordinator = new_coordinator();
+
+        let req = CompileRequest {
+            target: CompileTarget::LlvmIr,
+            target_triple: None,
+            channel: Channel::Stable,
+            crate_type: CrateType::Library(LibraryType::Lib),
+            mode: Mode::Debug,
+            edition: Edition::Rust2015,
+            tests: false,
+            backtrace: false,
+            extra: ExtraOptions::NONE,
+            json_diagnostics: false,
+            export_artifact: false,
+            code: r#"pub fn mul(a: u8, b: u8) -> u8 { a * b }"#.into(),
         };
 
         let response = coordinator.compile(req).with_timeout().await.unwrap();
 
-        let asm = docker_target_arch! {
-            x86_64: "eax, [rsi + rdi]",
-            aarch64: "w0, w1, w0",
-        };
-
         assert!(response.success, "stderr: {}", response.stderr);
-        assert_contains!(response.code, asm);
+        assert_contains!(response.code, "@llvm.umul.with.overflow.i8(i8, i8)");
 
         coordinator.shutdown().await?;
 
@@ -3843,135 +8377,162 @@ mod tests {
 
     #[tokio::test]
     #[snafu::report]
-    // Assembly flavor only makes sense when targeting x86(_64): this
-    // test will always fail on aarch64.
-    async fn compile_assembly_flavor() -> Result<()> {
-        let cases = [
-            (AssemblyFlavor::Att, "(%rsi,%rdi), %eax"),
-            (AssemblyFlavor::Intel, "eax, [rsi + rdi]"),
-        ];
+    async fn compile_extra_options() -> Result<()> {
+        let coordinator = new_coordinator();
 
-        for (flavor, expected) in cases {
-            let coordinator = new_coordinator();
+        let extra = ExtraOptions::new(
+            Channel::Stable,
+            ["-C", "overflow-checks=off"].map(Into::into),
+            [],
+            [],
+        )
+        .unwrap();
 
-            let req = CompileRequest {
-                target: CompileTarget::Assembly(
-                    flavor,
-                    DEFAULT_ASSEMBLY_DEMANGLE,
-                    DEFAULT_ASSEMBLY_PROCESS,
-                ),
-                code: ADD_CODE.into(),
-                ..ARBITRARY_ASSEMBLY_REQUEST
-            };
+        let req = CompileRequest {
+            target: CompileTarget::LlvmIr,
+            target_triple: None,
+            channel: Channel::Stable,
+            crate_type: CrateType::Library(LibraryType::Lib),
+            mode: Mode::Debug,
+            edition: Edition::Rust2015,
+            tests: false,
+            backtrace: false,
+            extra,
+            json_diagnostics: false,
+            export_artifact: false,
+            code: r#"pub fn mul(a: u8, b: u8) -> u8 { a * b }"#.into(),
+        };
 
-            let response = coordinator.compile(req).with_timeout().await.unwrap();
+        let response = coordinator.compile(req).with_timeout().await.unwrap();
 
-            assert!(response.success, "stderr: {}", response.stderr);
-            assert_contains!(response.code, expected);
+        assert!(response.success, "stderr: {}", response.stderr);
+        assert_not_contains!(response.code, "@llvm.umul.with.overflow.i8(i8, i8)");
 
-            coordinator.shutdown().await?;
-        }
+        coordinator.shutdown().await?;
 
         Ok(())
     }
 
-    #[tokio::test]
-    #[snafu::report]
-    // The demangling expects Linux-style symbols, not macOS: this
-    // test will always fail on macOS.
-    async fn compile_assembly_demangle() -> Result<()> {
-        let cases = [
-            (DemangleAssembly::Mangle, "10playground3add"),
-            (DemangleAssembly::Demangle, "playground::add"),
-        ];
+    #[test]
+    fn compile_for_target_triple() {
+        let req = CompileRequest {
+            target_triple: Some(TargetTriple::Aarch64UnknownLinuxGnu),
+            code: ADD_CODE.into(),
+            ..ARBITRARY_ASSEMBLY_REQUEST
+        };
 
-        for (mangle, expected) in cases {
-            let coordinator = new_coordinator();
+        let ExecuteCommandRequest { cmd, args, .. } = req.execute_cargo_request();
 
-            let req = CompileRequest {
-                target: CompileTarget::Assembly(
-                    DEFAULT_ASSEMBLY_FLAVOR,
-                    mangle,
-                    DEFAULT_ASSEMBLY_PROCESS,
-                ),
-                code: ADD_CODE.into(),
-                ..ARBITRARY_ASSEMBLY_REQUEST
-            };
+        assert_eq!(cmd, "rustc");
+        assert_contains!(args, &"aarch64-unknown-linux-gnu".to_owned());
+        assert_contains!(args, &"asm=compilation".to_owned());
+        assert!(args.iter().all(|a| !a.contains("x86-asm-syntax")));
+    }
 
-            let response = coordinator.compile(req).with_timeout().await.unwrap();
+    #[test]
+    fn artifact_paths() {
+        let req = |target, crate_type, target_triple| CompileRequest {
+            target,
+            crate_type,
+            target_triple,
+            export_artifact: true,
+            ..ARBITRARY_COMPILE_REQUEST
+        };
+        let staticlib = CrateType::Library(LibraryType::Staticlib);
+        let cdylib = CrateType::Library(LibraryType::Cdylib);
 
-            assert!(response.success, "stderr: {}", response.stderr);
-            assert_contains!(response.code, expected);
+        let host = req(CompileTarget::LlvmIr, CrateType::Binary, None);
+        assert_eq!(host.artifact_path().unwrap(), "target/release/playground");
 
-            coordinator.shutdown().await?;
-        }
+        let wasm = req(CompileTarget::Wasm, cdylib, None);
+        assert_eq!(
+            wasm.artifact_path().unwrap(),
+            "target/wasm32-unknown-unknown/release/playground.wasm",
+        );
 
-        Ok(())
+        let cross = req(
+            CompileTarget::LlvmIr,
+            staticlib,
+            Some(TargetTriple::Thumbv7emNoneEabihf),
+        );
+        assert_eq!(cross.artifact_path().unwrap(), "artifact");
+        assert_eq!(cross.artifact_name(), "libplayground.a");
+        let ExecuteCommandRequest { args, .. } = cross.execute_cargo_request();
+        assert_contains!(args, &"link=artifact".to_owned());
+
+        let hir = req(CompileTarget::Hir, CrateType::Binary, None);
+        assert_eq!(hir.artifact_path(), None);
     }
 
     #[tokio::test]
     #[snafu::report]
-    async fn compile_assembly_process() -> Result<()> {
+    async fn compile_artifact() -> Result<()> {
+        let coordinator = new_coordinator();
+
         let cases = [
-            (ProcessAssembly::Raw, true),
-            (ProcessAssembly::Filter, false),
+            (CrateType::Binary, "playground", &b"\x7fELF"[..]),
+            (
+                CrateType::Library(LibraryType::Staticlib),
+                "libplayground.a",
+                b"!<arch>\n",
+            ),
         ];
 
-        for (process, expected) in cases {
-            let coordinator = new_coordinator();
-
+        for (crate_type, name, magic) in cases {
             let req = CompileRequest {
-                target: CompileTarget::Assembly(
-                    DEFAULT_ASSEMBLY_FLAVOR,
-                    DEFAULT_ASSEMBLY_DEMANGLE,
-                    process,
-                ),
-                code: ADD_CODE.into(),
-                ..ARBITRARY_ASSEMBLY_REQUEST
+                crate_type,
+                export_artifact: true,
+                code: r#"pub fn x() {} fn main() {}"#.into(),
+                ..ARBITRARY_COMPILE_REQUEST
             };
 
             let response = coordinator.compile(req).with_timeout().await.unwrap();
 
             assert!(response.success, "stderr: {}", response.stderr);
-            if expected {
-                assert_contains!(response.code, ".cfi_startproc");
-            } else {
-                assert_not_contains!(response.code, ".cfi_startproc");
-            }
-
-            coordinator.shutdown().await?;
+            let artifact = response.artifact.as_ref().unwrap();
+            assert_eq!(artifact.name, name);
+            assert!(artifact.data.starts_with(magic), "{artifact:?}");
         }
 
-        Ok(())
-    }
+        let req = CompileRequest {
+            target: CompileTarget::Hir,
+            export_artifact: true,
+            ..ARBITRARY_COMPILE_REQUEST
+        };
+        let response = coordinator.compile(req).with_timeout().await;
+        assert_matches!(response, Err(CompileError::ArtifactNotAvailable));
 
-    const SUBTRACT_CODE: &str = r#"pub fn sub(a: u8, b: u8) -> u8 { a - b }"#;
+        coordinator.shutdown().await?;
 
-    const ARBITRARY_HIR_REQUEST: CompileRequest = CompileRequest {
-        target: CompileTarget::Hir,
-        channel: Channel::Nightly,
-        crate_type: CrateType::Library(LibraryType::Lib),
-        mode: Mode::Release,
-        edition: Edition::Rust2021,
-        tests: false,
-        backtrace: false,
-        code: Code::new(),
-    };
+        Ok(())
+    }
 
     #[tokio::test]
     #[snafu::report]
-    async fn compile_hir() -> Result<()> {
+    async fn compile_artifact_not_produced() -> Result<()> {
         let coordinator = new_coordinator();
 
+        // rustc does not produce a `cdylib` for this target
         let req = CompileRequest {
-            code: SUBTRACT_CODE.into(),
-            ..ARBITRARY_HIR_REQUEST
+            target: CompileTarget::Assembly(
+                AssemblyFlavor::Att,
+                DemangleAssembly::Demangle,
+                ProcessAssembly::Filter,
+            ),
+            crate_type: CrateType::Library(LibraryType::Cdylib),
+            target_triple: Some(TargetTriple::Thumbv7emNoneEabihf),
+            export_artifact: true,
+            code: r#"#![no_std] pub fn x() {}"#.into(),
+            ..ARBITRARY_COMPILE_REQUEST
         };
-
         let response = coordinator.compile(req).with_timeout().await.unwrap();
 
         assert!(response.success, "stderr: {}", response.stderr);
-        assert_contains!(response.code, "extern crate std");
+        assert!(response.artifact.is_none());
+        assert_contains!(
+            response.artifact_error.as_deref().unwrap(),
+            "was not produced"
+        );
 
         coordinator.shutdown().await?;
 
@@ -3980,30 +8541,104 @@ mod tests {
 
     #[tokio::test]
     #[snafu::report]
-    async fn compile_llvm_ir() -> Result<()> {
+    async fn compile_target_triple_validation() -> Result<()> {
         let coordinator = new_coordinator();
 
         let req = CompileRequest {
-            target: CompileTarget::LlvmIr,
-            channel: Channel::Stable,
-            crate_type: CrateType::Library(LibraryType::Lib),
-            mode: Mode::Debug,
-            edition: Edition::Rust2015,
-            tests: false,
-            backtrace: false,
-            code: r#"pub fn mul(a: u8, b: u8) -> u8 { a * b }"#.into(),
+            target: CompileTarget::Disassembly,
+            target_triple: Some(TargetTriple::Aarch64UnknownLinuxGnu),
+            ..ARBITRARY_COMPILE_REQUEST
         };
+        let response = coordinator.compile(req).with_timeout().await;
+        assert_matches!(response, Err(CompileError::TargetTripleNotAvailable { .. }));
 
-        let response = coordinator.compile(req).with_timeout().await.unwrap();
+        let extra =
+            ExtraOptions::new(Channel::Stable, [], ["serde/derive".to_owned()], []).unwrap();
+        let req = CompileRequest {
+            target_triple: Some(TargetTriple::Aarch64UnknownLinuxGnu),
+            extra,
+            ..ARBITRARY_COMPILE_REQUEST
+        };
+        let response = coordinator.compile(req).with_timeout().await;
+        assert_matches!(response, Err(CompileError::FeaturesWithTargetTriple { .. }));
 
-        assert!(response.success, "stderr: {}", response.stderr);
-        assert_contains!(response.code, "@llvm.umul.with.overflow.i8(i8, i8)");
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn compile_nightly_only_targets_are_rejected_on_stable() -> Result<()> {
+        let coordinator = new_coordinator();
+
+        let targets = [
+            CompileTarget::OptimizedMir(MirOptLevel::L2, None),
+            CompileTarget::OptimizedMir(MirOptLevel::L2, Some(MirPass::Inline)),
+            CompileTarget::LlvmMachineIr,
+        ];
+
+        for channel in [Channel::Stable, Channel::Beta] {
+            for target in targets {
+                let req = CompileRequest {
+                    target,
+                    channel,
+                    ..ARBITRARY_COMPILE_REQUEST
+                };
+                let response = coordinator.compile(req).with_timeout().await;
+                assert_matches!(response, Err(CompileError::TargetRequiresNightly));
+            }
+        }
 
         coordinator.shutdown().await?;
 
         Ok(())
     }
 
+    #[test]
+    fn extra_options_allow_list() {
+        let flags = |f: &[&str]| f.iter().map(|&f| f.to_owned()).collect::<Vec<_>>();
+
+        let extra = ExtraOptions::new(
+            Channel::Stable,
+            flags(&["-Ctarget-cpu=native", "-C", "opt-level=3", "--cfg", "demo"]),
+            flags(&["serde/derive"]),
+            [("RUST_BACKTRACE".to_owned(), "full".to_owned())],
+        )
+        .unwrap();
+        assert_eq!(
+            extra.rustc_flags(),
+            flags(&[
+                "-C",
+                "target-cpu=native",
+                "-C",
+                "opt-level=3",
+                "--cfg",
+                "demo"
+            ]),
+        );
+
+        let invalid = [
+            (Channel::Stable, flags(&["-C", "linker=/bin/sh"])),
+            (Channel::Stable, flags(&["-Zmir-opt-level=0"])),
+            (Channel::Nightly, flags(&["-Z", "unpretty=hir"])),
+            (Channel::Stable, flags(&["--emit", "asm"])),
+            (Channel::Stable, flags(&["-C"])),
+            (Channel::Stable, flags(&["--cfgdemo", "-C", "opt-level=3"])),
+            (Channel::Stable, flags(&["--cfg-demo"])),
+        ];
+
+        for (channel, rustc_flags) in invalid {
+            let r = ExtraOptions::new(channel, rustc_flags.clone(), [], []);
+            assert!(r.is_err(), "{rustc_flags:?} should be rejected");
+        }
+
+        assert!(ExtraOptions::new(Channel::Nightly, flags(&["-Zmir-opt-level=0"]), [], []).is_ok());
+        assert!(ExtraOptions::new(Channel::Stable, flags(&["--cfg=demo"]), [], []).is_ok());
+        assert!(ExtraOptions::new(Channel::Stable, [], flags(&["--all"]), []).is_err());
+        assert!(ExtraOptions::new(Channel::Stable, [], [], [("PATH".into(), "/".into())]).is_err());
+    }
+
     #[tokio::test]
     #[snafu::report]
     async fn compile_wasm() -> Result<()> {
@@ -4012,12 +8647,16 @@ mod tests {
 
         let req = CompileRequest {
             target: CompileTarget::Wasm,
+            target_triple: None,
             channel: Channel::Nightly,
             crate_type: CrateType::Library(LibraryType::Cdylib),
             mode: Mode::Release,
             edition: Edition::Rust2021,
             tests: false,
             backtrace: false,
+            extra: ExtraOptions::NONE,
+            json_diagnostics: false,
+            export_artifact: false,
             code: r#"#[export_name = "inc"] pub fn inc(a: u8) -> u8 { a + 1 }"#.into(),
         };
 
@@ -4038,6 +8677,7 @@ mod tests {
         channel: Channel::Stable,
         crate_type: CrateType::Binary,
         edition: Edition::Rust2015,
+        rustfmt_options: RustfmtOptions::NONE,
         code: Code::new(),
     };
 
@@ -4102,34 +8742,124 @@ mod tests {
             ("fn main() { gen { 1 } }", [false, false, false, true]),
         ];
 
-        for (code, works_in) in cases {
-            let coordinator = new_coordinator();
+        for (code, works_in) in cases {
+            let coordinator = new_coordinator();
+
+            for (edition, works) in Edition::ALL.into_iter().zip(works_in) {
+                let req = FormatRequest {
+                    edition,
+                    code: code.into(),
+                    ..ARBITRARY_FORMAT_REQUEST
+                };
+
+                let response = coordinator.format(req).with_timeout().await.unwrap();
+
+                assert_eq!(response.success, works, "{code} in {edition:?}");
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn format_multiple_files() -> Result<()> {
+        let coordinator = new_coordinator();
+
+        let req = FormatRequest {
+            code: kvs! {
+                "src/main.rs" => "fn  main  (  ){playground  ::  amaze()}",
+                "src/lib.rs" => "fn  amaze  (  ){}",
+            }
+            .collect(),
+            ..ARBITRARY_FORMAT_REQUEST
+        };
+
+        let response = coordinator.format(req).with_timeout().await.unwrap();
+
+        assert!(response.success, "stderr: {}", response.stderr);
+
+        let lines_for = |name| {
+            response
+                .code
+                .assume_multiple()
+                .iter()
+                .find(|cf| cf.name == name)
+                .unwrap()
+                .content
+                .lines()
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            lines_for("src/main.rs"),
+            ["fn main() {", "    playground::amaze()", "}"]
+        );
+        assert_eq!(lines_for("src/lib.rs"), ["fn amaze() {}"]);
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn format_rustfmt_options() -> Result<()> {
+        let coordinator = new_coordinator();
+
+        let code = "fn main() { let _ = (1, 2, 3); }";
+
+        let req = FormatRequest {
+            rustfmt_options: RustfmtOptions {
+                max_width: Some(20),
+                ..RustfmtOptions::NONE
+            },
+            code: code.into(),
+            ..ARBITRARY_FORMAT_REQUEST
+        };
+
+        let response = coordinator.format(req).with_timeout().await.unwrap();
 
-            for (edition, works) in Edition::ALL.into_iter().zip(works_in) {
-                let req = FormatRequest {
-                    edition,
-                    code: code.into(),
-                    ..ARBITRARY_FORMAT_REQUEST
-                };
+        assert!(response.success, "stderr: {}", response.stderr);
+        assert_eq!(
+            response.code.assume_single().lines().collect::<Vec<_>>(),
+            ["fn main() {", "    let _ =", "        (1, 2, 3);", "}"],
+        );
 
-                let response = coordinator.format(req).with_timeout().await.unwrap();
+        // The generated configuration does not outlive the request
+        let req = FormatRequest {
+            code: code.into(),
+            ..ARBITRARY_FORMAT_REQUEST
+        };
 
-                assert_eq!(response.success, works, "{code} in {edition:?}");
-            }
-        }
+        let response = coordinator.format(req).with_timeout().await.unwrap();
+
+        assert!(response.success, "stderr: {}", response.stderr);
+        assert_eq!(
+            response.code.assume_single().lines().collect::<Vec<_>>(),
+            ["fn main() {", "    let _ = (1, 2, 3);", "}"],
+        );
+
+        coordinator.shutdown().await?;
 
         Ok(())
     }
 
     #[tokio::test]
     #[snafu::report]
-    async fn format_multiple_files() -> Result<()> {
+    async fn format_user_rustfmt_toml() -> Result<()> {
         let coordinator = new_coordinator();
 
+        let config = "hard_tabs = true\nmax_width = 100\n";
+
         let req = FormatRequest {
+            rustfmt_options: RustfmtOptions {
+                max_width: Some(20),
+                ..RustfmtOptions::NONE
+            },
             code: kvs! {
-                "src/main.rs" => "fn  main  (  ){playground  ::  amaze()}",
-                "src/lib.rs" => "fn  amaze  (  ){}",
+                "src/main.rs" => "fn main() { let _ = (1, 2, 3); }",
+                "rustfmt.toml" => config,
             }
             .collect(),
             ..ARBITRARY_FORMAT_REQUEST
@@ -4139,33 +8869,56 @@ mod tests {
 
         assert!(response.success, "stderr: {}", response.stderr);
 
-        let lines_for = |name| {
-            response
-                .code
-                .assume_multiple()
-                .iter()
-                .find(|cf| cf.name == name)
-                .unwrap()
-                .content
-                .lines()
-                .collect::<Vec<_>>()
-        };
+        let files = response.code.assume_multiple();
+        let content_of = |name| &*files.iter().find(|cf| cf.name == name).unwrap().content;
 
         assert_eq!(
-            lines_for("src/main.rs"),
-            ["fn main() {", "    playground::amaze()", "}"]
+            content_of("src/main.rs").lines().collect::<Vec<_>>(),
+            ["fn main() {", "\tlet _ =", "\t\t(1, 2, 3);", "}"],
         );
-        assert_eq!(lines_for("src/lib.rs"), ["fn amaze() {}"]);
+        assert_eq!(content_of("rustfmt.toml"), config);
 
         coordinator.shutdown().await?;
 
         Ok(())
     }
 
+    #[test]
+    fn config_file_merge() {
+        let options = RustfmtOptions {
+            max_width: Some(80),
+            group_imports: Some(GroupImports::StdExternalCrate),
+            style_edition: Some(Edition::Rust2024),
+            other: [("wrap_comments".to_owned(), true.into())].into(),
+            ..RustfmtOptions::NONE
+        };
+
+        let options = options.to_table();
+
+        let config = b"max_width = 120\nhard_tabs = true\n".to_vec();
+        let config = ConfigFile::merge(config, &options);
+        let config = String::from_utf8(config).unwrap();
+        let config = config.parse::<toml::Table>().unwrap();
+
+        assert_eq!(config["max_width"].as_integer(), Some(80));
+        assert_eq!(config["hard_tabs"].as_bool(), Some(true));
+        assert_eq!(config["group_imports"].as_str(), Some("StdExternalCrate"));
+        assert_eq!(config["style_edition"].as_str(), Some("2024"));
+        assert_eq!(config["wrap_comments"].as_bool(), Some(true));
+
+        // Invalid configuration is left for rustfmt to report
+        assert_eq!(
+            ConfigFile::merge(b"max_width = ".to_vec(), &options),
+            b"max_width = ",
+        );
+    }
+
     const ARBITRARY_CLIPPY_REQUEST: ClippyRequest = ClippyRequest {
         channel: Channel::Stable,
         crate_type: CrateType::Library(LibraryType::Rlib),
         edition: Edition::Rust2021,
+        json_diagnostics: false,
+        lints: ClippyLints::NONE,
         code: Code::new(),
     };
 
@@ -4196,6 +8949,102 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    #[snafu::report]
+    async fn clippy_json_diagnostics() -> Result<()> {
+        let coordinator = new_coordinator();
+
+        let req = ClippyRequest {
+            code: r#"pub fn empty(v: &[u8]) -> bool { v.len() == 0 }"#.into(),
+            json_diagnostics: true,
+            ..ARBITRARY_CLIPPY_REQUEST
+        };
+
+        let response = coordinator.clippy(req).with_timeout().await.unwrap();
+
+        assert!(response.success, "stderr: {}", response.stderr);
+        assert_contains!(response.stderr, "clippy::len_zero");
+
+        let [diagnostic] = &response.diagnostics[..] else {
+            panic!("Expected one diagnostic, got {:?}", response.diagnostics);
+        };
+        assert_eq!(diagnostic.level, DiagnosticLevel::Warning);
+        assert_eq!(
+            diagnostic.code.as_ref().map(|c| &*c.code),
+            Some("clippy::len_zero"),
+        );
+
+        let suggestion = diagnostic
+            .children
+            .iter()
+            .flat_map(|c| &c.spans)
+            .find(|s| s.suggested_replacement.is_some())
+            .unwrap();
+        assert_eq!(
+            suggestion.suggested_replacement.as_deref(),
+            Some("v.is_empty()"),
+        );
+        assert_eq!(
+            suggestion.suggestion_applicability,
+            Some(Applicability::MachineApplicable),
+        );
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn clippy_lints() -> Result<()> {
+        let coordinator = new_coordinator();
+
+        let code = r#"
+            pub fn first(v: &[u8]) -> u8 { *v.first().unwrap() }
+            pub fn many(_: u8, _: u8, _: u8) {}
+        "#;
+
+        let req = ClippyRequest {
+            lints: ClippyLints {
+                groups: ClippyLintGroups {
+                    pedantic: true,
+                    ..ClippyLintGroups::NONE
+                },
+                levels: [
+                    ("clippy::unwrap_used".into(), LintLevel::Deny),
+                    ("clippy::must_use_candidate".into(), LintLevel::Allow),
+                ]
+                .into(),
+                config: [("too-many-arguments-threshold".into(), 2.into())].into(),
+            },
+            code: code.into(),
+            ..ARBITRARY_CLIPPY_REQUEST
+        };
+
+        let response = coordinator.clippy(req).with_timeout().await.unwrap();
+
+        assert!(!response.success, "stderr: {}", response.stderr);
+        assert_contains!(response.stderr, "`-D clippy::unwrap-used`");
+        assert_contains!(response.stderr, "implied by `-W clippy::pedantic`");
+        assert_contains!(response.stderr, "too many arguments (3/2)");
+        assert_not_contains!(response.stderr, "must_use_candidate");
+
+        // The configuration does not outlive the request
+        let req = ClippyRequest {
+            code: code.into(),
+            ..ARBITRARY_CLIPPY_REQUEST
+        };
+
+        let response = coordinator.clippy(req).with_timeout().await.unwrap();
+
+        assert!(response.success, "stderr: {}", response.stderr);
+        assert_not_contains!(response.stderr, "too many arguments");
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
     #[tokio::test]
     #[snafu::report]
     async fn clippy_multiple_files() -> Result<()> {
@@ -4261,15 +9110,308 @@ mod tests {
         Ok(())
     }
 
+    const ARBITRARY_FIX_REQUEST: FixRequest = FixRequest {
+        channel: Channel::Stable,
+        crate_type: CrateType::Library(LibraryType::Rlib),
+        edition: Edition::Rust2021,
+        tool: FixTool::Rustc,
+        code: Code::new(),
+    };
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn fix() -> Result<()> {
+        let coordinator = new_coordinator();
+
+        let req = FixRequest {
+            code: "pub fn one() -> u8 { let mut a = 1; a }".into(),
+            ..ARBITRARY_FIX_REQUEST
+        };
+
+        let response = coordinator.fix(req).with_timeout().await.unwrap();
+
+        assert!(response.success, "stderr: {}", response.stderr);
+        assert_eq!(
+            response.code.assume_single(),
+            "pub fn one() -> u8 { let a = 1; a }",
+        );
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn fix_clippy() -> Result<()> {
+        let coordinator = new_coordinator();
+
+        let req = FixRequest {
+            tool: FixTool::Clippy,
+            code: "pub fn empty(v: &[u8]) -> bool { v.len() == 0 }".into(),
+            ..ARBITRARY_FIX_REQUEST
+        };
+
+        let response = coordinator.fix(req).with_timeout().await.unwrap();
+
+        assert!(response.success, "stderr: {}", response.stderr);
+        assert_eq!(
+            response.code.assume_single(),
+            "pub fn empty(v: &[u8]) -> bool { v.is_empty() }",
+        );
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn fix_multiple_files() -> Result<()> {
+        let coordinator = new_coordinator();
+
+        let req = FixRequest {
+            crate_type: CrateType::Binary,
+            code: kvs! {
+                "src/main.rs" => "fn main() { let mut a = playground::one(); a; }",
+                "src/lib.rs" => "pub fn one() -> u8 { let mut a = 1; a }",
+            }
+            .collect(),
+            ..ARBITRARY_FIX_REQUEST
+        };
+
+        let response = coordinator.fix(req).with_timeout().await.unwrap();
+
+        assert!(response.success, "stderr: {}", response.stderr);
+
+        let content_for = |name| {
+            &*response
+                .code
+                .assume_multiple()
+                .iter()
+                .find(|cf| cf.name == name)
+                .unwrap()
+                .content
+        };
+
+        assert_eq!(
+            content_for("src/main.rs"),
+            "fn main() { let a = playground::one(); a; }",
+        );
+        assert_eq!(
+            content_for("src/lib.rs"),
+            "pub fn one() -> u8 { let a = 1; a }",
+        );
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
+    const ARBITRARY_MIGRATE_REQUEST: MigrateRequest = MigrateRequest {
+        channel: Channel::Stable,
+        crate_type: CrateType::Library(LibraryType::Rlib),
+        edition: Edition::Rust2015,
+        code: Code::new(),
+    };
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn migrate() -> Result<()> {
+        let coordinator = new_coordinator();
+
+        let req = MigrateRequest {
+            code: "pub fn unused() { let async = 1; }".into(),
+            ..ARBITRARY_MIGRATE_REQUEST
+        };
+
+        let response = coordinator.migrate(req).with_timeout().await.unwrap();
+
+        assert!(response.success, "stderr: {}", response.stderr);
+        assert_eq!(response.edition, Edition::Rust2018);
+        assert!(
+            response.stdout.lines().all(|l| !l.starts_with('{')),
+            "Cargo messages leaked into stdout: {}",
+            response.stdout,
+        );
+        assert_eq!(
+            response.code.assume_single(),
+            "pub fn unused() { let r#async = 1; }",
+        );
+
+        let container = coordinator.select_channel(Channel::Stable).await?;
+        let cargo_toml = ModifyCargoToml::read(&container.commander)
+            .with_timeout()
+            .await
+            .unwrap();
+        assert_eq!(cargo_toml["package"]["edition"].as_str(), Some("2018"));
+
+        let [lint] = &response.lints[..] else {
+            panic!("Expected one lint, got {:?}", response.lints);
+        };
+        assert_eq!(
+            lint.code.as_ref().map(|c| &*c.code),
+            Some("keyword_idents_2018"),
+        );
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[snafu::report]
+    async fn migrate_latest_edition() -> Result<()> {
+        let coordinator = new_coordinator();
+
+        let req = MigrateRequest {
+            edition: Edition::Rust2024,
+            ..ARBITRARY_MIGRATE_REQUEST
+        };
+
+        let response = coordinator.migrate(req).with_timeout().await;
+
+        assert!(
+            matches!(response, Err(MigrateError::AlreadyLatestEdition { .. })),
+            "{response:?}",
+        );
+
+        coordinator.shutdown().await?;
+
+        Ok(())
+    }
+
     const ARBITRARY_MIRI_REQUEST: MiriRequest = MiriRequest {
         channel: Channel::Nightly,
         crate_type: CrateType::Binary,
         edition: Edition::Rust2021,
         tests: false,
         aliasing_model: AliasingModel::Stacked,
+        options: MiriOptions::NONE,
         code: Code::new(),
     };
 
+    #[test]
+    fn miri_options_flags() {
+        let miriflags = |options| {
+            let req = MiriRequest {
+                options,
+                ..ARBITRARY_MIRI_REQUEST
+            };
+            let ExecuteCommandRequest { envs, .. } = req.execute_cargo_request();
+            envs["MIRIFLAGS"].clone()
+        };
+
+        assert_eq!(miriflags(MiriOptions::NONE), "-Zmiri-disable-isolation");
+
+        assert_eq!(
+            miriflags(MiriOptions {
+                many_seeds: Some(0..16),
+                strict_provenance: true,
+                ignore_leaks: true,
+                preemption_rate: Some(0.0),
+                isolation: true,
+                symbolic_alignment_check: true,
+                ..MiriOptions::NONE
+            }),
+            "-Zmiri-many-seeds=0..16 -Zmiri-strict-provenance -Zmiri-ignore-leaks \
+             -Zmiri-preemption-rate=0 -Zmiri-symbolic-alignment-check",
+        );
+
+        assert_contains!(
+            miriflags(MiriOptions {
+                seed: Some(42),
+                ..MiriOptions::NONE
+            }),
+            "-Zmiri-seed=42"
+        );
+    }
+
+    #[test]
+    fn miri_options_validation() {
+        let valid = MiriOptions {
+            many_seeds: Some(10..10 + MiriOptions::MAX_MANY_SEEDS),
+            preemption_rate: Some(1.0),
+            ..MiriOptions::NONE
+        };
+        assert!(valid.validate().is_ok());
+
+        let invalid = [
+            MiriOptions {
+                seed: Some(1),
+                many_seeds: Some(0..2),
+                ..MiriOptions::NONE
+            },
+            MiriOptions {
+                many_seeds: Some(5..5),
+                ..MiriOptions::NONE
+            },
+            MiriOptions {
+                many_seeds: Some(0..MiriOptions::MAX_MANY_SEEDS + 1),
+                ..MiriOptions::NONE
+            },
+            MiriOptions {
+                preemption_rate: Some(1.5),
+                ..MiriOptions::NONE
+            },
+            MiriOptions {
+                preemption_rate: Some(f64::NAN),
+                ..MiriOptions::NONE
+            },
+        ];
+        for options in invalid {
+            assert!(options.validate().is_err(), "{options:?}");
+        }
+    }
+
+    #[test]
+    fn bench_request_validation() {
+        let valid = BenchRequest {
+            channel: Channel::Nightly,
+            crate_type: CrateType::Library(LibraryType::Lib),
+            edition: Edition::Rust2021,
+            filter: Some("fast".into()),
+            time_limit: BenchRequest::MAX_TIME_LIMIT,
+            code: "".into(),
+        };
+        assert!(valid.validate().is_ok());
+
+        let invalid = [
+            BenchRequest {
+                filter: Some("--list".into()),
+                ..valid.clone()
+            },
+            BenchRequest {
+                time_limit: Duration::ZERO,
+                ..valid.clone()
+            },
+            BenchRequest {
+                time_limit: BenchRequest::MAX_TIME_LIMIT + Duration::from_secs(1),
+                ..valid.clone()
+            },
+        ];
+        for request in invalid {
+            assert!(request.validate().is_err(), "{request:?}");
+        }
+    }
+
+    #[test]
+    fn test_options_validation() {
+        let valid = TestOptions {
+            filter: Some("tests::a-b".into()),
+            ..TestOptions::NONE
+        };
+        assert!(valid.validate().is_ok());
+
+        for filter in ["--list", "--exact", "-Zunstable-options"] {
+            let invalid = TestOptions {
+                filter: Some(filter.into()),
+                ..TestOptions::NONE
+            };
+            assert!(invalid.validate().is_err(), "{filter}");
+        }
+    }
+
     #[tokio::test]
     #[snafu::report]
     async fn miri() -> Result<()> {
@@ -4452,6 +9594,11 @@ mod tests {
             edition: Edition::Rust2021,
             tests: false,
             backtrace: false,
+            test_options: TestOptions::NONE,
+            extra: ExtraOptions::NONE,
+            json_diagnostics: false,
+            sanitizer: None,
+            coverage: false,
             code: "pub fn alpha() {}".into(),
         };
 
@@ -4466,12 +9613,16 @@ mod tests {
         // Create a lib.rs file
         let req = CompileRequest {
             target: CompileTarget::LlvmIr,
+            target_triple: None,
             channel: req.channel,
             mode: req.mode,
             edition: req.edition,
             crate_type: CrateType::Library(LibraryType::Rlib),
             tests: req.tests,
             backtrace: req.backtrace,
+            extra: req.extra,
+            json_diagnostics: false,
+            export_artifact: false,
             code: "pub fn beta() {}".into(),
         };
 
@@ -4502,6 +9653,11 @@ mod tests {
             crate_type: CrateType::Binary,
             tests: false,
             backtrace: false,
+            test_options: TestOptions::NONE,
+            extra: ExtraOptions::NONE,
+            json_diagnostics: false,
+            sanitizer: None,
+            coverage: false,
             code: r#"fn main() { println!("hello") }"#.into(),
         };
 
@@ -4530,6 +9686,11 @@ mod tests {
             crate_type: CrateType::Binary,
             tests: false,
             backtrace: false,
+            test_options: TestOptions::NONE,
+            extra: ExtraOptions::NONE,
+            json_diagnostics: false,
+            sanitizer: None,
+            coverage: false,
             code: r#"fn main() { std::process::abort(); }"#.into(),
         };
 
@@ -4551,6 +9712,11 @@ mod tests {
             crate_type: CrateType::Binary,
             tests: false,
             backtrace: false,
+            test_options: TestOptions::NONE,
+            extra: ExtraOptions::NONE,
+            json_diagnostics: false,
+            sanitizer: None,
+            coverage: false,
             code: Code::new(),
         }
     }
@@ -4667,9 +9833,11 @@ mod tests {
             ..new_execution_limited_request()
         };
 
-        let err = coordinator.execute(req).with_timeout().await.unwrap_err();
-        let err = snafu::ChainCompat::new(&err).last().unwrap();
-        assert_contains!(err.to_string(), "bytes of output, exiting");
+        let response = coordinator.execute(req).with_timeout().await.unwrap();
+
+        assert!(!response.success, "stderr: {}", response.stderr);
+        assert!(response.stdout.len() <= ResourceProfile::default().output_bytes);
+        assert_contains!(response.stderr, "Output truncated after");
 
         coordinator.shutdown().await?;
 
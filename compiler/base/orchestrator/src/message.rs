@@ -2,11 +2,25 @@ use serde::{Deserialize, Serialize};
 use std::{
     collections::{HashMap, VecDeque},
     fmt,
+    time::{Duration, SystemTime},
 };
 
 pub type JobId = u64;
 pub type Path = String;
 
+/// Increases whenever a message is added, removed, or encoded
+/// differently. Checked by the [`Hello`][] handshake.
+pub const PROTOCOL_VERSION: u32 = 4;
+
+/// The oldest protocol version that the other side may speak. Only
+/// increase this when a message is removed or encoded differently;
+/// new messages are only sent to workers whose [`Capabilities`][]
+/// list them.
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+/// The most data a single chunked read or write moves.
+pub const MAX_CHUNK_LEN: u32 = 1024 * 1024;
+
 macro_rules! impl_narrow_to_broad {
     ($enum_type:ident, $($variant_name:ident => $variant_type:ident),* $(,)?) => {
         $(
@@ -22,7 +36,8 @@ macro_rules! impl_narrow_to_broad {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Multiplexed<T>(pub JobId, pub T);
 
-#[derive(Debug, Serialize, Deserialize, strum_macros::AsRefStr)]
+/// The variant names are reported by [`Capabilities`][].
+#[derive(Debug, Serialize, Deserialize, strum_macros::AsRefStr, strum_macros::VariantNames)]
 pub enum CoordinatorMessage {
     WriteFile(WriteFileRequest),
     DeleteFile(DeleteFileRequest),
@@ -31,6 +46,13 @@ pub enum CoordinatorMessage {
     StdinPacket(String),
     StdinClose,
     Kill,
+    /// Must stay in place so that workers of any version can decode
+    /// it or fail to.
+    Hello(Hello),
+    ListDirectory(ListDirectoryRequest),
+    StatFile(StatFileRequest),
+    ReadFileChunk(ReadFileChunkRequest),
+    WriteFileChunk(WriteFileChunkRequest),
 }
 
 impl_narrow_to_broad!(
@@ -39,6 +61,11 @@ impl_narrow_to_broad!(
     DeleteFile => DeleteFileRequest,
     ReadFile => ReadFileRequest,
     ExecuteCommand => ExecuteCommandRequest,
+    Hello => Hello,
+    ListDirectory => ListDirectoryRequest,
+    StatFile => StatFileRequest,
+    ReadFileChunk => ReadFileChunkRequest,
+    WriteFileChunk => WriteFileChunkRequest,
 );
 
 #[derive(Debug, Serialize, Deserialize, strum_macros::AsRefStr)]
@@ -50,9 +77,17 @@ pub enum WorkerMessage {
     StdoutPacket(String),
     StderrPacket(String),
     CommandStatistics(CommandStatistics),
+    OutputTruncated(OutputTruncated),
     /// Vestigial; remove after a while
     Error(SerializedError),
     Error2(SerializedError2),
+    /// Must stay in place so that coordinators of any version can
+    /// decode it.
+    Capabilities(Capabilities),
+    ListDirectory(ListDirectoryResponse),
+    StatFile(StatFileResponse),
+    ReadFileChunk(ReadFileChunkResponse),
+    WriteFileChunk(WriteFileChunkResponse),
 }
 
 macro_rules! impl_broad_to_narrow_with_error {
@@ -81,6 +116,12 @@ impl_narrow_to_broad!(
     ReadFile => ReadFileResponse,
     ExecuteCommand => ExecuteCommandResponse,
     CommandStatistics => CommandStatistics,
+    OutputTruncated => OutputTruncated,
+    Capabilities => Capabilities,
+    ListDirectory => ListDirectoryResponse,
+    StatFile => StatFileResponse,
+    ReadFileChunk => ReadFileChunkResponse,
+    WriteFileChunk => WriteFileChunkResponse,
 );
 
 impl_broad_to_narrow_with_error!(
@@ -89,8 +130,45 @@ impl_broad_to_narrow_with_error!(
     DeleteFile => DeleteFileResponse,
     ReadFile => ReadFileResponse,
     ExecuteCommand => ExecuteCommandResponse,
+    Capabilities => Capabilities,
+    ListDirectory => ListDirectoryResponse,
+    StatFile => StatFileResponse,
+    ReadFileChunk => ReadFileChunkResponse,
+    WriteFileChunk => WriteFileChunkResponse,
 );
 
+/// The first message sent to a new worker. The fields may never
+/// change.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Hello {
+    pub protocol_version: u32,
+}
+
+impl Hello {
+    pub fn new() -> Self {
+        Self {
+            protocol_version: PROTOCOL_VERSION,
+        }
+    }
+}
+
+/// The worker's response to [`Hello`][]. The fields may never
+/// change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Capabilities {
+    pub protocol_version: u32,
+    /// Identifies the worker binary.
+    pub worker_build: String,
+    /// The [`CoordinatorMessage`][] kinds that the worker handles.
+    pub message_kinds: Vec<String>,
+}
+
+impl Capabilities {
+    pub fn supports(&self, kind: &str) -> bool {
+        self.message_kinds.iter().any(|k| k == kind)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WriteFileRequest {
     pub path: Path,
@@ -100,6 +178,25 @@ pub struct WriteFileRequest {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WriteFileResponse(pub ());
 
+/// Writes part of a file so that large files do not monopolize the
+/// connection to the worker.
+///
+/// The chunks are collected in a staging file beside `path`. The chunk
+/// at offset zero creates the staging file and must complete before
+/// any other chunk is sent. The remaining chunks may be sent
+/// concurrently, except for the last, which moves the staging file to
+/// `path` and must be sent after all others have completed.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WriteFileChunkRequest {
+    pub path: Path,
+    pub offset: u64,
+    pub data: Vec<u8>,
+    pub last: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WriteFileChunkResponse(pub ());
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DeleteFileRequest {
     pub path: Path,
@@ -116,12 +213,72 @@ pub struct ReadFileRequest {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ReadFileResponse(pub Vec<u8>);
 
+/// Reads part of a file so that large files do not monopolize the
+/// connection to the worker.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReadFileChunkRequest {
+    pub path: Path,
+    pub offset: u64,
+    /// Capped at [`MAX_CHUNK_LEN`][].
+    pub len: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReadFileChunkResponse {
+    pub data: Vec<u8>,
+    /// No data remains after this chunk. Always set when fewer bytes
+    /// than requested are returned.
+    pub last: bool,
+}
+
+/// An empty path lists the project directory.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListDirectoryRequest {
+    pub path: Path,
+}
+
+/// Sorted by name. Symlinks are not followed.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListDirectoryResponse(pub Vec<DirectoryEntry>);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryEntry {
+    pub name: String,
+    pub stat: FileStat,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatFileRequest {
+    pub path: Path,
+}
+
+/// `None` when the file does not exist. Symlinks are not followed.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatFileResponse(pub Option<FileStat>);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileStat {
+    pub kind: FileKind,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FileKind {
+    File,
+    Directory,
+    Symlink,
+    Other,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ExecuteCommandRequest {
     pub cmd: String,
     pub args: Vec<String>,
     pub envs: HashMap<String, String>,
-    pub cwd: Option<String>, // None means in project direcotry.
+    pub cwd: Option<String>,               // None means in project direcotry.
+    pub output_limit: Option<OutputLimit>, // None means the worker's default.
+    pub deadline: Option<Duration>,        // Relative to when the process starts.
 }
 
 impl ExecuteCommandRequest {
@@ -134,10 +291,22 @@ impl ExecuteCommandRequest {
             args: args.into_iter().map(Into::into).collect(),
             envs: Default::default(),
             cwd: None,
+            output_limit: None,
+            deadline: None,
         }
     }
 }
 
+/// How much output a process may write to stdout and stderr combined
+/// before the rest is discarded.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputLimit {
+    pub bytes: usize,
+    /// Kill the process once the limit has been reached instead of
+    /// letting it run to completion.
+    pub kill: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ExecuteCommandResponse {
     pub success: bool,
@@ -150,6 +319,11 @@ pub struct CommandStatistics {
     pub resident_set_size_bytes: u64,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OutputTruncated {
+    pub output_limit: OutputLimit,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SerializedError(pub String);
 
@@ -221,3 +395,23 @@ impl OneToOneResponse for ReadFileRequest {
 impl OneToOneResponse for ExecuteCommandRequest {
     type Response = ExecuteCommandResponse;
 }
+
+impl OneToOneResponse for Hello {
+    type Response = Capabilities;
+}
+
+impl OneToOneResponse for ListDirectoryRequest {
+    type Response = ListDirectoryResponse;
+}
+
+impl OneToOneResponse for StatFileRequest {
+    type Response = StatFileResponse;
+}
+
+impl OneToOneResponse for ReadFileChunkRequest {
+    type Response = ReadFileChunkResponse;
+}
+
+impl OneToOneResponse for WriteFileChunkRequest {
+    type Response = WriteFileChunkResponse;
+}
@@ -31,18 +31,24 @@
 //!   - [`tokio::process::ChildStderr`][]
 //!
 
-use futures::FutureExt as _;
+use futures::{future, FutureExt as _};
 use snafu::prelude::*;
 use std::{
-    collections::HashMap,
+    collections::{BTreeSet, HashMap},
     io,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
     pin::pin,
     process::{ExitStatus, Stdio},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
 };
+use strum::VariantNames as _;
 use tokio::{
-    fs,
-    io::{AsyncRead, AsyncReadExt, AsyncWriteExt},
+    fs::{self, File, OpenOptions},
+    io::{AsyncRead, AsyncReadExt, AsyncSeekExt, AsyncWriteExt},
     process::{Child, ChildStderr, ChildStdin, ChildStdout, Command},
     select,
     sync::mpsc,
@@ -53,14 +59,25 @@ use tokio_util::sync::{CancellationToken, DropGuard};
 use crate::{
     bincode_input_closed,
     message::{
-        CoordinatorMessage, DeleteFileRequest, DeleteFileResponse, ExecuteCommandRequest,
-        ExecuteCommandResponse, JobId, Multiplexed, ReadFileRequest, ReadFileResponse,
-        SerializedError2, WorkerMessage, WriteFileRequest, WriteFileResponse,
+        Capabilities, CoordinatorMessage, DeleteFileRequest, DeleteFileResponse, DirectoryEntry,
+        ExecuteCommandRequest, ExecuteCommandResponse, FileKind, FileStat, JobId,
+        ListDirectoryRequest, ListDirectoryResponse, Multiplexed, OutputLimit, OutputTruncated,
+        ReadFileChunkRequest, ReadFileChunkResponse, ReadFileRequest, ReadFileResponse,
+        SerializedError2, StatFileRequest, StatFileResponse, WorkerMessage, WriteFileChunkRequest,
+        WriteFileChunkResponse, WriteFileRequest, WriteFileResponse, MAX_CHUNK_LEN,
+        MIN_PROTOCOL_VERSION, PROTOCOL_VERSION,
     },
     DropErrorDetailsExt as _, TaskAbortExt as _,
 };
 
-pub async fn listen(project_dir: impl Into<PathBuf>) -> Result<(), Error> {
+/// The default maximum number of bytes a process may write to stdout
+/// and stderr combined.
+pub const DEFAULT_OUTPUT_BYTE_LIMIT: usize = 640 * 1024;
+
+pub async fn listen(
+    project_dir: impl Into<PathBuf>,
+    output_byte_limit: usize,
+) -> Result<(), Error> {
     let project_dir = project_dir.into();
 
     let (coordinator_msg_tx, coordinator_msg_rx) = mpsc::channel(8);
@@ -68,8 +85,12 @@ pub async fn listen(project_dir: impl Into<PathBuf>) -> Result<(), Error> {
     let mut io_tasks = spawn_io_queue(coordinator_msg_tx, worker_msg_rx);
 
     let (process_tx, process_rx) = mpsc::channel(8);
-    let process_task =
-        tokio::spawn(manage_processes(process_rx, project_dir.clone())).abort_on_drop();
+    let process_task = tokio::spawn(manage_processes(
+        process_rx,
+        project_dir.clone(),
+        output_byte_limit,
+    ))
+    .abort_on_drop();
 
     let handler_task = tokio::spawn(handle_coordinator_message(
         coordinator_msg_rx,
@@ -128,6 +149,7 @@ async fn handle_coordinator_message(
     use handle_coordinator_message_error::*;
 
     let mut tasks = JoinSet::new();
+    let staging_files = StagingFiles::default();
 
     loop {
         select! {
@@ -176,6 +198,31 @@ async fn handle_coordinator_message(
                         });
                     }
 
+                    CoordinatorMessage::WriteFileChunk(req) => {
+                        let project_dir = project_dir.clone();
+                        let staging_files = staging_files.clone();
+                        let worker_msg_tx = worker_msg_tx();
+
+                        tasks.spawn(async move {
+                            worker_msg_tx
+                                .send(handle_write_file_chunk(req, project_dir, staging_files).await)
+                                .await
+                                .context(UnableToSendWriteFileChunkResponseSnafu)
+                        });
+                    }
+
+                    CoordinatorMessage::ReadFileChunk(req) => {
+                        let project_dir = project_dir.clone();
+                        let worker_msg_tx = worker_msg_tx();
+
+                        tasks.spawn(async move {
+                            worker_msg_tx
+                                .send(handle_read_file_chunk(req, project_dir).await)
+                                .await
+                                .context(UnableToSendReadFileChunkResponseSnafu)
+                        });
+                    }
+
                     CoordinatorMessage::ExecuteCommand(req) => {
                         process_tx
                             .send(Multiplexed(job_id, ProcessCommand::Start(req, worker_msg_tx())))
@@ -207,6 +254,45 @@ async fn handle_coordinator_message(
                         .drop_error_details()
                         .context(UnableToSendKillSnafu)?;
                     }
+
+                    CoordinatorMessage::ListDirectory(req) => {
+                        let project_dir = project_dir.clone();
+                        let worker_msg_tx = worker_msg_tx();
+
+                        tasks.spawn(async move {
+                            worker_msg_tx
+                                .send(handle_list_directory(req, project_dir).await)
+                                .await
+                                .context(UnableToSendListDirectoryResponseSnafu)
+                        });
+                    }
+
+                    CoordinatorMessage::StatFile(req) => {
+                        let project_dir = project_dir.clone();
+                        let worker_msg_tx = worker_msg_tx();
+
+                        tasks.spawn(async move {
+                            worker_msg_tx
+                                .send(handle_stat_file(req, project_dir).await)
+                                .await
+                                .context(UnableToSendStatFileResponseSnafu)
+                        });
+                    }
+
+                    // The coordinator also checks our version
+                    CoordinatorMessage::Hello(hello) => {
+                        let coordinator = hello.protocol_version;
+                        let capabilities = if coordinator >= MIN_PROTOCOL_VERSION {
+                            Ok(capabilities())
+                        } else {
+                            UnsupportedProtocolSnafu { coordinator }.fail()
+                        };
+
+                        worker_msg_tx()
+                            .send(capabilities)
+                            .await
+                            .context(UnableToSendCapabilitiesSnafu)?;
+                    }
                 }
             }
 
@@ -216,6 +302,9 @@ async fn handle_coordinator_message(
         }
     }
 
+    // The coordinator is gone, so no transfer will be completed
+    staging_files.remove_all().await;
+
     Ok(())
 }
 
@@ -231,6 +320,21 @@ pub enum HandleCoordinatorMessageError {
     #[snafu(display("Could not send the read command response to the coordinator"))]
     UnableToSendReadFileResponse { source: MultiplexingSenderError },
 
+    #[snafu(display("Could not send the write file chunk response to the coordinator"))]
+    UnableToSendWriteFileChunkResponse { source: MultiplexingSenderError },
+
+    #[snafu(display("Could not send the read file chunk response to the coordinator"))]
+    UnableToSendReadFileChunkResponse { source: MultiplexingSenderError },
+
+    #[snafu(display("Could not send the list directory response to the coordinator"))]
+    UnableToSendListDirectoryResponse { source: MultiplexingSenderError },
+
+    #[snafu(display("Could not send the stat file response to the coordinator"))]
+    UnableToSendStatFileResponse { source: MultiplexingSenderError },
+
+    #[snafu(display("Could not send the capabilities to the coordinator"))]
+    UnableToSendCapabilities { source: MultiplexingSenderError },
+
     #[snafu(display("Failed to send command execution request to the command task"))]
     UnableToSendCommandExecutionRequest { source: mpsc::error::SendError<()> },
 
@@ -247,6 +351,32 @@ pub enum HandleCoordinatorMessageError {
     TaskPanicked { source: tokio::task::JoinError },
 }
 
+/// Identifies the worker binary. Set `PLAYGROUND_WORKER_BUILD` when
+/// compiling to include details such as the commit.
+pub const WORKER_BUILD: &str = match option_env!("PLAYGROUND_WORKER_BUILD") {
+    Some(build) => build,
+    None => env!("CARGO_PKG_VERSION"),
+};
+
+fn capabilities() -> Capabilities {
+    Capabilities {
+        protocol_version: PROTOCOL_VERSION,
+        worker_build: WORKER_BUILD.to_owned(),
+        message_kinds: CoordinatorMessage::VARIANTS
+            .iter()
+            .map(|&k| k.to_owned())
+            .collect(),
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display(
+    "The coordinator uses protocol version {coordinator} but version {MIN_PROTOCOL_VERSION} or newer is required"
+))]
+struct UnsupportedProtocolError {
+    coordinator: u32,
+}
+
 #[derive(Debug, Clone)]
 struct MultiplexingSender {
     job_id: JobId,
@@ -300,7 +430,9 @@ async fn handle_write_file(
 ) -> Result<WriteFileResponse, WriteFileError> {
     use write_file_error::*;
 
-    let path = parse_working_dir(Some(req.path), project_dir);
+    let path = confine_path(&req.path, &project_dir, Resolve::File)
+        .await
+        .context(PathNotAllowedSnafu { path: req.path })?;
 
     // Create intermediate directories.
     if let Some(parent_dir) = path.parent() {
@@ -319,6 +451,12 @@ async fn handle_write_file(
 #[derive(Debug, Snafu)]
 #[snafu(module)]
 pub enum WriteFileError {
+    #[snafu(display("The path `{path}` is not allowed"))]
+    PathNotAllowed {
+        source: ConfinePathError,
+        path: String,
+    },
+
     #[snafu(display("Failed to create parent directory {}", parent_dir.display()))]
     UnableToCreateDir {
         source: std::io::Error,
@@ -332,13 +470,176 @@ pub enum WriteFileError {
     },
 }
 
+async fn handle_write_file_chunk(
+    req: WriteFileChunkRequest,
+    project_dir: PathBuf,
+    staging_files: StagingFiles,
+) -> Result<WriteFileChunkResponse, WriteFileChunkError> {
+    use write_file_chunk_error::*;
+
+    let path = confine_path(&req.path, &project_dir, Resolve::File)
+        .await
+        .context(PathNotAllowedSnafu { path: &req.path })?;
+
+    // The user's code may have put a symlink in its place
+    let staging = staging_path(&path);
+    let relative_staging = staging.strip_prefix(&project_dir).unwrap_or(&staging);
+    let staging = confine_path(
+        &relative_staging.to_string_lossy(),
+        &project_dir,
+        Resolve::Parent,
+    )
+    .await
+    .context(PathNotAllowedSnafu { path: &req.path })?;
+
+    let written = write_file_chunk(&req, &path, &staging).await;
+
+    match written {
+        Ok(()) if req.last => staging_files.remove(&staging),
+        Ok(()) => staging_files.insert(staging),
+        Err(_) => {
+            fs::remove_file(&staging).await.ok(/* It may not have been created */);
+            staging_files.remove(&staging);
+        }
+    }
+
+    written.map(WriteFileChunkResponse)
+}
+
+async fn write_file_chunk(
+    req: &WriteFileChunkRequest,
+    path: &Path,
+    staging: &Path,
+) -> Result<(), WriteFileChunkError> {
+    use write_file_chunk_error::*;
+
+    let mut options = OpenOptions::new();
+    options.write(true).custom_flags(libc::O_NOFOLLOW);
+
+    let mut file = if req.offset == 0 {
+        // Create intermediate directories.
+        if let Some(parent_dir) = path.parent() {
+            fs::create_dir_all(parent_dir)
+                .await
+                .context(UnableToCreateDirSnafu { parent_dir })?;
+        }
+
+        // Start over from a previous, abandoned transfer
+        match fs::remove_file(staging).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e).context(UnableToOpenFileSnafu { path: staging }),
+        }
+
+        options
+            .create_new(true)
+            .open(staging)
+            .await
+            .context(UnableToOpenFileSnafu { path: staging })?
+    } else {
+        options
+            .open(staging)
+            .await
+            .context(UnableToOpenFileSnafu { path: staging })?
+    };
+
+    file.seek(io::SeekFrom::Start(req.offset))
+        .await
+        .context(UnableToWriteFileSnafu { path: staging })?;
+    file.write_all(&req.data)
+        .await
+        .context(UnableToWriteFileSnafu { path: staging })?;
+    file.flush()
+        .await
+        .context(UnableToWriteFileSnafu { path: staging })?;
+
+    if req.last {
+        fs::rename(staging, path)
+            .await
+            .context(UnableToMoveFileSnafu { path })?;
+    }
+
+    Ok(())
+}
+
+/// A hidden sibling of the file so that partially written files are
+/// never used.
+fn staging_path(path: &Path) -> PathBuf {
+    let mut name = std::ffi::OsString::from(".");
+    name.push(path.file_name().unwrap_or_default());
+    name.push(".partial");
+    path.with_file_name(name)
+}
+
+/// The staging files of chunked writes that are still in progress.
+#[derive(Debug, Clone, Default)]
+struct StagingFiles(Arc<Mutex<BTreeSet<PathBuf>>>);
+
+impl StagingFiles {
+    fn insert(&self, path: PathBuf) {
+        self.lock().insert(path);
+    }
+
+    fn remove(&self, path: &Path) {
+        self.lock().remove(path);
+    }
+
+    async fn remove_all(&self) {
+        let paths = std::mem::take(&mut *self.lock());
+        for path in paths {
+            fs::remove_file(path).await.ok(/* The file may have been deleted by the user's code */);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeSet<PathBuf>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+pub enum WriteFileChunkError {
+    #[snafu(display("The path `{path}` is not allowed"))]
+    PathNotAllowed {
+        source: ConfinePathError,
+        path: String,
+    },
+
+    #[snafu(display("Failed to create parent directory {}", parent_dir.display()))]
+    UnableToCreateDir {
+        source: std::io::Error,
+        parent_dir: PathBuf,
+    },
+
+    #[snafu(display("Failed to open file {}", path.display()))]
+    UnableToOpenFile {
+        source: std::io::Error,
+        path: PathBuf,
+    },
+
+    #[snafu(display("Failed to write file {}", path.display()))]
+    UnableToWriteFile {
+        source: std::io::Error,
+        path: PathBuf,
+    },
+
+    #[snafu(display("Failed to move the completed file to {}", path.display()))]
+    UnableToMoveFile {
+        source: std::io::Error,
+        path: PathBuf,
+    },
+}
+
 async fn handle_delete_file(
     req: DeleteFileRequest,
     project_dir: PathBuf,
 ) -> Result<DeleteFileResponse, DeleteFileError> {
     use delete_file_error::*;
 
-    let path = parse_working_dir(Some(req.path), project_dir);
+    // Deleting a symlink doesn't touch what it points to
+    let path = confine_path(&req.path, &project_dir, Resolve::Parent)
+        .await
+        .context(PathNotAllowedSnafu { path: req.path })?;
 
     let r = match fs::remove_file(&path).await {
         Ok(()) => Ok(()),
@@ -353,6 +654,12 @@ async fn handle_delete_file(
 #[derive(Debug, Snafu)]
 #[snafu(module)]
 pub enum DeleteFileError {
+    #[snafu(display("The path `{path}` is not allowed"))]
+    PathNotAllowed {
+        source: ConfinePathError,
+        path: String,
+    },
+
     #[snafu(display("Failed to delete file {}", path.display()))]
     UnableToDeleteFile {
         source: std::io::Error,
@@ -366,7 +673,9 @@ async fn handle_read_file(
 ) -> Result<ReadFileResponse, ReadFileError> {
     use read_file_error::*;
 
-    let path = parse_working_dir(Some(req.path), project_dir);
+    let path = confine_path(&req.path, &project_dir, Resolve::File)
+        .await
+        .context(PathNotAllowedSnafu { path: req.path })?;
 
     let content = fs::read(&path)
         .await
@@ -378,6 +687,12 @@ async fn handle_read_file(
 #[derive(Debug, Snafu)]
 #[snafu(module)]
 pub enum ReadFileError {
+    #[snafu(display("The path `{path}` is not allowed"))]
+    PathNotAllowed {
+        source: ConfinePathError,
+        path: String,
+    },
+
     #[snafu(display("Failed to read file {}", path.display()))]
     UnableToReadFile {
         source: std::io::Error,
@@ -385,6 +700,282 @@ pub enum ReadFileError {
     },
 }
 
+async fn handle_read_file_chunk(
+    req: ReadFileChunkRequest,
+    project_dir: PathBuf,
+) -> Result<ReadFileChunkResponse, ReadFileChunkError> {
+    use read_file_chunk_error::*;
+
+    let path = confine_path(&req.path, &project_dir, Resolve::File)
+        .await
+        .context(PathNotAllowedSnafu { path: req.path })?;
+
+    let mut file = File::open(&path)
+        .await
+        .context(UnableToOpenFileSnafu { path: &path })?;
+    let size = file
+        .metadata()
+        .await
+        .context(UnableToReadFileSnafu { path: &path })?
+        .len();
+
+    file.seek(io::SeekFrom::Start(req.offset))
+        .await
+        .context(UnableToReadFileSnafu { path: &path })?;
+
+    let len = req.len.min(MAX_CHUNK_LEN);
+    let mut data = Vec::with_capacity(usize::try_from(len).unwrap_or(usize::MAX));
+    file.take(len.into())
+        .read_to_end(&mut data)
+        .await
+        .context(UnableToReadFileSnafu { path })?;
+
+    let end = req.offset.saturating_add(data.len() as u64);
+    let last = data.len() < len as usize || end >= size;
+
+    Ok(ReadFileChunkResponse { data, last })
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+pub enum ReadFileChunkError {
+    #[snafu(display("The path `{path}` is not allowed"))]
+    PathNotAllowed {
+        source: ConfinePathError,
+        path: String,
+    },
+
+    #[snafu(display("Failed to open file {}", path.display()))]
+    UnableToOpenFile {
+        source: std::io::Error,
+        path: PathBuf,
+    },
+
+    #[snafu(display("Failed to read file {}", path.display()))]
+    UnableToReadFile {
+        source: std::io::Error,
+        path: PathBuf,
+    },
+}
+
+async fn handle_list_directory(
+    req: ListDirectoryRequest,
+    project_dir: PathBuf,
+) -> Result<ListDirectoryResponse, ListDirectoryError> {
+    use list_directory_error::*;
+
+    let path = confine_path(&req.path, &project_dir, Resolve::Directory)
+        .await
+        .context(PathNotAllowedSnafu { path: req.path })?;
+
+    let mut dir = fs::read_dir(&path)
+        .await
+        .context(UnableToListDirectorySnafu { path: &path })?;
+
+    let mut entries = vec![];
+    while let Some(entry) = dir
+        .next_entry()
+        .await
+        .context(UnableToListDirectorySnafu { path: &path })?
+    {
+        let metadata = entry
+            .metadata()
+            .await
+            .context(UnableToStatSnafu { path: entry.path() })?;
+
+        entries.push(DirectoryEntry {
+            name: entry.file_name().to_string_lossy().into_owned(),
+            stat: file_stat(&metadata),
+        });
+    }
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(ListDirectoryResponse(entries))
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+pub enum ListDirectoryError {
+    #[snafu(display("The path `{path}` is not allowed"))]
+    PathNotAllowed {
+        source: ConfinePathError,
+        path: String,
+    },
+
+    #[snafu(display("Failed to list directory {}", path.display()))]
+    UnableToListDirectory {
+        source: std::io::Error,
+        path: PathBuf,
+    },
+
+    #[snafu(display("Failed to get information about {}", path.display()))]
+    UnableToStat {
+        source: std::io::Error,
+        path: PathBuf,
+    },
+}
+
+async fn handle_stat_file(
+    req: StatFileRequest,
+    project_dir: PathBuf,
+) -> Result<StatFileResponse, StatFileError> {
+    use stat_file_error::*;
+
+    let path = confine_path(&req.path, &project_dir, Resolve::Parent)
+        .await
+        .context(PathNotAllowedSnafu { path: req.path })?;
+
+    let stat = match fs::symlink_metadata(&path).await {
+        Ok(metadata) => Some(file_stat(&metadata)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e).context(UnableToStatSnafu { path }),
+    };
+
+    Ok(StatFileResponse(stat))
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+pub enum StatFileError {
+    #[snafu(display("The path `{path}` is not allowed"))]
+    PathNotAllowed {
+        source: ConfinePathError,
+        path: String,
+    },
+
+    #[snafu(display("Failed to get information about {}", path.display()))]
+    UnableToStat {
+        source: std::io::Error,
+        path: PathBuf,
+    },
+}
+
+fn file_stat(metadata: &std::fs::Metadata) -> FileStat {
+    let file_type = metadata.file_type();
+    let kind = if file_type.is_symlink() {
+        FileKind::Symlink
+    } else if file_type.is_dir() {
+        FileKind::Directory
+    } else if file_type.is_file() {
+        FileKind::File
+    } else {
+        FileKind::Other
+    };
+
+    FileStat {
+        kind,
+        size: metadata.len(),
+        modified: metadata.modified().ok(),
+    }
+}
+
+/// How much of a path is checked against symlinks that leave the
+/// project directory.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Resolve {
+    /// The file itself, for operations that follow symlinks.
+    File,
+    /// Only the directories containing the file.
+    Parent,
+    /// The directory itself, which may be the project directory.
+    Directory,
+}
+
+/// Resolves a path from the coordinator inside of the project
+/// directory.
+///
+/// File names come straight from users, so the path must be
+/// relative and may not use `..` to leave the project directory.
+/// Symlinks, such as those created by the user's code, may not point
+/// outside of the project directory either.
+async fn confine_path(
+    path: &str,
+    project_dir: &Path,
+    resolve: Resolve,
+) -> Result<PathBuf, ConfinePathError> {
+    use confine_path_error::*;
+
+    let relative = normalize_path(path)?;
+    ensure!(
+        resolve == Resolve::Directory || !relative.as_os_str().is_empty(),
+        EmptySnafu
+    );
+    let path = project_dir.join(relative);
+
+    let project_dir = fs::canonicalize(project_dir)
+        .await
+        .context(UnableToResolveSnafu { path: project_dir })?;
+
+    let checked = match resolve {
+        Resolve::File | Resolve::Directory => Some(&*path),
+        Resolve::Parent => path.parent(),
+    };
+
+    // The file or its parent directories may not exist yet
+    for ancestor in checked.into_iter().flat_map(Path::ancestors) {
+        match fs::symlink_metadata(ancestor).await {
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e).context(UnableToResolveSnafu { path: ancestor }),
+        }
+
+        // Following a dangling symlink could create a file anywhere
+        let resolved = match fs::canonicalize(ancestor).await {
+            Ok(resolved) => resolved,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return DanglingSymlinkSnafu.fail(),
+            Err(e) => return Err(e).context(UnableToResolveSnafu { path: ancestor }),
+        };
+
+        ensure!(resolved.starts_with(&project_dir), OutsideProjectSnafu);
+        break;
+    }
+
+    Ok(path)
+}
+
+/// Removes `.` and `..` components without touching the file system.
+fn normalize_path(path: &str) -> Result<PathBuf, ConfinePathError> {
+    use confine_path_error::*;
+
+    let mut normalized = PathBuf::new();
+
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(c) => normalized.push(c),
+            Component::CurDir => {}
+            Component::ParentDir => ensure!(normalized.pop(), EscapesProjectSnafu),
+            Component::RootDir | Component::Prefix(_) => return AbsoluteSnafu.fail(),
+        }
+    }
+
+    Ok(normalized)
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+pub enum ConfinePathError {
+    #[snafu(display("The path must be relative to the project directory"))]
+    Absolute,
+
+    #[snafu(display("The path leaves the project directory"))]
+    EscapesProject,
+
+    #[snafu(display("The path does not name a file"))]
+    Empty,
+
+    #[snafu(display("The path resolves to a location outside of the project directory"))]
+    OutsideProject,
+
+    #[snafu(display("The path contains a symlink to a missing file"))]
+    DanglingSymlink,
+
+    #[snafu(display("Failed to resolve {}", path.display()))]
+    UnableToResolve {
+        source: std::io::Error,
+        path: PathBuf,
+    },
+}
+
 // Current working directory defaults to project dir unless specified otherwise.
 fn parse_working_dir(cwd: Option<String>, project_path: impl Into<PathBuf>) -> PathBuf {
     let mut final_path = project_path.into();
@@ -404,6 +995,7 @@ enum ProcessCommand {
 
 struct ProcessState {
     project_path: PathBuf,
+    output_byte_limit: usize,
     processes: JoinSet<Result<(), ProcessError>>,
     stdin_senders: HashMap<JobId, mpsc::Sender<String>>,
     stdin_shutdown_tx: mpsc::Sender<JobId>,
@@ -411,9 +1003,14 @@ struct ProcessState {
 }
 
 impl ProcessState {
-    fn new(project_path: PathBuf, stdin_shutdown_tx: mpsc::Sender<JobId>) -> Self {
+    fn new(
+        project_path: PathBuf,
+        output_byte_limit: usize,
+        stdin_shutdown_tx: mpsc::Sender<JobId>,
+    ) -> Self {
         Self {
             project_path,
+            output_byte_limit,
             processes: Default::default(),
             stdin_senders: Default::default(),
             stdin_shutdown_tx,
@@ -431,6 +1028,12 @@ impl ProcessState {
 
         let token = CancellationToken::new();
 
+        let deadline = req.deadline;
+        let output_limit = req.output_limit.unwrap_or(OutputLimit {
+            bytes: self.output_byte_limit,
+            kill: true,
+        });
+
         let RunningChild {
             child,
             stdin_rx,
@@ -458,7 +1061,15 @@ impl ProcessState {
             move || stream_command_statistics(child_id, worker_msg_tx, handle)
         });
 
-        let task_set = stream_stdio(worker_msg_tx.clone(), stdin_rx, stdin, stdout, stderr);
+        let task_set = stream_stdio(
+            worker_msg_tx.clone(),
+            stdin_rx,
+            stdin,
+            stdout,
+            stderr,
+            OutputBudget::new(output_limit),
+            token.clone(),
+        );
 
         self.kill_tokens.insert(job_id, token.clone().drop_guard());
 
@@ -467,6 +1078,7 @@ impl ProcessState {
             async move {
                 let message = process_end(
                     token,
+                    deadline,
                     child,
                     task_set,
                     statistics_task,
@@ -521,11 +1133,12 @@ impl ProcessState {
 async fn manage_processes(
     mut rx: mpsc::Receiver<Multiplexed<ProcessCommand>>,
     project_path: PathBuf,
+    output_byte_limit: usize,
 ) -> Result<(), ProcessError> {
     use process_error::*;
 
     let (stdin_shutdown_tx, mut stdin_shutdown_rx) = mpsc::channel(8);
-    let mut state = ProcessState::new(project_path, stdin_shutdown_tx);
+    let mut state = ProcessState::new(project_path, output_byte_limit, stdin_shutdown_tx);
 
     loop {
         select! {
@@ -578,6 +1191,8 @@ fn process_begin(
         args,
         envs,
         cwd,
+        output_limit: _,
+        deadline: _,
     } = req;
     let mut child = Command::new(&cmd)
         .args(args)
@@ -609,6 +1224,7 @@ fn process_begin(
 
 async fn process_end(
     token: CancellationToken,
+    deadline: Option<Duration>,
     mut child: Child,
     mut task_set: JoinSet<Result<(), StdioError>>,
     statistics_task: tokio::task::JoinHandle<Result<(), CommandStatisticsError>>,
@@ -619,6 +1235,15 @@ async fn process_end(
 
     let mut cancelled = pin!(token.cancelled().fuse());
 
+    let mut expired = pin!(async {
+        match deadline {
+            Some(deadline) => tokio::time::sleep(deadline).await,
+            None => future::pending().await,
+        }
+    }
+    .fuse());
+    let mut timed_out = false;
+
     let status = loop {
         select! {
             // The user requested that the process be killed
@@ -626,6 +1251,12 @@ async fn process_end(
                 child.kill().await.context(KillChildSnafu)?;
             },
 
+            // The process ran for too long
+            () = &mut expired => {
+                timed_out = true;
+                child.kill().await.context(KillChildSnafu)?;
+            },
+
             // The process exited normally
             status = child.wait() => break status,
 
@@ -659,7 +1290,12 @@ async fn process_end(
         .context(StatisticsTaskFailedSnafu)?;
 
     let success = status.success();
-    let exit_detail = extract_exit_detail(status);
+    let exit_detail = match deadline {
+        Some(deadline) if timed_out => {
+            format!("Timed out after {} s", deadline.as_secs_f64())
+        }
+        _ => extract_exit_detail(status),
+    };
 
     Ok(ExecuteCommandResponse {
         success,
@@ -1001,6 +1637,8 @@ fn stream_stdio(
     mut stdin: ChildStdin,
     stdout: ChildStdout,
     stderr: ChildStderr,
+    budget: OutputBudget,
+    token: CancellationToken,
 ) -> JoinSet<Result<(), StdioError>> {
     use stdio_error::*;
 
@@ -1019,13 +1657,25 @@ fn stream_stdio(
     });
 
     set.spawn({
-        copy_child_output(stdout, coordinator_tx.clone(), WorkerMessage::StdoutPacket)
-            .context(CopyStdoutSnafu)
+        copy_child_output(
+            stdout,
+            coordinator_tx.clone(),
+            budget.clone(),
+            token.clone(),
+            WorkerMessage::StdoutPacket,
+        )
+        .context(CopyStdoutSnafu)
     });
 
     set.spawn({
-        copy_child_output(stderr, coordinator_tx, WorkerMessage::StderrPacket)
-            .context(CopyStderrSnafu)
+        copy_child_output(
+            stderr,
+            coordinator_tx,
+            budget,
+            token,
+            WorkerMessage::StderrPacket,
+        )
+        .context(CopyStderrSnafu)
     });
 
     set
@@ -1239,40 +1889,303 @@ mod test {
         assert_eq!(buffer.next().await.unwrap().as_deref(), None);
         assert!(buffer.reader.is_empty());
     }
+
+    #[test]
+    fn paths_are_normalized() {
+        let normalized = |p| normalize_path(p).map(|p| p.to_string_lossy().into_owned());
+
+        assert_eq!(normalized("src/main.rs").unwrap(), "src/main.rs");
+        assert_eq!(normalized("./src/../src/./lib.rs").unwrap(), "src/lib.rs");
+
+        assert_matches!(normalized("/etc/passwd"), Err(ConfinePathError::Absolute));
+        assert_matches!(normalized("../x"), Err(ConfinePathError::EscapesProject));
+        assert_matches!(
+            normalized("src/../../x"),
+            Err(ConfinePathError::EscapesProject)
+        );
+        assert_eq!(normalized("src/..").unwrap(), "");
+        assert_eq!(normalized("").unwrap(), "");
+    }
+
+    #[tokio::test]
+    async fn symlinks_may_not_leave_the_project() {
+        let project = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let project_dir = project.path();
+
+        std::os::unix::fs::symlink(outside.path(), project_dir.join("escape")).unwrap();
+        std::os::unix::fs::symlink(outside.path().join("file"), project_dir.join("link")).unwrap();
+
+        let confined = |p, r| confine_path(p, project_dir, r);
+
+        assert_matches!(
+            confined("src/..", Resolve::File).await,
+            Err(ConfinePathError::Empty)
+        );
+        assert_matches!(
+            confined("", Resolve::Parent).await,
+            Err(ConfinePathError::Empty)
+        );
+        assert!(confined("", Resolve::Directory).await.is_ok());
+        assert_matches!(
+            confined("escape", Resolve::Directory).await,
+            Err(ConfinePathError::OutsideProject)
+        );
+
+        assert_eq!(
+            confined("src/new/main.rs", Resolve::File).await.unwrap(),
+            project_dir.join("src/new/main.rs"),
+        );
+
+        assert_matches!(
+            confined("escape/file", Resolve::File).await,
+            Err(ConfinePathError::OutsideProject)
+        );
+        assert_matches!(
+            confined("escape/new/file", Resolve::Parent).await,
+            Err(ConfinePathError::OutsideProject)
+        );
+        assert_matches!(
+            confined("link", Resolve::File).await,
+            Err(ConfinePathError::DanglingSymlink)
+        );
+
+        std::fs::write(outside.path().join("file"), "").unwrap();
+        assert_matches!(
+            confined("link", Resolve::File).await,
+            Err(ConfinePathError::OutsideProject)
+        );
+
+        // The link itself is inside of the project
+        assert!(confined("link", Resolve::Parent).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn files_are_read_in_chunks() {
+        let project = tempfile::tempdir().unwrap();
+        let project_dir = project.path().to_owned();
+        std::fs::write(project_dir.join("file"), "abcdef").unwrap();
+
+        let read = |offset, len| {
+            let req = ReadFileChunkRequest {
+                path: "file".to_owned(),
+                offset,
+                len,
+            };
+            handle_read_file_chunk(req, project_dir.clone())
+        };
+
+        let chunk = read(0, 4).await.unwrap();
+        assert_eq!(chunk.data, b"abcd");
+        assert!(!chunk.last);
+
+        let chunk = read(4, 4).await.unwrap();
+        assert_eq!(chunk.data, b"ef");
+        assert!(chunk.last);
+
+        let chunk = read(2, 4).await.unwrap();
+        assert_eq!(chunk.data, b"cdef");
+        assert!(chunk.last);
+
+        let chunk = read(10, 4).await.unwrap();
+        assert!(chunk.data.is_empty());
+        assert!(chunk.last);
+    }
+
+    #[tokio::test]
+    async fn files_are_written_in_chunks() {
+        let project = tempfile::tempdir().unwrap();
+        let project_dir = project.path().to_owned();
+        let path = project_dir.join("src/file");
+        let staging_files = StagingFiles::default();
+
+        let write = |offset, data: &[u8], last| {
+            let req = WriteFileChunkRequest {
+                path: "src/file".to_owned(),
+                offset,
+                data: data.to_vec(),
+                last,
+            };
+            handle_write_file_chunk(req, project_dir.clone(), staging_files.clone())
+        };
+
+        write(0, b"abc", false).await.unwrap();
+        write(6, b"ghi", false).await.unwrap();
+        write(3, b"def", false).await.unwrap();
+        assert!(!path.exists(), "Incomplete files must not be visible");
+
+        write(9, b"j", true).await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"abcdefghij");
+        assert!(!staging_path(&path).exists());
+
+        // Chunks after the first require the staging file
+        assert_matches!(
+            write(3, b"x", true).await,
+            Err(WriteFileChunkError::UnableToOpenFile { .. })
+        );
+        assert_eq!(std::fs::read(&path).unwrap(), b"abcdefghij");
+
+        // Abandoned transfers are cleaned up
+        write(0, b"abc", false).await.unwrap();
+        assert!(staging_path(&path).exists());
+        staging_files.remove_all().await;
+        assert!(!staging_path(&path).exists());
+    }
+
+    #[tokio::test]
+    async fn chunks_are_not_written_through_symlinks() {
+        let project = tempfile::tempdir().unwrap();
+        let project_dir = project.path().to_owned();
+        let outside = tempfile::tempdir().unwrap();
+        let target = outside.path().join("target");
+        std::fs::write(&target, b"original").unwrap();
+
+        let staging = staging_path(&project_dir.join("file"));
+        std::os::unix::fs::symlink(&target, &staging).unwrap();
+
+        let write = |offset, last| {
+            let req = WriteFileChunkRequest {
+                path: "file".to_owned(),
+                offset,
+                data: b"changed".to_vec(),
+                last,
+            };
+            handle_write_file_chunk(req, project_dir.clone(), Default::default())
+        };
+
+        // A later chunk is rejected and the staging file removed
+        assert_matches!(
+            write(7, false).await,
+            Err(WriteFileChunkError::UnableToOpenFile { .. })
+        );
+        assert!(staging.symlink_metadata().is_err());
+
+        // The first chunk replaces the symlink
+        std::os::unix::fs::symlink(&target, &staging).unwrap();
+        write(0, true).await.unwrap();
+
+        assert_eq!(std::fs::read(&target).unwrap(), b"original");
+        assert_eq!(std::fs::read(project_dir.join("file")).unwrap(), b"changed");
+    }
+
+    #[tokio::test]
+    async fn output_is_truncated_at_the_limit() {
+        let (tx, mut rx) = mpsc::channel(8);
+        let coordinator_tx = MultiplexingSender { job_id: 0, tx };
+        let budget = OutputBudget::new(OutputLimit {
+            bytes: 6,
+            kill: true,
+        });
+        let token = CancellationToken::new();
+
+        let reader = FixedAsyncRead::success_exact(["abc", "d🙂", "ef"]);
+        copy_child_output(
+            reader,
+            coordinator_tx,
+            budget,
+            token.clone(),
+            WorkerMessage::StdoutPacket,
+        )
+        .await
+        .unwrap();
+
+        let mut messages = vec![];
+        while let Ok(Multiplexed(_, msg)) = rx.try_recv() {
+            messages.push(msg);
+        }
+
+        assert_matches!(&messages[..], [
+            WorkerMessage::StdoutPacket(a),
+            WorkerMessage::StdoutPacket(b),
+            WorkerMessage::OutputTruncated(_),
+        ] if a == "abc" && b == "d");
+        assert!(token.is_cancelled());
+    }
 }
 
-const OUTPUT_BYTE_LIMIT: usize = 640 * 1024;
+/// The output allowance shared by the stdout and stderr of one process.
+#[derive(Debug, Clone)]
+struct OutputBudget {
+    limit: OutputLimit,
+    remaining: Arc<AtomicUsize>,
+    truncated: Arc<AtomicBool>,
+}
+
+impl OutputBudget {
+    fn new(limit: OutputLimit) -> Self {
+        Self {
+            limit,
+            remaining: Arc::new(AtomicUsize::new(limit.bytes)),
+            truncated: Default::default(),
+        }
+    }
+
+    /// Reserves up to `n_bytes` and returns how many may be forwarded.
+    fn take(&self, n_bytes: usize) -> usize {
+        let previous = self
+            .remaining
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |remaining| {
+                Some(remaining.saturating_sub(n_bytes))
+            })
+            .unwrap_or_else(|remaining| remaining);
+
+        previous.min(n_bytes)
+    }
+
+    /// Returns `true` only for the first caller.
+    fn mark_truncated(&self) -> bool {
+        !self.truncated.swap(true, Ordering::SeqCst)
+    }
+}
 
 async fn copy_child_output(
     output: impl AsyncRead + Unpin,
     coordinator_tx: MultiplexingSender,
+    budget: OutputBudget,
+    token: CancellationToken,
     mut xform: impl FnMut(String) -> WorkerMessage,
 ) -> Result<(), CopyChildOutputError> {
     use copy_child_output_error::*;
 
     let mut buf = Utf8BufReader::new(output);
-    let mut n_total_bytes: usize = 0;
 
-    while let Some(buffer) = buf.next().await.context(UnableToReadSnafu)? {
+    // Once the budget is exhausted, we keep reading (and discarding)
+    // so that the process does not block on a full pipe.
+    while let Some(mut buffer) = buf.next().await.context(UnableToReadSnafu)? {
         let n_bytes = buffer.len();
+        let mut n_allowed = budget.take(n_bytes);
 
-        coordinator_tx
-            .send_ok(xform(buffer))
-            .await
-            .context(UnableToSendSnafu)?;
+        if n_allowed < n_bytes {
+            while !buffer.is_char_boundary(n_allowed) {
+                n_allowed -= 1;
+            }
+            buffer.truncate(n_allowed);
+        }
 
-        n_total_bytes = n_total_bytes.saturating_add(n_bytes);
-        ensure!(
-            n_total_bytes <= OUTPUT_BYTE_LIMIT,
-            TooManyBytesSnafu { n_total_bytes }
-        );
+        if !buffer.is_empty() {
+            coordinator_tx
+                .send_ok(xform(buffer))
+                .await
+                .context(UnableToSendSnafu)?;
+        }
+
+        if n_allowed < n_bytes && budget.mark_truncated() {
+            let output_limit = budget.limit;
+
+            coordinator_tx
+                .send_ok(OutputTruncated { output_limit })
+                .await
+                .context(UnableToSendSnafu)?;
+
+            if output_limit.kill {
+                token.cancel();
+            }
+        }
     }
 
     Ok(())
 }
 
-const BYTE_LIMIT_URL: &str = "https://github.com/rust-lang/rust-playground/discussions/1027";
-
 #[derive(Debug, Snafu)]
 #[snafu(module)]
 pub enum CopyChildOutputError {
@@ -1281,11 +2194,6 @@ pub enum CopyChildOutputError {
 
     #[snafu(display("Failed to send output packet"))]
     UnableToSend { source: MultiplexingSenderError },
-
-    #[snafu(display(
-        "Generated {n_total_bytes} bytes of output, exiting (640K ought to be enough for anybody). If this was not an accident, tell us more at {BYTE_LIMIT_URL}"
-    ))]
-    TooManyBytes { n_total_bytes: usize },
 }
 
 // stdin/out <--> messages.
@@ -2,7 +2,8 @@
 
 use orchestrator::coordinator::{
     limits::{self, Acquisition},
-    CoordinatorFactory, ResourceLimits,
+    BackendConfig, ContainerRuntime, CoordinatorFactory, LocalBackendConfig, ResourceLimits,
+    ResourceProfile,
 };
 use std::{
     net::SocketAddr,
@@ -22,9 +23,14 @@ const DEFAULT_WEBSOCKET_IDLE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5)
 const DEFAULT_WEBSOCKET_SESSION_TIMEOUT: Duration = Duration::from_secs(45 * 60);
 const DEFAULT_WEBSOCKET_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 
+const DEFAULT_ARTIFACT_LIMIT_BYTES: u64 = 256 * 1024 * 1024;
+const DEFAULT_ARTIFACT_TIME_TO_LIVE: Duration = Duration::from_secs(10 * 60);
+
 const DEFAULT_COORDINATORS_LIMIT: usize = 25;
 const DEFAULT_PROCESSES_LIMIT: usize = 10;
 
+const DEFAULT_LOCAL_WORKER: &str = "worker";
+
 mod env;
 mod gist;
 mod metrics;
@@ -60,7 +66,10 @@ struct Config {
     feature_flags: FeatureFlags,
     request_db_path: Option<PathBuf>,
     websocket_config: WebSocketConfig,
+    artifact_config: ArtifactConfig,
     limits: Arc<dyn ResourceLimits>,
+    backend: BackendConfig,
+    resource_profile: ResourceProfile,
     port: u16,
     root: PathBuf,
 }
@@ -159,6 +168,23 @@ impl Config {
             }
         };
 
+        let artifact_config = {
+            let limit_bytes = env::var("PLAYGROUND_ARTIFACT_LIMIT_BYTES")
+                .ok()
+                .and_then(|l| l.parse().ok())
+                .unwrap_or(DEFAULT_ARTIFACT_LIMIT_BYTES);
+
+            let time_to_live = env::var("PLAYGROUND_ARTIFACT_TIME_TO_LIVE_S")
+                .ok()
+                .and_then(|l| l.parse().map(Duration::from_secs).ok())
+                .unwrap_or(DEFAULT_ARTIFACT_TIME_TO_LIVE);
+
+            ArtifactConfig {
+                limit_bytes,
+                time_to_live,
+            }
+        };
+
         let coordinators_limit = env::var("PLAYGROUND_COORDINATORS_LIMIT")
             .ok()
             .and_then(|l| l.parse().ok())
@@ -175,6 +201,84 @@ impl Config {
             LifecycleMetrics,
         ));
 
+        let backend = if let Some(template) = env::var_os("PLAYGROUND_LOCAL_TEMPLATE_DIR") {
+            warn!("Running the compilers on the host without any isolation");
+
+            let worker = env::var_os("PLAYGROUND_LOCAL_WORKER")
+                .unwrap_or_else(|| DEFAULT_LOCAL_WORKER.into());
+
+            BackendConfig::Local(Arc::new(LocalBackendConfig {
+                worker: worker.into(),
+                template: template.into(),
+            }))
+        } else {
+            let container_runtime = match env::var("PLAYGROUND_CONTAINER_RUNTIME").as_deref() {
+                Ok("docker") | Err(_) => ContainerRuntime::Docker,
+                Ok("podman") => ContainerRuntime::Podman,
+                Ok(other) => {
+                    let default = ContainerRuntime::default();
+                    warn!("Unknown container runtime `{}`, using {:?}", other, default);
+                    default
+                }
+            };
+
+            BackendConfig::Container(container_runtime)
+        };
+
+        let resource_profile = {
+            let defaults = ResourceProfile::default();
+
+            let memory_bytes = env::var("PLAYGROUND_MEMORY_LIMIT_BYTES")
+                .ok()
+                .and_then(|l| l.parse().ok())
+                .unwrap_or(defaults.memory_bytes);
+
+            let memory_swap_bytes = env::var("PLAYGROUND_MEMORY_SWAP_LIMIT_BYTES")
+                .ok()
+                .and_then(|l| l.parse().ok())
+                .unwrap_or(defaults.memory_swap_bytes);
+
+            let cpus = env::var("PLAYGROUND_CPUS_LIMIT")
+                .ok()
+                .and_then(|l| l.parse().ok())
+                .or(defaults.cpus);
+
+            let pids = env::var("PLAYGROUND_PIDS_LIMIT")
+                .ok()
+                .and_then(|l| l.parse().ok())
+                .unwrap_or(defaults.pids);
+
+            let timeout = env::var("PLAYGROUND_TIMEOUT_S")
+                .ok()
+                .and_then(|l| l.parse().map(Duration::from_secs).ok())
+                .unwrap_or(defaults.timeout);
+
+            // Zero disables the limit
+            let process_timeout = match env::var("PLAYGROUND_PROCESS_TIMEOUT_S")
+                .ok()
+                .and_then(|l| l.parse().ok())
+            {
+                Some(0) => None,
+                Some(s) => Some(Duration::from_secs(s)),
+                None => defaults.process_timeout,
+            };
+
+            let output_bytes = env::var("PLAYGROUND_OUTPUT_LIMIT_BYTES")
+                .ok()
+                .and_then(|l| l.parse().ok())
+                .unwrap_or(defaults.output_bytes);
+
+            ResourceProfile {
+                memory_bytes,
+                memory_swap_bytes,
+                cpus,
+                pids,
+                timeout,
+                process_timeout,
+                output_bytes,
+            }
+        };
+
         Self {
             address,
             cors_enabled,
@@ -183,7 +287,10 @@ impl Config {
             feature_flags,
             request_db_path,
             websocket_config,
+            artifact_config,
             limits,
+            backend,
+            resource_profile,
             port,
             root,
         }
@@ -221,7 +328,11 @@ impl Config {
     }
 
     fn coordinator_factory(&self) -> CoordinatorFactory {
-        CoordinatorFactory::new(self.limits.clone())
+        CoordinatorFactory::with_config(
+            self.limits.clone(),
+            self.backend.clone(),
+            self.resource_profile.clone(),
+        )
     }
 
     fn server_socket_addr(&self) -> SocketAddr {
@@ -306,3 +417,11 @@ impl WebSocketConfig {
         self.handshake_timeout + self.session_timeout + WIGGLE_ROOM
     }
 }
+
+#[derive(Debug, Copy, Clone)]
+struct ArtifactConfig {
+    /// How many bytes of compiled artifacts are kept in total
+    limit_bytes: u64,
+    /// How long a compiled artifact can be downloaded for
+    time_to_live: Duration,
+}
@@ -1,4 +1,6 @@
-use orchestrator::coordinator::{self, Channel, CompileTarget, CrateType, Edition, Mode};
+use orchestrator::coordinator::{
+    self, Channel, ClippyLintGroups, CompileTarget, CrateType, Edition, Mode,
+};
 use prometheus::{
     register_histogram, register_histogram_vec, register_int_counter, register_int_counter_vec,
     register_int_gauge, Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge,
@@ -82,7 +84,11 @@ pub(crate) enum Endpoint {
     Execute,
     Format,
     Miri,
+    Bench,
+    Doc,
     Clippy,
+    Fix,
+    Migrate,
     MacroExpansion,
     MetaCrates,
     MetaVersions,
@@ -106,6 +112,7 @@ pub(crate) struct LabelsCore {
     crate_type: Option<CrateType>,
     tests: Option<bool>,
     backtrace: Option<bool>,
+    lint_groups: Option<ClippyLintGroups>,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -120,10 +127,11 @@ pub(crate) struct Labels {
     crate_type: Option<CrateType>,
     tests: Option<bool>,
     backtrace: Option<bool>,
+    lint_groups: Option<ClippyLintGroups>,
 }
 
 impl Labels {
-    const COUNT: usize = 9;
+    const COUNT: usize = 10;
 
     const LABELS: &'static [&'static str; Self::COUNT] = &[
         "endpoint",
@@ -135,6 +143,7 @@ impl Labels {
         "crate_type",
         "tests",
         "backtrace",
+        "lint_groups",
     ];
 
     fn as_values(&self) -> [&'static str; Self::COUNT] {
@@ -148,6 +157,7 @@ impl Labels {
             crate_type,
             tests,
             backtrace,
+            lint_groups,
         } = *self;
 
         fn b(v: Option<bool>) -> &'static str {
@@ -158,7 +168,10 @@ impl Labels {
             Some(CompileTarget::Assembly(_, _, _)) => "Assembly",
             Some(CompileTarget::Hir) => "Hir",
             Some(CompileTarget::LlvmIr) => "LlvmIr",
+            Some(CompileTarget::LlvmMachineIr) => "LlvmMachineIr",
             Some(CompileTarget::Mir) => "Mir",
+            Some(CompileTarget::OptimizedMir(_, _)) => "OptimizedMir",
+            Some(CompileTarget::Disassembly) => "Disassembly",
             Some(CompileTarget::Wasm) => "Wasm",
             None => "",
         };
@@ -188,6 +201,7 @@ impl Labels {
         };
         let tests = b(tests);
         let backtrace = b(backtrace);
+        let lint_groups = lint_groups.map_or("", lint_groups_label);
 
         [
             endpoint.into(),
@@ -199,6 +213,7 @@ impl Labels {
             crate_type,
             tests,
             backtrace,
+            lint_groups,
         ]
     }
 
@@ -211,6 +226,7 @@ impl Labels {
             crate_type,
             tests,
             backtrace,
+            lint_groups,
         } = labels_core;
         Self {
             endpoint,
@@ -222,10 +238,41 @@ impl Labels {
             crate_type,
             tests,
             backtrace,
+            lint_groups,
         }
     }
 }
 
+/// Every combination of groups gets its own value, which keeps the
+/// number of values small.
+fn lint_groups_label(groups: ClippyLintGroups) -> &'static str {
+    let ClippyLintGroups {
+        pedantic,
+        nursery,
+        restriction,
+        cargo,
+    } = groups;
+
+    match (pedantic, nursery, restriction, cargo) {
+        (false, false, false, false) => "Default",
+        (true, false, false, false) => "Pedantic",
+        (false, true, false, false) => "Nursery",
+        (true, true, false, false) => "Pedantic+Nursery",
+        (false, false, true, false) => "Restriction",
+        (true, false, true, false) => "Pedantic+Restriction",
+        (false, true, true, false) => "Nursery+Restriction",
+        (true, true, true, false) => "Pedantic+Nursery+Restriction",
+        (false, false, false, true) => "Cargo",
+        (true, false, false, true) => "Pedantic+Cargo",
+        (false, true, false, true) => "Nursery+Cargo",
+        (true, true, false, true) => "Pedantic+Nursery+Cargo",
+        (false, false, true, true) => "Restriction+Cargo",
+        (true, false, true, true) => "Pedantic+Restriction+Cargo",
+        (false, true, true, true) => "Nursery+Restriction+Cargo",
+        (true, true, true, true) => "Pedantic+Nursery+Restriction+Cargo",
+    }
+}
+
 pub(crate) async fn track_metric_no_request_async<B, Fut, Resp, E>(
     endpoint: Endpoint,
     body: B,
@@ -253,6 +300,7 @@ where
         crate_type: None,
         tests: None,
         backtrace: None,
+        lint_groups: None,
     };
 
     record_metric_complete(labels, elapsed);
@@ -268,12 +316,16 @@ impl HasLabelsCore for coordinator::CompileRequest {
     fn labels_core(&self) -> LabelsCore {
         let Self {
             target,
+            target_triple: _,
             channel,
             crate_type,
             mode,
             edition,
             tests,
             backtrace,
+            extra: _,
+            json_diagnostics: _,
+            export_artifact: _,
             code: _,
         } = *self;
 
@@ -285,6 +337,7 @@ impl HasLabelsCore for coordinator::CompileRequest {
             crate_type: Some(crate_type),
             tests: Some(tests),
             backtrace: Some(backtrace),
+            lint_groups: None,
         }
     }
 }
@@ -298,6 +351,11 @@ impl HasLabelsCore for coordinator::ExecuteRequest {
             edition,
             tests,
             backtrace,
+            test_options: _,
+            extra: _,
+            json_diagnostics: _,
+            sanitizer: _,
+            coverage: _,
             code: _,
         } = *self;
 
@@ -309,6 +367,7 @@ impl HasLabelsCore for coordinator::ExecuteRequest {
             crate_type: Some(crate_type),
             tests: Some(tests),
             backtrace: Some(backtrace),
+            lint_groups: None,
         }
     }
 }
@@ -319,6 +378,52 @@ impl HasLabelsCore for coordinator::FormatRequest {
             channel,
             crate_type,
             edition,
+            rustfmt_options: _,
+            code: _,
+        } = *self;
+
+        LabelsCore {
+            target: None,
+            channel: Some(channel),
+            mode: None,
+            edition: Some(Some(edition)),
+            crate_type: Some(crate_type),
+            tests: None,
+            backtrace: None,
+            lint_groups: None,
+        }
+    }
+}
+
+impl HasLabelsCore for coordinator::MigrateRequest {
+    fn labels_core(&self) -> LabelsCore {
+        let Self {
+            channel,
+            crate_type,
+            edition,
+            code: _,
+        } = *self;
+
+        LabelsCore {
+            target: None,
+            channel: Some(channel),
+            mode: None,
+            edition: Some(Some(edition)),
+            crate_type: Some(crate_type),
+            tests: None,
+            backtrace: None,
+            lint_groups: None,
+        }
+    }
+}
+
+impl HasLabelsCore for coordinator::FixRequest {
+    fn labels_core(&self) -> LabelsCore {
+        let Self {
+            channel,
+            crate_type,
+            edition,
+            tool: _,
             code: _,
         } = *self;
 
@@ -330,6 +435,7 @@ impl HasLabelsCore for coordinator::FormatRequest {
             crate_type: Some(crate_type),
             tests: None,
             backtrace: None,
+            lint_groups: None,
         }
     }
 }
@@ -340,6 +446,60 @@ impl HasLabelsCore for coordinator::ClippyRequest {
             channel,
             crate_type,
             edition,
+            json_diagnostics: _,
+            lints:
+                coordinator::ClippyLints {
+                    groups,
+                    levels: _,
+                    config: _,
+                },
+            code: _,
+        } = *self;
+
+        LabelsCore {
+            target: None,
+            channel: Some(channel),
+            mode: None,
+            edition: Some(Some(edition)),
+            crate_type: Some(crate_type),
+            tests: None,
+            backtrace: None,
+            lint_groups: Some(groups),
+        }
+    }
+}
+
+impl HasLabelsCore for coordinator::DocRequest {
+    fn labels_core(&self) -> LabelsCore {
+        let Self {
+            channel,
+            crate_type,
+            edition,
+            mode,
+            code: _,
+        } = *self;
+
+        LabelsCore {
+            target: None,
+            channel: Some(channel),
+            mode: None,
+            edition: Some(Some(edition)),
+            crate_type: Some(crate_type),
+            tests: Some(mode == coordinator::DocMode::Test),
+            backtrace: None,
+            lint_groups: None,
+        }
+    }
+}
+
+impl HasLabelsCore for coordinator::BenchRequest {
+    fn labels_core(&self) -> LabelsCore {
+        let Self {
+            channel,
+            crate_type,
+            edition,
+            filter: _,
+            time_limit: _,
             code: _,
         } = *self;
 
@@ -351,6 +511,7 @@ impl HasLabelsCore for coordinator::ClippyRequest {
             crate_type: Some(crate_type),
             tests: None,
             backtrace: None,
+            lint_groups: None,
         }
     }
 }
@@ -363,6 +524,7 @@ impl HasLabelsCore for coordinator::MiriRequest {
             edition,
             tests,
             aliasing_model: _,
+            options: _,
             code: _,
         } = *self;
 
@@ -374,6 +536,7 @@ impl HasLabelsCore for coordinator::MiriRequest {
             crate_type: Some(crate_type),
             tests: Some(tests),
             backtrace: None,
+            lint_groups: None,
         }
     }
 }
@@ -395,6 +558,7 @@ impl HasLabelsCore for coordinator::MacroExpansionRequest {
             crate_type: Some(crate_type),
             tests: None,
             backtrace: None,
+            lint_groups: None,
         }
     }
 }
@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::{collections::BTreeMap, num::NonZeroU32, sync::Arc};
 
 #[derive(Debug, Clone, Serialize)]
 pub(crate) struct ErrorJson {
@@ -9,12 +9,18 @@ pub(crate) struct ErrorJson {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct CompileRequest {
     pub(crate) target: String,
+    #[serde(default, rename = "targetTriple")]
+    pub(crate) target_triple: Option<String>,
     #[serde(rename = "assemblyFlavor")]
     pub(crate) assembly_flavor: Option<String>,
     #[serde(rename = "demangleAssembly")]
     pub(crate) demangle_assembly: Option<String>,
     #[serde(rename = "processAssembly")]
     pub(crate) process_assembly: Option<String>,
+    #[serde(default, rename = "mirOptLevel")]
+    pub(crate) mir_opt_level: Option<String>,
+    #[serde(default, rename = "mirPass")]
+    pub(crate) mir_pass: Option<String>,
     pub(crate) channel: String,
     pub(crate) mode: String,
     #[serde(default)]
@@ -24,6 +30,16 @@ pub(crate) struct CompileRequest {
     pub(crate) tests: bool,
     #[serde(default)]
     pub(crate) backtrace: bool,
+    #[serde(default, rename = "rustcFlags")]
+    pub(crate) rustc_flags: Vec<String>,
+    #[serde(default)]
+    pub(crate) features: Vec<String>,
+    #[serde(default)]
+    pub(crate) envs: BTreeMap<String, String>,
+    #[serde(default, rename = "jsonDiagnostics")]
+    pub(crate) json_diagnostics: bool,
+    #[serde(default, rename = "exportArtifact")]
+    pub(crate) export_artifact: bool,
     pub(crate) code: Code,
 }
 
@@ -35,6 +51,19 @@ pub(crate) struct CompileResponse {
     pub(crate) code: String,
     pub(crate) stdout: String,
     pub(crate) stderr: String,
+    pub(crate) diagnostics: Vec<Diagnostic>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) artifact: Option<CompiledArtifact>,
+    #[serde(rename = "artifactError", skip_serializing_if = "Option::is_none")]
+    pub(crate) artifact_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct CompiledArtifact {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) size: u64,
+    pub(crate) url: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,6 +77,20 @@ pub(crate) struct ExecuteRequest {
     pub(crate) tests: bool,
     #[serde(default)]
     pub(crate) backtrace: bool,
+    #[serde(default, rename = "testOptions")]
+    pub(crate) test_options: TestOptions,
+    #[serde(default, rename = "rustcFlags")]
+    pub(crate) rustc_flags: Vec<String>,
+    #[serde(default)]
+    pub(crate) features: Vec<String>,
+    #[serde(default)]
+    pub(crate) envs: BTreeMap<String, String>,
+    #[serde(default, rename = "jsonDiagnostics")]
+    pub(crate) json_diagnostics: bool,
+    #[serde(default)]
+    pub(crate) sanitizer: Option<String>,
+    #[serde(default)]
+    pub(crate) coverage: bool,
     pub(crate) code: Code,
 }
 
@@ -58,6 +101,51 @@ pub(crate) struct ExecuteResponse {
     pub(crate) exit_detail: String,
     pub(crate) stdout: String,
     pub(crate) stderr: String,
+    pub(crate) diagnostics: Vec<Diagnostic>,
+    pub(crate) tests: Vec<TestResult>,
+    #[serde(rename = "sanitizerReports")]
+    pub(crate) sanitizer_reports: Vec<SanitizerReport>,
+    pub(crate) coverage: Vec<FileCoverage>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct SanitizerReport {
+    pub(crate) sanitizer: &'static str,
+    pub(crate) kind: String,
+    pub(crate) summary: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct FileCoverage {
+    pub(crate) path: String,
+    pub(crate) lines: Vec<LineCoverage>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct LineCoverage {
+    pub(crate) line: u32,
+    pub(crate) count: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct TestOptions {
+    #[serde(default)]
+    pub(crate) filter: Option<String>,
+    #[serde(default)]
+    pub(crate) ignored: bool,
+    #[serde(default)]
+    pub(crate) nocapture: bool,
+    #[serde(default, rename = "testThreads")]
+    pub(crate) test_threads: Option<NonZeroU32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct TestResult {
+    pub(crate) name: String,
+    pub(crate) outcome: &'static str,
+    #[serde(rename = "durationSecs")]
+    pub(crate) duration_secs: Option<f64>,
+    pub(crate) output: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -66,9 +154,26 @@ pub(crate) struct FormatRequest {
     pub(crate) channel: Option<String>,
     #[serde(default)]
     pub(crate) edition: String,
+    #[serde(default, rename = "rustfmtOptions")]
+    pub(crate) rustfmt_options: RustfmtOptions,
     pub(crate) code: Code,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct RustfmtOptions {
+    #[serde(default, rename = "maxWidth")]
+    pub(crate) max_width: Option<u32>,
+    #[serde(default, rename = "importsGranularity")]
+    pub(crate) imports_granularity: Option<String>,
+    #[serde(default, rename = "groupImports")]
+    pub(crate) group_imports: Option<String>,
+    #[serde(default, rename = "styleEdition")]
+    pub(crate) style_edition: Option<String>,
+    /// Any other option, keyed by its name in `rustfmt.toml`.
+    #[serde(default)]
+    pub(crate) other: BTreeMap<String, serde_json::Value>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub(crate) struct FormatResponse {
     pub(crate) success: bool,
@@ -87,15 +192,144 @@ pub(crate) struct ClippyRequest {
     pub(crate) crate_type: String,
     #[serde(default)]
     pub(crate) edition: String,
+    #[serde(default, rename = "jsonDiagnostics")]
+    pub(crate) json_diagnostics: bool,
+    #[serde(default)]
+    pub(crate) lints: ClippyLints,
     pub(crate) code: Code,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct ClippyLints {
+    /// Such as `pedantic` or `nursery`.
+    #[serde(default)]
+    pub(crate) groups: Vec<String>,
+    /// Lint names, such as `clippy::unwrap_used`, to a level.
+    #[serde(default)]
+    pub(crate) levels: BTreeMap<String, String>,
+    /// Written to `clippy.toml`.
+    #[serde(default)]
+    pub(crate) config: BTreeMap<String, serde_json::Value>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub(crate) struct ClippyResponse {
     pub(crate) success: bool,
     pub(crate) exit_detail: String,
     pub(crate) stdout: String,
     pub(crate) stderr: String,
+    pub(crate) diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct MigrateRequest {
+    #[serde(default)]
+    pub(crate) channel: Option<String>,
+    #[serde(default = "default_crate_type", rename = "crateType")]
+    pub(crate) crate_type: String,
+    pub(crate) edition: String,
+    pub(crate) code: Code,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct MigrateResponse {
+    pub(crate) success: bool,
+    #[serde(rename = "exitDetail")]
+    pub(crate) exit_detail: String,
+    pub(crate) edition: String,
+    pub(crate) code: Code,
+    pub(crate) lints: Vec<Diagnostic>,
+    pub(crate) stdout: String,
+    pub(crate) stderr: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct FixRequest {
+    #[serde(default)]
+    pub(crate) channel: Option<String>,
+    #[serde(default = "default_crate_type", rename = "crateType")]
+    pub(crate) crate_type: String,
+    #[serde(default)]
+    pub(crate) edition: String,
+    #[serde(default)]
+    pub(crate) tool: Option<String>,
+    pub(crate) code: Code,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct FixResponse {
+    pub(crate) success: bool,
+    #[serde(rename = "exitDetail")]
+    pub(crate) exit_detail: String,
+    pub(crate) code: Code,
+    pub(crate) stdout: String,
+    pub(crate) stderr: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct DocRequest {
+    #[serde(default)]
+    pub(crate) channel: Option<String>,
+    #[serde(default = "default_crate_type", rename = "crateType")]
+    pub(crate) crate_type: String,
+    #[serde(default)]
+    pub(crate) edition: String,
+    #[serde(default)]
+    pub(crate) mode: Option<String>,
+    pub(crate) code: Code,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct DocResponse {
+    pub(crate) success: bool,
+    #[serde(rename = "exitDetail")]
+    pub(crate) exit_detail: String,
+    pub(crate) files: Vec<DocFile>,
+    pub(crate) tests: Vec<TestResult>,
+    pub(crate) stdout: String,
+    pub(crate) stderr: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct DocFile {
+    pub(crate) path: String,
+    pub(crate) content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct BenchRequest {
+    #[serde(default)]
+    pub(crate) channel: Option<String>,
+    #[serde(default = "default_crate_type", rename = "crateType")]
+    pub(crate) crate_type: String,
+    #[serde(default)]
+    pub(crate) edition: String,
+    #[serde(default)]
+    pub(crate) filter: Option<String>,
+    #[serde(default, rename = "timeLimitSecs")]
+    pub(crate) time_limit_secs: Option<u64>,
+    pub(crate) code: Code,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct BenchResponse {
+    pub(crate) success: bool,
+    #[serde(rename = "exitDetail")]
+    pub(crate) exit_detail: String,
+    pub(crate) benchmarks: Vec<BenchResult>,
+    pub(crate) stdout: String,
+    pub(crate) stderr: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct BenchResult {
+    pub(crate) name: String,
+    #[serde(rename = "medianNs")]
+    pub(crate) median_ns: f64,
+    #[serde(rename = "spreadNs")]
+    pub(crate) spread_ns: f64,
+    #[serde(rename = "megabytesPerSecond")]
+    pub(crate) megabytes_per_second: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -107,6 +341,33 @@ pub(crate) struct MiriRequest {
     pub(crate) tests: bool,
     #[serde(default, rename = "aliasingModel")]
     pub(crate) aliasing_model: Option<String>,
+    #[serde(default)]
+    pub(crate) options: MiriOptions,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct MiriOptions {
+    #[serde(default)]
+    pub(crate) seed: Option<u64>,
+    #[serde(default, rename = "manySeeds")]
+    pub(crate) many_seeds: Option<SeedRange>,
+    #[serde(default, rename = "strictProvenance")]
+    pub(crate) strict_provenance: bool,
+    #[serde(default, rename = "ignoreLeaks")]
+    pub(crate) ignore_leaks: bool,
+    #[serde(default, rename = "preemptionRate")]
+    pub(crate) preemption_rate: Option<f64>,
+    #[serde(default)]
+    pub(crate) isolation: bool,
+    #[serde(default, rename = "symbolicAlignmentCheck")]
+    pub(crate) symbolic_alignment_check: bool,
+}
+
+/// The seeds from `start` up to, but not including, `end`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct SeedRange {
+    pub(crate) start: u64,
+    pub(crate) end: u64,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -212,3 +473,38 @@ pub(crate) struct CodeFile {
     pub(crate) name: String,
     pub(crate) content: String,
 }
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Diagnostic {
+    pub(crate) message: String,
+    pub(crate) code: Option<String>,
+    pub(crate) level: &'static str,
+    pub(crate) spans: Vec<DiagnosticSpan>,
+    pub(crate) children: Vec<Diagnostic>,
+    pub(crate) rendered: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct DiagnosticSpan {
+    #[serde(rename = "fileName")]
+    pub(crate) file_name: String,
+    #[serde(rename = "byteStart")]
+    pub(crate) byte_start: u32,
+    #[serde(rename = "byteEnd")]
+    pub(crate) byte_end: u32,
+    #[serde(rename = "lineStart")]
+    pub(crate) line_start: u32,
+    #[serde(rename = "lineEnd")]
+    pub(crate) line_end: u32,
+    #[serde(rename = "columnStart")]
+    pub(crate) column_start: u32,
+    #[serde(rename = "columnEnd")]
+    pub(crate) column_end: u32,
+    #[serde(rename = "isPrimary")]
+    pub(crate) is_primary: bool,
+    pub(crate) label: Option<String>,
+    #[serde(rename = "suggestedReplacement")]
+    pub(crate) suggested_replacement: Option<String>,
+    #[serde(rename = "suggestionApplicability")]
+    pub(crate) suggestion_applicability: Option<&'static str>,
+}
@@ -25,7 +25,7 @@ use axum_extra::{
     TypedHeader,
 };
 use futures::{FutureExt, TryFutureExt};
-use orchestrator::coordinator::{self, CoordinatorFactory, DockerBackend, TRACKED_CONTAINERS};
+use orchestrator::coordinator::{self, ConfiguredBackend, CoordinatorFactory, TRACKED_CONTAINERS};
 use snafu::prelude::*;
 use std::{
     convert::TryInto,
@@ -46,6 +46,7 @@ use tracing::{error, error_span, field};
 
 use crate::{env::PLAYGROUND_GITHUB_TOKEN, public_http_api as api};
 
+use artifacts::ArtifactStore;
 use cache::{
     cache_task, CacheTaskItem, CacheTx, CacheTxError, Stamped, SANDBOX_CACHE_TIME_TO_LIVE,
 };
@@ -57,8 +58,7 @@ const CORS_CACHE_TIME_TO_LIVE: Duration = ONE_HOUR;
 const MAX_AGE_ONE_DAY: HeaderValue = HeaderValue::from_static("public, max-age=86400");
 const MAX_AGE_ONE_YEAR: HeaderValue = HeaderValue::from_static("public, max-age=31536000");
 
-const DOCKER_PROCESS_TIMEOUT_SOFT: Duration = Duration::from_secs(10);
-
+mod artifacts;
 mod cache;
 mod websocket;
 
@@ -79,6 +79,8 @@ pub(crate) async fn serve(config: Config) {
     let request_db = config.request_database();
     let (db_task, db_handle) = request_db.spawn();
 
+    let (artifacts_task, artifacts) = ArtifactStore::spawn(config.artifact_config);
+
     let root_files = static_file_service(config.root_path(), MAX_AGE_ONE_DAY);
     let asset_files = static_file_service(config.asset_path(), MAX_AGE_ONE_YEAR);
     let rewrite_help_as_index = middleware::from_fn(rewrite_help_as_index);
@@ -92,8 +94,13 @@ pub(crate) async fn serve(config: Config) {
         .route("/execute", post(execute))
         .route("/format", post(format))
         .route("/clippy", post(clippy))
+        .route("/fix", post(fix))
+        .route("/migrate", post(migrate))
         .route("/miri", post(miri))
+        .route("/bench", post(bench))
+        .route("/doc", post(doc))
         .route("/macro-expansion", post(macro_expansion))
+        .route("/artifacts/{id}", get(artifact_get))
         .route("/meta/crates", get_or_post(meta_crates))
         .route("/meta/versions", get(meta_versions))
         .route("/meta/gist", post(meta_gist_create))
@@ -110,6 +117,7 @@ pub(crate) async fn serve(config: Config) {
         )
         .layer(Extension(factory))
         .layer(Extension(db_handle))
+        .layer(Extension(artifacts))
         .layer(Extension(cache_crates_tx))
         .layer(Extension(cache_versions_tx))
         .layer(Extension(config.github_token()))
@@ -178,6 +186,7 @@ pub(crate) async fn serve(config: Config) {
         v = db_task => v.unwrap(),
         v = cache_crates_task => v.unwrap(),
         v = cache_versions_task => v.unwrap(),
+        v = artifacts_task => v.unwrap(),
     }
 }
 
@@ -244,14 +253,39 @@ async fn evaluate(
 async fn compile(
     Extension(factory): Extension<Factory>,
     Extension(db): Extension<Handle>,
+    Extension(artifacts): Extension<ArtifactStore>,
     Json(req): Json<api::CompileRequest>,
 ) -> Result<Json<api::CompileResponse>> {
     attempt_record_request(db, req, async |req| {
-        with_coordinator(&factory.0, req, async |c, req| {
-            c.compile(req).context(CompileSnafu).await
-        })
-        .await
-        .map(Json)
+        let mut resp: coordinator::WithOutput<coordinator::CompileResponse> =
+            with_coordinator(&factory.0, req, async |c, req| {
+                c.compile(req).context(CompileSnafu).await
+            })
+            .await?;
+
+        let artifact = resp.response.artifact.take();
+        let mut resp = api::CompileResponse::from(resp);
+
+        // The compiled code is still useful without the artifact
+        if let Some(artifact) = artifact {
+            let name = artifact.name.clone();
+            let size = artifact.data.len() as u64;
+
+            match artifacts.insert(artifact) {
+                Ok(id) => {
+                    let url = format!("/artifacts/{id}");
+                    resp.artifact = Some(api::CompiledArtifact {
+                        id,
+                        name,
+                        size,
+                        url,
+                    });
+                }
+                Err(e) => resp.artifact_error = Some(e.to_string()),
+            }
+        }
+
+        Ok(Json(resp))
     })
     .await
 }
@@ -301,6 +335,75 @@ async fn clippy(
     .await
 }
 
+async fn fix(
+    Extension(factory): Extension<Factory>,
+    Extension(db): Extension<Handle>,
+    Json(req): Json<api::FixRequest>,
+) -> Result<Json<api::FixResponse>> {
+    attempt_record_request(db, req, async |req| {
+        with_coordinator(&factory.0, req, async |c, req| {
+            c.fix(req).context(FixSnafu).await
+        })
+        .await
+        .map(Json)
+    })
+    .await
+}
+
+async fn migrate(
+    Extension(factory): Extension<Factory>,
+    Extension(db): Extension<Handle>,
+    Json(req): Json<api::MigrateRequest>,
+) -> Result<Json<api::MigrateResponse>> {
+    attempt_record_request(db, req, async |req| {
+        with_coordinator(&factory.0, req, async |c, req| {
+            c.migrate(req).context(MigrateSnafu).await
+        })
+        .await
+        .map(Json)
+    })
+    .await
+}
+
+async fn doc(
+    Extension(factory): Extension<Factory>,
+    Extension(db): Extension<Handle>,
+    Json(req): Json<api::DocRequest>,
+) -> Result<Json<api::DocResponse>> {
+    attempt_record_request(db, req, async |req| {
+        with_coordinator(&factory.0, req, async |c, req| {
+            c.doc(req).context(DocSnafu).await
+        })
+        .await
+        .map(Json)
+    })
+    .await
+}
+
+async fn bench(
+    Extension(factory): Extension<Factory>,
+    Extension(db): Extension<Handle>,
+    Json(req): Json<api::BenchRequest>,
+) -> Result<Json<api::BenchResponse>> {
+    attempt_record_request(db, req, async |req| {
+        // The whole request is abandoned after this, so there's no
+        // point in benchmarking for longer.
+        let timeout = factory.0.resource_profile().timeout;
+
+        with_coordinator(
+            &factory.0,
+            req,
+            async |c, mut req: coordinator::BenchRequest| {
+                req.time_limit = req.time_limit.min(timeout);
+                c.bench(req).context(BenchSnafu).await
+            },
+        )
+        .await
+        .map(Json)
+    })
+    .await
+}
+
 async fn miri(
     Extension(factory): Extension<Factory>,
     Extension(db): Extension<Handle>,
@@ -355,6 +458,22 @@ impl HasEndpoint for api::ClippyRequest {
     const ENDPOINT: Endpoint = Endpoint::Clippy;
 }
 
+impl HasEndpoint for api::FixRequest {
+    const ENDPOINT: Endpoint = Endpoint::Fix;
+}
+
+impl HasEndpoint for api::MigrateRequest {
+    const ENDPOINT: Endpoint = Endpoint::Migrate;
+}
+
+impl HasEndpoint for api::DocRequest {
+    const ENDPOINT: Endpoint = Endpoint::Doc;
+}
+
+impl HasEndpoint for api::BenchRequest {
+    const ENDPOINT: Endpoint = Endpoint::Bench;
+}
+
 impl HasEndpoint for api::MiriRequest {
     const ENDPOINT: Endpoint = Endpoint::Miri;
 }
@@ -409,6 +528,30 @@ impl IsSuccess for coordinator::ClippyResponse {
     }
 }
 
+impl IsSuccess for coordinator::FixResponse {
+    fn is_success(&self) -> bool {
+        self.success
+    }
+}
+
+impl IsSuccess for coordinator::MigrateResponse {
+    fn is_success(&self) -> bool {
+        self.success
+    }
+}
+
+impl IsSuccess for coordinator::DocResponse {
+    fn is_success(&self) -> bool {
+        self.success
+    }
+}
+
+impl IsSuccess for coordinator::BenchResponse {
+    fn is_success(&self) -> bool {
+        self.success
+    }
+}
+
 impl IsSuccess for coordinator::MiriResponse {
     fn is_success(&self) -> bool {
         self.success
@@ -434,7 +577,7 @@ impl Outcome {
 async fn with_coordinator<WebReq, WebResp, Req, Resp>(
     factory: &CoordinatorFactory,
     req: WebReq,
-    f: impl AsyncFnOnce(&coordinator::Coordinator<DockerBackend>, Req) -> Result<Resp>,
+    f: impl AsyncFnOnce(&coordinator::Coordinator<ConfiguredBackend>, Req) -> Result<Resp>,
 ) -> Result<WebResp>
 where
     WebReq: TryInto<Req>,
@@ -444,7 +587,7 @@ where
     Resp: Into<WebResp>,
     Resp: IsSuccess,
 {
-    let coordinator = factory.build();
+    let coordinator = factory.build_configured();
 
     let job = async {
         let req = req.try_into()?;
@@ -454,7 +597,8 @@ where
         let start = Instant::now();
 
         let job = f(&coordinator, req);
-        let resp = tokio::time::timeout(DOCKER_PROCESS_TIMEOUT_SOFT, job).await;
+        let timeout = factory.resource_profile().timeout;
+        let resp = tokio::time::timeout(timeout, job).await;
 
         let elapsed = start.elapsed();
 
@@ -484,6 +628,29 @@ where
     resp
 }
 
+async fn artifact_get(
+    Extension(artifacts): Extension<ArtifactStore>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let artifact = artifacts.get(&id).ok_or(StatusCode::NOT_FOUND)?;
+
+    let content_type = if artifact.name.ends_with(".wasm") {
+        "application/wasm"
+    } else {
+        "application/octet-stream"
+    };
+    let content_disposition = format!(r#"attachment; filename="{}""#, artifact.name);
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_owned()),
+            (header::CONTENT_DISPOSITION, content_disposition),
+            (header::CACHE_CONTROL, "private, no-store".to_owned()),
+        ],
+        artifact.data,
+    ))
+}
+
 async fn meta_crates(
     Extension(tx): Extension<CacheCratesTx>,
     if_none_match: Option<TypedHeader<IfNoneMatch>>,
@@ -686,7 +853,7 @@ type CacheCratesItem = CacheTaskItem<api::MetaCratesResponse, CacheCratesError>;
 #[tracing::instrument(skip_all)]
 async fn cache_crates_task(factory: Arc<CoordinatorFactory>, rx: mpsc::Receiver<CacheCratesItem>) {
     cache_task(rx, move || {
-        let coordinator = factory.build::<DockerBackend>();
+        let coordinator = factory.build_configured();
 
         async move {
             let crates = coordinator.crates().map_ok(From::from).await?;
@@ -718,7 +885,7 @@ async fn cache_versions_task(
     rx: mpsc::Receiver<CacheVersionsItem>,
 ) {
     cache_task(rx, move || {
-        let coordinator = factory.build::<DockerBackend>();
+        let coordinator = factory.build_configured();
 
         async move {
             let versions = coordinator.versions().map_ok(From::from).await?;
@@ -849,6 +1016,26 @@ enum Error {
         source: api_orchestrator_integration_impls::ParseClippyRequestError,
     },
 
+    #[snafu(transparent)]
+    FixRequest {
+        source: api_orchestrator_integration_impls::ParseFixRequestError,
+    },
+
+    #[snafu(transparent)]
+    MigrateRequest {
+        source: api_orchestrator_integration_impls::ParseMigrateRequestError,
+    },
+
+    #[snafu(transparent)]
+    DocRequest {
+        source: api_orchestrator_integration_impls::ParseDocRequestError,
+    },
+
+    #[snafu(transparent)]
+    BenchRequest {
+        source: api_orchestrator_integration_impls::ParseBenchRequestError,
+    },
+
     #[snafu(transparent)]
     MiriRequest {
         source: api_orchestrator_integration_impls::ParseMiriRequestError,
@@ -899,6 +1086,26 @@ enum Error {
         source: orchestrator::coordinator::ClippyError,
     },
 
+    #[snafu(display("Unable to process the fix request"))]
+    Fix {
+        source: orchestrator::coordinator::FixError,
+    },
+
+    #[snafu(display("Unable to process the migrate request"))]
+    Migrate {
+        source: orchestrator::coordinator::MigrateError,
+    },
+
+    #[snafu(display("Unable to process the documentation request"))]
+    Doc {
+        source: orchestrator::coordinator::DocError,
+    },
+
+    #[snafu(display("Unable to process the bench request"))]
+    Bench {
+        source: orchestrator::coordinator::BenchError,
+    },
+
     #[snafu(display("Unable to process the Miri request"))]
     Miri {
         source: orchestrator::coordinator::MiriError,
@@ -918,7 +1125,7 @@ type Result<T, E = Error> = ::std::result::Result<T, E>;
 pub(crate) mod api_orchestrator_integration_impls {
     use orchestrator::coordinator::*;
     use snafu::prelude::*;
-    use std::convert::TryFrom;
+    use std::{collections::BTreeMap, convert::TryFrom, time::Duration};
 
     use crate::gist;
     use crate::public_http_api as api;
@@ -1013,6 +1220,77 @@ pub(crate) mod api_orchestrator_integration_impls {
         }
     }
 
+    impl From<Diagnostic> for api::Diagnostic {
+        fn from(value: Diagnostic) -> Self {
+            let Diagnostic {
+                message,
+                code,
+                level,
+                spans,
+                children,
+                rendered,
+            } = value;
+
+            let level = match level {
+                DiagnosticLevel::InternalCompilerError => "error: internal compiler error",
+                DiagnosticLevel::Error => "error",
+                DiagnosticLevel::Warning => "warning",
+                DiagnosticLevel::Note => "note",
+                DiagnosticLevel::Help => "help",
+                DiagnosticLevel::FailureNote => "failure-note",
+                DiagnosticLevel::Unknown => "unknown",
+            };
+
+            Self {
+                message,
+                code: code.map(|c| c.code),
+                level,
+                spans: spans.into_iter().map(Into::into).collect(),
+                children: children.into_iter().map(Into::into).collect(),
+                rendered,
+            }
+        }
+    }
+
+    impl From<DiagnosticSpan> for api::DiagnosticSpan {
+        fn from(value: DiagnosticSpan) -> Self {
+            let DiagnosticSpan {
+                file_name,
+                byte_start,
+                byte_end,
+                line_start,
+                line_end,
+                column_start,
+                column_end,
+                is_primary,
+                label,
+                suggested_replacement,
+                suggestion_applicability,
+            } = value;
+
+            let suggestion_applicability = suggestion_applicability.map(|a| match a {
+                Applicability::MachineApplicable => "MachineApplicable",
+                Applicability::MaybeIncorrect => "MaybeIncorrect",
+                Applicability::HasPlaceholders => "HasPlaceholders",
+                Applicability::Unspecified => "Unspecified",
+            });
+
+            Self {
+                file_name,
+                byte_start,
+                byte_end,
+                line_start,
+                line_end,
+                column_start,
+                column_end,
+                is_primary,
+                label,
+                suggested_replacement,
+                suggestion_applicability,
+            }
+        }
+    }
+
     impl TryFrom<api::EvaluateRequest> for ExecuteRequest {
         type Error = ParseEvaluateRequestError;
 
@@ -1044,6 +1322,11 @@ pub(crate) mod api_orchestrator_integration_impls {
                 crate_type: CrateType::Binary,
                 tests,
                 backtrace: false,
+                test_options: TestOptions::NONE,
+                extra: ExtraOptions::NONE,
+                json_diagnostics: false,
+                sanitizer: None,
+                coverage: false,
                 code: code.into(),
             })
         }
@@ -1096,31 +1379,50 @@ pub(crate) mod api_orchestrator_integration_impls {
         fn try_from(other: api::CompileRequest) -> Result<Self, Self::Error> {
             let api::CompileRequest {
                 target,
+                target_triple,
                 assembly_flavor,
                 demangle_assembly,
                 process_assembly,
+                mir_opt_level,
+                mir_pass,
                 channel,
                 mode,
                 edition,
                 crate_type,
                 tests,
                 backtrace,
+                rustc_flags,
+                features,
+                envs,
+                json_diagnostics,
+                export_artifact,
                 code,
             } = other;
 
+            let channel = parse_channel(&channel)?;
+
             Ok(Self {
                 target: parse_target(
                     &target,
                     assembly_flavor.as_deref(),
                     demangle_assembly.as_deref(),
                     process_assembly.as_deref(),
+                    mir_opt_level.as_deref(),
+                    mir_pass.as_deref(),
                 )?,
-                channel: parse_channel(&channel)?,
+                target_triple: target_triple
+                    .as_deref()
+                    .map(parse_target_triple)
+                    .transpose()?,
+                channel,
                 crate_type: parse_crate_type(&crate_type)?,
                 mode: parse_mode(&mode)?,
                 edition: parse_edition(&edition)?,
                 tests,
                 backtrace,
+                extra: ExtraOptions::new(channel, rustc_flags, features, envs)?,
+                json_diagnostics,
+                export_artifact,
                 code: code.into(),
             })
         }
@@ -1131,6 +1433,9 @@ pub(crate) mod api_orchestrator_integration_impls {
         #[snafu(transparent)]
         Target { source: ParseCompileTargetError },
 
+        #[snafu(transparent)]
+        TargetTriple { source: ParseTargetTripleError },
+
         #[snafu(transparent)]
         Channel { source: ParseChannelError },
 
@@ -1142,6 +1447,9 @@ pub(crate) mod api_orchestrator_integration_impls {
 
         #[snafu(transparent)]
         Edition { source: ParseEditionError },
+
+        #[snafu(transparent)]
+        ExtraOptions { source: ExtraOptionsError },
     }
 
     impl From<WithOutput<CompileResponse>> for api::CompileResponse {
@@ -1155,6 +1463,9 @@ pub(crate) mod api_orchestrator_integration_impls {
                 success,
                 exit_detail,
                 code,
+                diagnostics,
+                artifact: _,
+                artifact_error,
             } = response;
 
             Self {
@@ -1163,6 +1474,10 @@ pub(crate) mod api_orchestrator_integration_impls {
                 code,
                 stdout,
                 stderr,
+                diagnostics: diagnostics.into_iter().map(Into::into).collect(),
+                // Stored separately; see `compile`
+                artifact: None,
+                artifact_error,
             }
         }
     }
@@ -1178,16 +1493,30 @@ pub(crate) mod api_orchestrator_integration_impls {
                 crate_type,
                 tests,
                 backtrace,
+                test_options,
+                rustc_flags,
+                features,
+                envs,
+                json_diagnostics,
+                sanitizer,
+                coverage,
                 code,
             } = other;
 
+            let channel = parse_channel(&channel)?;
+
             Ok(Self {
-                channel: parse_channel(&channel)?,
+                channel,
                 crate_type: parse_crate_type(&crate_type)?,
                 mode: parse_mode(&mode)?,
                 edition: parse_edition(&edition)?,
                 tests,
                 backtrace,
+                test_options: TestOptions::try_from(test_options)?,
+                extra: ExtraOptions::new(channel, rustc_flags, features, envs)?,
+                json_diagnostics,
+                sanitizer: sanitizer.as_deref().map(parse_sanitizer).transpose()?,
+                coverage,
                 code: code.into(),
             })
         }
@@ -1206,6 +1535,15 @@ pub(crate) mod api_orchestrator_integration_impls {
 
         #[snafu(transparent)]
         Edition { source: ParseEditionError },
+
+        #[snafu(transparent)]
+        ExtraOptions { source: ExtraOptionsError },
+
+        #[snafu(transparent)]
+        TestOptions { source: TestFilterError },
+
+        #[snafu(transparent)]
+        Sanitizer { source: ParseSanitizerError },
     }
 
     impl From<WithOutput<ExecuteResponse>> for api::ExecuteResponse {
@@ -1218,6 +1556,10 @@ pub(crate) mod api_orchestrator_integration_impls {
             let ExecuteResponse {
                 success,
                 exit_detail,
+                diagnostics,
+                tests,
+                sanitizer_reports,
+                coverage,
             } = response;
 
             Self {
@@ -1225,6 +1567,94 @@ pub(crate) mod api_orchestrator_integration_impls {
                 exit_detail,
                 stdout,
                 stderr,
+                diagnostics: diagnostics.into_iter().map(Into::into).collect(),
+                tests: tests.into_iter().map(Into::into).collect(),
+                sanitizer_reports: sanitizer_reports.into_iter().map(Into::into).collect(),
+                coverage: coverage.into_iter().map(Into::into).collect(),
+            }
+        }
+    }
+
+    impl From<FileCoverage> for api::FileCoverage {
+        fn from(value: FileCoverage) -> Self {
+            let FileCoverage { path, lines } = value;
+
+            let lines = lines
+                .into_iter()
+                .map(|LineCoverage { line, count }| api::LineCoverage { line, count })
+                .collect();
+
+            Self { path, lines }
+        }
+    }
+
+    impl From<SanitizerReport> for api::SanitizerReport {
+        fn from(value: SanitizerReport) -> Self {
+            let SanitizerReport {
+                sanitizer,
+                kind,
+                summary,
+            } = value;
+
+            let sanitizer = match sanitizer {
+                Sanitizer::Address => "address",
+                Sanitizer::Leak => "leak",
+                Sanitizer::Memory => "memory",
+                Sanitizer::Thread => "thread",
+            };
+
+            Self {
+                sanitizer,
+                kind,
+                summary,
+            }
+        }
+    }
+
+    impl TryFrom<api::TestOptions> for TestOptions {
+        type Error = TestFilterError;
+
+        fn try_from(value: api::TestOptions) -> std::result::Result<Self, Self::Error> {
+            let api::TestOptions {
+                filter,
+                ignored,
+                nocapture,
+                test_threads,
+            } = value;
+
+            let options = Self {
+                filter,
+                ignored,
+                nocapture,
+                test_threads,
+            };
+            options.validate()?;
+
+            Ok(options)
+        }
+    }
+
+    impl From<TestResult> for api::TestResult {
+        fn from(value: TestResult) -> Self {
+            let TestResult {
+                name,
+                outcome,
+                duration,
+                output,
+            } = value;
+
+            let outcome = match outcome {
+                TestOutcome::Passed => "passed",
+                TestOutcome::Failed => "failed",
+                TestOutcome::Ignored => "ignored",
+                TestOutcome::Benchmarked(_) => "benchmarked",
+            };
+
+            Self {
+                name,
+                outcome,
+                duration_secs: duration.map(|d| d.as_secs_f64()),
+                output,
             }
         }
     }
@@ -1236,6 +1666,7 @@ pub(crate) mod api_orchestrator_integration_impls {
             let api::FormatRequest {
                 channel,
                 edition,
+                rustfmt_options,
                 code,
             } = other;
 
@@ -1248,6 +1679,7 @@ pub(crate) mod api_orchestrator_integration_impls {
                 channel,
                 crate_type: CrateType::Binary, // TODO: use what user has submitted
                 edition: parse_edition(&edition)?,
+                rustfmt_options: RustfmtOptions::try_from(rustfmt_options)?,
                 code: code.into(),
             })
         }
@@ -1260,6 +1692,76 @@ pub(crate) mod api_orchestrator_integration_impls {
 
         #[snafu(transparent)]
         Edition { source: ParseEditionError },
+
+        #[snafu(transparent)]
+        RustfmtOptions { source: ParseRustfmtOptionsError },
+    }
+
+    impl TryFrom<api::RustfmtOptions> for RustfmtOptions {
+        type Error = ParseRustfmtOptionsError;
+
+        fn try_from(other: api::RustfmtOptions) -> std::result::Result<Self, Self::Error> {
+            let api::RustfmtOptions {
+                max_width,
+                imports_granularity,
+                group_imports,
+                style_edition,
+                other,
+            } = other;
+
+            let other = parse_toml_options(other)?;
+
+            Ok(RustfmtOptions {
+                max_width,
+                imports_granularity: imports_granularity
+                    .as_deref()
+                    .map(parse_imports_granularity)
+                    .transpose()?,
+                group_imports: group_imports
+                    .as_deref()
+                    .map(parse_group_imports)
+                    .transpose()?,
+                style_edition: style_edition.as_deref().map(parse_edition).transpose()?,
+                other,
+            })
+        }
+    }
+
+    #[derive(Debug, Snafu)]
+    pub(crate) enum ParseRustfmtOptionsError {
+        #[snafu(transparent)]
+        ImportsGranularity {
+            source: ParseImportsGranularityError,
+        },
+
+        #[snafu(transparent)]
+        GroupImports { source: ParseGroupImportsError },
+
+        #[snafu(transparent)]
+        StyleEdition { source: ParseEditionError },
+
+        #[snafu(transparent)]
+        Other { source: ParseTomlOptionError },
+    }
+
+    fn parse_toml_options(
+        options: BTreeMap<String, serde_json::Value>,
+    ) -> Result<BTreeMap<String, toml::Value>, ParseTomlOptionError> {
+        options
+            .into_iter()
+            .map(|(name, value)| {
+                let value = toml::Value::try_from(value)
+                    .context(ParseTomlOptionSnafu { name: name.clone() })?;
+                Ok((name, value))
+            })
+            .collect()
+    }
+
+    #[derive(Debug, Snafu)]
+    #[snafu(display("The option '{name}' cannot be represented in TOML"))]
+    pub(crate) struct ParseTomlOptionError {
+        name: String,
+        source: toml::ser::Error,
     }
 
     impl From<WithOutput<FormatResponse>> for api::FormatResponse {
@@ -1293,6 +1795,8 @@ pub(crate) mod api_orchestrator_integration_impls {
                 channel,
                 crate_type,
                 edition,
+                json_diagnostics,
+                lints,
                 code,
             } = other;
 
@@ -1305,6 +1809,8 @@ pub(crate) mod api_orchestrator_integration_impls {
                 channel,
                 crate_type: parse_crate_type(&crate_type)?,
                 edition: parse_edition(&edition)?,
+                json_diagnostics,
+                lints: ClippyLints::try_from(lints)?,
                 code: code.into(),
             })
         }
@@ -1320,6 +1826,68 @@ pub(crate) mod api_orchestrator_integration_impls {
 
         #[snafu(transparent)]
         Edition { source: ParseEditionError },
+
+        #[snafu(transparent)]
+        Lints { source: ParseClippyLintsError },
+    }
+
+    impl TryFrom<api::ClippyLints> for ClippyLints {
+        type Error = ParseClippyLintsError;
+
+        fn try_from(other: api::ClippyLints) -> std::result::Result<Self, Self::Error> {
+            let api::ClippyLints {
+                groups,
+                levels,
+                config,
+            } = other;
+
+            let mut lint_groups = ClippyLintGroups::NONE;
+            for group in groups {
+                *lint_group_flag(&mut lint_groups, &group)? = true;
+            }
+
+            let levels = levels
+                .into_iter()
+                .map(|(lint, level)| Ok((lint, parse_lint_level(&level)?)))
+                .collect::<Result<_, ParseLintLevelError>>()?;
+
+            Ok(ClippyLints {
+                groups: lint_groups,
+                levels,
+                config: parse_toml_options(config)?,
+            })
+        }
+    }
+
+    fn lint_group_flag<'a>(
+        groups: &'a mut ClippyLintGroups,
+        s: &str,
+    ) -> Result<&'a mut bool, ParseClippyLintGroupError> {
+        Ok(match s {
+            "pedantic" => &mut groups.pedantic,
+            "nursery" => &mut groups.nursery,
+            "restriction" => &mut groups.restriction,
+            "cargo" => &mut groups.cargo,
+            value => return ParseClippyLintGroupSnafu { value }.fail(),
+        })
+    }
+
+    #[derive(Debug, Snafu)]
+    pub(crate) enum ParseClippyLintsError {
+        #[snafu(transparent)]
+        Group { source: ParseClippyLintGroupError },
+
+        #[snafu(transparent)]
+        Level { source: ParseLintLevelError },
+
+        #[snafu(transparent)]
+        Config { source: ParseTomlOptionError },
+    }
+
+    #[derive(Debug, Snafu)]
+    #[snafu(display("'{value}' is not a valid Clippy lint group"))]
+    pub(crate) struct ParseClippyLintGroupError {
+        value: String,
     }
 
     impl From<WithOutput<ClippyResponse>> for api::ClippyResponse {
@@ -1332,6 +1900,7 @@ pub(crate) mod api_orchestrator_integration_impls {
             let ClippyResponse {
                 success,
                 exit_detail,
+                diagnostics,
             } = response;
 
             Self {
@@ -1339,53 +1908,399 @@ pub(crate) mod api_orchestrator_integration_impls {
                 exit_detail,
                 stdout,
                 stderr,
+                diagnostics: diagnostics.into_iter().map(Into::into).collect(),
             }
         }
     }
 
-    impl TryFrom<api::MiriRequest> for MiriRequest {
-        type Error = ParseMiriRequestError;
+    impl TryFrom<api::FixRequest> for FixRequest {
+        type Error = ParseFixRequestError;
 
-        fn try_from(other: api::MiriRequest) -> std::result::Result<Self, Self::Error> {
-            let api::MiriRequest {
-                code,
+        fn try_from(other: api::FixRequest) -> std::result::Result<Self, Self::Error> {
+            let api::FixRequest {
+                channel,
+                crate_type,
                 edition,
-                tests,
-                aliasing_model,
+                tool,
+                code,
             } = other;
 
-            let aliasing_model = match aliasing_model {
-                Some(am) => parse_aliasing_model(&am)?,
-                None => AliasingModel::Stacked,
+            let channel = match channel {
+                Some(c) => parse_channel(&c)?,
+                None => Channel::Nightly,
             };
 
-            Ok(MiriRequest {
-                channel: Channel::Nightly,     // TODO: use what user has submitted
-                crate_type: CrateType::Binary, // TODO: use what user has submitted
+            let tool = match tool {
+                Some(t) => parse_fix_tool(&t)?,
+                None => FixTool::Rustc,
+            };
+
+            Ok(FixRequest {
+                channel,
+                crate_type: parse_crate_type(&crate_type)?,
                 edition: parse_edition(&edition)?,
-                tests,
-                aliasing_model,
+                tool,
                 code: code.into(),
             })
         }
     }
 
     #[derive(Debug, Snafu)]
-    pub(crate) enum ParseMiriRequestError {
+    pub(crate) enum ParseFixRequestError {
+        #[snafu(transparent)]
+        Channel { source: ParseChannelError },
+
+        #[snafu(transparent)]
+        CrateType { source: ParseCrateTypeError },
+
         #[snafu(transparent)]
         Edition { source: ParseEditionError },
+
         #[snafu(transparent)]
-        AliasingMode { source: ParseAliasingModelError },
+        Tool { source: ParseFixToolError },
     }
 
-    impl From<WithOutput<MiriResponse>> for api::MiriResponse {
-        fn from(other: WithOutput<MiriResponse>) -> Self {
+    impl From<WithOutput<FixResponse>> for api::FixResponse {
+        fn from(other: WithOutput<FixResponse>) -> Self {
             let WithOutput {
                 response,
                 stdout,
                 stderr,
             } = other;
-            let MiriResponse {
+            let FixResponse {
+                success,
+                exit_detail,
+                code,
+            } = response;
+
+            Self {
+                success,
+                exit_detail,
+                code: code.into(),
+                stdout,
+                stderr,
+            }
+        }
+    }
+
+    impl TryFrom<api::MigrateRequest> for MigrateRequest {
+        type Error = ParseMigrateRequestError;
+
+        fn try_from(other: api::MigrateRequest) -> std::result::Result<Self, Self::Error> {
+            let api::MigrateRequest {
+                channel,
+                crate_type,
+                edition,
+                code,
+            } = other;
+
+            let channel = match channel {
+                Some(c) => parse_channel(&c)?,
+                None => Channel::Nightly,
+            };
+
+            Ok(MigrateRequest {
+                channel,
+                crate_type: parse_crate_type(&crate_type)?,
+                edition: parse_edition(&edition)?,
+                code: code.into(),
+            })
+        }
+    }
+
+    #[derive(Debug, Snafu)]
+    pub(crate) enum ParseMigrateRequestError {
+        #[snafu(transparent)]
+        Channel { source: ParseChannelError },
+
+        #[snafu(transparent)]
+        CrateType { source: ParseCrateTypeError },
+
+        #[snafu(transparent)]
+        Edition { source: ParseEditionError },
+    }
+
+    impl From<WithOutput<MigrateResponse>> for api::MigrateResponse {
+        fn from(other: WithOutput<MigrateResponse>) -> Self {
+            let WithOutput {
+                response,
+                stdout,
+                stderr,
+            } = other;
+            let MigrateResponse {
+                success,
+                exit_detail,
+                edition,
+                code,
+                lints,
+            } = response;
+
+            let edition = match edition {
+                Edition::Rust2015 => "2015",
+                Edition::Rust2018 => "2018",
+                Edition::Rust2021 => "2021",
+                Edition::Rust2024 => "2024",
+            };
+
+            Self {
+                success,
+                exit_detail,
+                edition: edition.into(),
+                code: code.into(),
+                lints: lints.into_iter().map(Into::into).collect(),
+                stdout,
+                stderr,
+            }
+        }
+    }
+
+    impl TryFrom<api::DocRequest> for DocRequest {
+        type Error = ParseDocRequestError;
+
+        fn try_from(other: api::DocRequest) -> std::result::Result<Self, Self::Error> {
+            let api::DocRequest {
+                channel,
+                crate_type,
+                edition,
+                mode,
+                code,
+            } = other;
+
+            let channel = match channel {
+                Some(c) => parse_channel(&c)?,
+                None => Channel::Stable,
+            };
+
+            let mode = match mode {
+                Some(m) => parse_doc_mode(&m)?,
+                None => DocMode::Build,
+            };
+
+            Ok(DocRequest {
+                channel,
+                crate_type: parse_crate_type(&crate_type)?,
+                edition: parse_edition(&edition)?,
+                mode,
+                code: code.into(),
+            })
+        }
+    }
+
+    #[derive(Debug, Snafu)]
+    pub(crate) enum ParseDocRequestError {
+        #[snafu(transparent)]
+        Channel { source: ParseChannelError },
+
+        #[snafu(transparent)]
+        CrateType { source: ParseCrateTypeError },
+
+        #[snafu(transparent)]
+        Edition { source: ParseEditionError },
+
+        #[snafu(transparent)]
+        Mode { source: ParseDocModeError },
+    }
+
+    impl From<WithOutput<DocResponse>> for api::DocResponse {
+        fn from(other: WithOutput<DocResponse>) -> Self {
+            let WithOutput {
+                response,
+                stdout,
+                stderr,
+            } = other;
+            let DocResponse {
+                success,
+                exit_detail,
+                files,
+                tests,
+            } = response;
+
+            Self {
+                success,
+                exit_detail,
+                files: files.into_iter().map(Into::into).collect(),
+                tests: tests.into_iter().map(Into::into).collect(),
+                stdout,
+                stderr,
+            }
+        }
+    }
+
+    impl From<DocFile> for api::DocFile {
+        fn from(value: DocFile) -> Self {
+            let DocFile { path, content } = value;
+            Self { path, content }
+        }
+    }
+
+    impl TryFrom<api::BenchRequest> for BenchRequest {
+        type Error = ParseBenchRequestError;
+
+        fn try_from(other: api::BenchRequest) -> std::result::Result<Self, Self::Error> {
+            let api::BenchRequest {
+                channel,
+                crate_type,
+                edition,
+                filter,
+                time_limit_secs,
+                code,
+            } = other;
+
+            // `#[bench]` is only available on nightly
+            let channel = match channel {
+                Some(c) => parse_channel(&c)?,
+                None => Channel::Nightly,
+            };
+
+            let time_limit =
+                time_limit_secs.map_or(BenchRequest::MAX_TIME_LIMIT, Duration::from_secs);
+
+            let request = BenchRequest {
+                channel,
+                crate_type: parse_crate_type(&crate_type)?,
+                edition: parse_edition(&edition)?,
+                filter,
+                time_limit,
+                code: code.into(),
+            };
+            request.validate()?;
+
+            Ok(request)
+        }
+    }
+
+    #[derive(Debug, Snafu)]
+    pub(crate) enum ParseBenchRequestError {
+        #[snafu(transparent)]
+        Channel { source: ParseChannelError },
+
+        #[snafu(transparent)]
+        CrateType { source: ParseCrateTypeError },
+
+        #[snafu(transparent)]
+        Edition { source: ParseEditionError },
+
+        #[snafu(transparent)]
+        Request { source: BenchRequestError },
+    }
+
+    impl From<WithOutput<BenchResponse>> for api::BenchResponse {
+        fn from(other: WithOutput<BenchResponse>) -> Self {
+            let WithOutput {
+                response,
+                stdout,
+                stderr,
+            } = other;
+            let BenchResponse {
+                success,
+                exit_detail,
+                benchmarks,
+            } = response;
+
+            Self {
+                success,
+                exit_detail,
+                benchmarks: benchmarks.into_iter().map(Into::into).collect(),
+                stdout,
+                stderr,
+            }
+        }
+    }
+
+    impl From<BenchResult> for api::BenchResult {
+        fn from(value: BenchResult) -> Self {
+            let BenchResult { name, stats } = value;
+            let BenchStats {
+                median,
+                spread,
+                megabytes_per_second,
+            } = stats;
+
+            Self {
+                name,
+                median_ns: median.as_secs_f64() * 1e9,
+                spread_ns: spread.as_secs_f64() * 1e9,
+                megabytes_per_second,
+            }
+        }
+    }
+
+    impl TryFrom<api::MiriRequest> for MiriRequest {
+        type Error = ParseMiriRequestError;
+
+        fn try_from(other: api::MiriRequest) -> std::result::Result<Self, Self::Error> {
+            let api::MiriRequest {
+                code,
+                edition,
+                tests,
+                aliasing_model,
+                options,
+            } = other;
+
+            let aliasing_model = match aliasing_model {
+                Some(am) => parse_aliasing_model(&am)?,
+                None => AliasingModel::Stacked,
+            };
+
+            Ok(MiriRequest {
+                channel: Channel::Nightly,     // TODO: use what user has submitted
+                crate_type: CrateType::Binary, // TODO: use what user has submitted
+                edition: parse_edition(&edition)?,
+                tests,
+                aliasing_model,
+                options: MiriOptions::try_from(options)?,
+                code: code.into(),
+            })
+        }
+    }
+
+    #[derive(Debug, Snafu)]
+    pub(crate) enum ParseMiriRequestError {
+        #[snafu(transparent)]
+        Edition { source: ParseEditionError },
+        #[snafu(transparent)]
+        AliasingMode { source: ParseAliasingModelError },
+        #[snafu(transparent)]
+        Options { source: MiriOptionsError },
+    }
+
+    impl TryFrom<api::MiriOptions> for MiriOptions {
+        type Error = MiriOptionsError;
+
+        fn try_from(other: api::MiriOptions) -> std::result::Result<Self, Self::Error> {
+            let api::MiriOptions {
+                seed,
+                many_seeds,
+                strict_provenance,
+                ignore_leaks,
+                preemption_rate,
+                isolation,
+                symbolic_alignment_check,
+            } = other;
+
+            let options = MiriOptions {
+                seed,
+                many_seeds: many_seeds.map(|api::SeedRange { start, end }| start..end),
+                strict_provenance,
+                ignore_leaks,
+                preemption_rate,
+                isolation,
+                symbolic_alignment_check,
+            };
+            options.validate()?;
+
+            Ok(options)
+        }
+    }
+
+    impl From<WithOutput<MiriResponse>> for api::MiriResponse {
+        fn from(other: WithOutput<MiriResponse>) -> Self {
+            let WithOutput {
+                response,
+                stdout,
+                stderr,
+            } = other;
+            let MiriResponse {
                 success,
                 exit_detail,
             } = response;
@@ -1441,11 +2356,13 @@ pub(crate) mod api_orchestrator_integration_impls {
         }
     }
 
-    fn parse_target(
+    pub(crate) fn parse_target(
         target: &str,
         assembly_flavor: Option<&str>,
         demangle_assembly: Option<&str>,
         process_assembly: Option<&str>,
+        mir_opt_level: Option<&str>,
+        mir_pass: Option<&str>,
     ) -> Result<CompileTarget, ParseCompileTargetError> {
         Ok(match target {
             "asm" => {
@@ -1467,8 +2384,20 @@ pub(crate) mod api_orchestrator_integration_impls {
                 CompileTarget::Assembly(assembly_flavor, demangle, process_assembly)
             }
             "llvm-ir" => CompileTarget::LlvmIr,
+            "llvm-machine-ir" => CompileTarget::LlvmMachineIr,
             "mir" => CompileTarget::Mir,
+            "optimized-mir" => {
+                let mir_opt_level = match mir_opt_level {
+                    Some(l) => parse_mir_opt_level(l)?,
+                    None => MirOptLevel::L2,
+                };
+
+                let mir_pass = mir_pass.map(parse_mir_pass).transpose()?;
+
+                CompileTarget::OptimizedMir(mir_opt_level, mir_pass)
+            }
             "hir" => CompileTarget::Hir,
+            "disassembly" => CompileTarget::Disassembly,
             "wasm" => CompileTarget::Wasm,
             value => return InvalidTargetSnafu { value }.fail(),
         })
@@ -1485,6 +2414,12 @@ pub(crate) mod api_orchestrator_integration_impls {
         #[snafu(transparent)]
         ProcessAssembly { source: ParseProcessAssemblyError },
 
+        #[snafu(transparent)]
+        MirOptLevel { source: ParseMirOptLevelError },
+
+        #[snafu(transparent)]
+        MirPass { source: ParseMirPassError },
+
         #[snafu(display("'{value}' is not a valid target"))]
         InvalidTarget { value: String },
     }
@@ -1531,6 +2466,58 @@ pub(crate) mod api_orchestrator_integration_impls {
         value: String,
     }
 
+    fn parse_mir_opt_level(s: &str) -> Result<MirOptLevel, ParseMirOptLevelError> {
+        Ok(match s {
+            "0" => MirOptLevel::L0,
+            "1" => MirOptLevel::L1,
+            "2" => MirOptLevel::L2,
+            "3" => MirOptLevel::L3,
+            "4" => MirOptLevel::L4,
+            value => return ParseMirOptLevelSnafu { value }.fail(),
+        })
+    }
+
+    #[derive(Debug, Snafu)]
+    #[snafu(display("'{value}' is not a valid MIR optimization level"))]
+    pub(crate) struct ParseMirOptLevelError {
+        value: String,
+    }
+
+    fn parse_mir_pass(s: &str) -> Result<MirPass, ParseMirPassError> {
+        Ok(match s {
+            "CopyProp" => MirPass::CopyProp,
+            "DataflowConstProp" => MirPass::DataflowConstProp,
+            "DestinationPropagation" => MirPass::DestinationPropagation,
+            "GVN" => MirPass::Gvn,
+            "Inline" => MirPass::Inline,
+            "JumpThreading" => MirPass::JumpThreading,
+            "ReferencePropagation" => MirPass::ReferencePropagation,
+            value => return ParseMirPassSnafu { value }.fail(),
+        })
+    }
+
+    #[derive(Debug, Snafu)]
+    #[snafu(display("'{value}' is not a supported MIR pass"))]
+    pub(crate) struct ParseMirPassError {
+        value: String,
+    }
+
+    pub(crate) fn parse_target_triple(s: &str) -> Result<TargetTriple, ParseTargetTripleError> {
+        Ok(match s {
+            "aarch64-unknown-linux-gnu" => TargetTriple::Aarch64UnknownLinuxGnu,
+            "i686-unknown-linux-gnu" => TargetTriple::I686UnknownLinuxGnu,
+            "riscv64gc-unknown-linux-gnu" => TargetTriple::Riscv64gcUnknownLinuxGnu,
+            "thumbv7em-none-eabihf" => TargetTriple::Thumbv7emNoneEabihf,
+            value => return ParseTargetTripleSnafu { value }.fail(),
+        })
+    }
+
+    #[derive(Debug, Snafu)]
+    #[snafu(display("'{value}' is not a supported target triple"))]
+    pub(crate) struct ParseTargetTripleError {
+        value: String,
+    }
+
     pub(crate) fn parse_channel(s: &str) -> Result<Channel, ParseChannelError> {
         Ok(match s {
             "stable" => Channel::Stable,
@@ -1597,6 +2584,72 @@ pub(crate) mod api_orchestrator_integration_impls {
         value: String,
     }
 
+    pub(crate) fn parse_imports_granularity(
+        s: &str,
+    ) -> Result<ImportsGranularity, ParseImportsGranularityError> {
+        Ok(match s {
+            "preserve" => ImportsGranularity::Preserve,
+            "crate" => ImportsGranularity::Crate,
+            "module" => ImportsGranularity::Module,
+            "item" => ImportsGranularity::Item,
+            "one" => ImportsGranularity::One,
+            value => return ParseImportsGranularitySnafu { value }.fail(),
+        })
+    }
+
+    #[derive(Debug, Snafu)]
+    #[snafu(display("'{value}' is not a valid imports granularity"))]
+    pub(crate) struct ParseImportsGranularityError {
+        value: String,
+    }
+
+    pub(crate) fn parse_group_imports(s: &str) -> Result<GroupImports, ParseGroupImportsError> {
+        Ok(match s {
+            "preserve" => GroupImports::Preserve,
+            "std-external-crate" => GroupImports::StdExternalCrate,
+            "one" => GroupImports::One,
+            value => return ParseGroupImportsSnafu { value }.fail(),
+        })
+    }
+
+    #[derive(Debug, Snafu)]
+    #[snafu(display("'{value}' is not a valid import grouping"))]
+    pub(crate) struct ParseGroupImportsError {
+        value: String,
+    }
+
+    pub(crate) fn parse_lint_level(s: &str) -> Result<LintLevel, ParseLintLevelError> {
+        Ok(match s {
+            "allow" => LintLevel::Allow,
+            "warn" => LintLevel::Warn,
+            "deny" => LintLevel::Deny,
+            "forbid" => LintLevel::Forbid,
+            value => return ParseLintLevelSnafu { value }.fail(),
+        })
+    }
+
+    #[derive(Debug, Snafu)]
+    #[snafu(display("'{value}' is not a valid lint level"))]
+    pub(crate) struct ParseLintLevelError {
+        value: String,
+    }
+
+    pub(crate) fn parse_sanitizer(s: &str) -> Result<Sanitizer, ParseSanitizerError> {
+        Ok(match s {
+            "address" => Sanitizer::Address,
+            "leak" => Sanitizer::Leak,
+            "memory" => Sanitizer::Memory,
+            "thread" => Sanitizer::Thread,
+            value => return ParseSanitizerSnafu { value }.fail(),
+        })
+    }
+
+    #[derive(Debug, Snafu)]
+    #[snafu(display("'{value}' is not a valid sanitizer"))]
+    pub(crate) struct ParseSanitizerError {
+        value: String,
+    }
+
     pub(crate) fn parse_aliasing_model(s: &str) -> Result<AliasingModel, ParseAliasingModelError> {
         Ok(match s {
             "stacked" => AliasingModel::Stacked,
@@ -1611,6 +2664,34 @@ pub(crate) mod api_orchestrator_integration_impls {
         value: String,
     }
 
+    pub(crate) fn parse_fix_tool(s: &str) -> Result<FixTool, ParseFixToolError> {
+        Ok(match s {
+            "rustc" => FixTool::Rustc,
+            "clippy" => FixTool::Clippy,
+            value => return ParseFixToolSnafu { value }.fail(),
+        })
+    }
+
+    #[derive(Debug, Snafu)]
+    #[snafu(display("'{value}' is not a valid fix tool"))]
+    pub(crate) struct ParseFixToolError {
+        value: String,
+    }
+
+    pub(crate) fn parse_doc_mode(s: &str) -> Result<DocMode, ParseDocModeError> {
+        Ok(match s {
+            "build" => DocMode::Build,
+            "test" => DocMode::Test,
+            value => return ParseDocModeSnafu { value }.fail(),
+        })
+    }
+
+    #[derive(Debug, Snafu)]
+    #[snafu(display("'{value}' is not a valid documentation mode"))]
+    pub(crate) struct ParseDocModeError {
+        value: String,
+    }
+
     impl From<gist::Gist> for api::MetaGistResponse {
         fn from(me: gist::Gist) -> Self {
             api::MetaGistResponse {
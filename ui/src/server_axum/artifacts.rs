@@ -0,0 +1,226 @@
+//! Compiled binaries and libraries are too large to embed in the
+//! JSON compilation response, so they are held here for a short time
+//! and downloaded separately.
+
+use axum::body::Bytes;
+use orchestrator::{coordinator::Artifact, TaskAbortExt as _};
+use snafu::prelude::*;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::time::{self, MissedTickBehavior};
+use tokio_util::task::AbortOnDropHandle;
+
+use crate::ArtifactConfig;
+
+/// Expired artifacts are otherwise only removed when another artifact
+/// is stored or downloaded.
+const SWEEP_PERIOD: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone)]
+pub(crate) struct StoredArtifact {
+    pub(crate) name: String,
+    pub(crate) data: Bytes,
+    expires_at: Instant,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ArtifactStore(Arc<Mutex<Inner>>);
+
+#[derive(Debug)]
+struct Inner {
+    config: ArtifactConfig,
+    artifacts: HashMap<String, StoredArtifact>,
+    total_bytes: u64,
+}
+
+impl ArtifactStore {
+    /// The returned task periodically removes expired artifacts.
+    pub(crate) fn spawn(config: ArtifactConfig) -> (AbortOnDropHandle<()>, Self) {
+        let store = Self(Arc::new(Mutex::new(Inner::new(config))));
+
+        let task = tokio::spawn({
+            let store = store.clone();
+            async move {
+                let mut interval = time::interval(SWEEP_PERIOD);
+                interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+                loop {
+                    interval.tick().await;
+                    store.lock().remove_expired(Instant::now());
+                }
+            }
+        })
+        .abort_on_drop();
+
+        (task, store)
+    }
+
+    /// Returns the identifier to download the artifact with. The
+    /// artifacts closest to expiring are evicted to make room.
+    pub(crate) fn insert(&self, artifact: Artifact) -> Result<String, InsertError> {
+        self.lock().insert(artifact, Instant::now())
+    }
+
+    pub(crate) fn get(&self, id: &str) -> Option<StoredArtifact> {
+        self.lock().get(id, Instant::now())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Inner {
+    fn new(config: ArtifactConfig) -> Self {
+        Self {
+            config,
+            artifacts: HashMap::new(),
+            total_bytes: 0,
+        }
+    }
+
+    fn insert(&mut self, artifact: Artifact, now: Instant) -> Result<String, InsertError> {
+        let Artifact { name, data } = artifact;
+        let size = data.len() as u64;
+
+        let limit = self.config.limit_bytes;
+        ensure!(size <= limit, TooLargeSnafu { size, limit });
+
+        self.remove_expired(now);
+        while self.total_bytes + size > limit {
+            self.remove_oldest();
+        }
+
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        let expires_at = now + self.config.time_to_live;
+        let data = Bytes::from(data);
+
+        self.total_bytes += size;
+        self.artifacts.insert(
+            id.clone(),
+            StoredArtifact {
+                name,
+                data,
+                expires_at,
+            },
+        );
+
+        Ok(id)
+    }
+
+    fn get(&mut self, id: &str, now: Instant) -> Option<StoredArtifact> {
+        self.remove_expired(now);
+        self.artifacts.get(id).cloned()
+    }
+
+    fn remove_expired(&mut self, now: Instant) {
+        let Self {
+            artifacts,
+            total_bytes,
+            ..
+        } = self;
+
+        artifacts.retain(|_, a| {
+            let keep = a.expires_at > now;
+            if !keep {
+                *total_bytes -= a.data.len() as u64;
+            }
+            keep
+        });
+    }
+
+    fn remove_oldest(&mut self) {
+        let oldest = self
+            .artifacts
+            .iter()
+            .min_by_key(|(_, a)| a.expires_at)
+            .map(|(id, _)| id.clone());
+
+        if let Some(a) = oldest.and_then(|id| self.artifacts.remove(&id)) {
+            self.total_bytes -= a.data.len() as u64;
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub(crate) enum InsertError {
+    #[snafu(display("The artifact is {size} bytes but only {limit} bytes may be stored"))]
+    TooLarge { size: u64, limit: u64 },
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TIME_TO_LIVE: Duration = Duration::from_secs(60);
+
+    fn inner(limit_bytes: u64) -> Inner {
+        Inner::new(ArtifactConfig {
+            limit_bytes,
+            time_to_live: TIME_TO_LIVE,
+        })
+    }
+
+    fn artifact(name: &str, size: usize) -> Artifact {
+        Artifact {
+            name: name.to_owned(),
+            data: vec![0; size],
+        }
+    }
+
+    #[test]
+    fn oldest_artifacts_are_evicted_to_make_room() {
+        let mut inner = inner(100);
+        let now = Instant::now();
+
+        let a = inner.insert(artifact("a", 40), now).unwrap();
+        let b = inner
+            .insert(artifact("b", 40), now + Duration::from_secs(1))
+            .unwrap();
+        let c = inner
+            .insert(artifact("c", 40), now + Duration::from_secs(2))
+            .unwrap();
+
+        let now = now + Duration::from_secs(3);
+        assert!(inner.get(&a, now).is_none());
+        assert_eq!(inner.get(&b, now).unwrap().name, "b");
+        assert_eq!(inner.get(&c, now).unwrap().name, "c");
+        assert_eq!(inner.total_bytes, 80);
+    }
+
+    #[test]
+    fn artifacts_expire() {
+        let mut inner = inner(100);
+        let now = Instant::now();
+
+        let a = inner.insert(artifact("a", 10), now).unwrap();
+        let b = inner
+            .insert(artifact("b", 20), now + Duration::from_secs(30))
+            .unwrap();
+
+        let now = now + TIME_TO_LIVE;
+        assert!(inner.get(&a, now).is_none());
+        assert!(inner.get(&b, now).is_some());
+        assert_eq!(inner.total_bytes, 20);
+
+        inner.remove_expired(now + TIME_TO_LIVE);
+        assert!(inner.artifacts.is_empty());
+        assert_eq!(inner.total_bytes, 0);
+    }
+
+    #[test]
+    fn artifacts_larger_than_the_limit_are_rejected() {
+        let mut inner = inner(100);
+        let now = Instant::now();
+
+        let a = inner.insert(artifact("a", 50), now).unwrap();
+        let too_large = inner.insert(artifact("b", 101), now);
+
+        assert!(matches!(too_large, Err(InsertError::TooLarge { .. })));
+        assert!(inner.get(&a, now).is_some());
+        assert_eq!(inner.total_bytes, 50);
+    }
+}
@@ -1,20 +1,25 @@
 use crate::{
     metrics::{self, record_metric, Endpoint, HasLabelsCore, Outcome},
+    public_http_api as api,
     request_database::Handle,
-    server_axum::api_orchestrator_integration_impls::*,
+    server_axum::{api_orchestrator_integration_impls::*, IsSuccess},
     WebSocketConfig,
 };
 
 use axum::extract::ws::{Message, WebSocket};
-use futures::{future::Fuse, Future, FutureExt, StreamExt, TryFutureExt};
+use futures::{
+    future::{BoxFuture, Fuse},
+    stream::{self, BoxStream},
+    Future, FutureExt, StreamExt, TryFutureExt,
+};
 use orchestrator::{
-    coordinator::{self, Coordinator, CoordinatorFactory, DockerBackend},
+    coordinator::{self, ConfiguredBackend, Coordinator, CoordinatorFactory},
     DropErrorDetailsExt,
 };
 use snafu::prelude::*;
 use std::{
     collections::BTreeMap,
-    convert::TryFrom,
+    convert::{Infallible, TryFrom},
     pin::pin,
     sync::{
         atomic::{AtomicU64, Ordering},
@@ -72,6 +77,45 @@ enum WSMessageRequest {
 
     #[serde(rename = "output/execute/wsExecuteKill")]
     ExecuteKill { meta: Meta },
+
+    #[serde(rename = "output/compile/wsCompileRequest")]
+    CompileRequest { payload: CompileRequest, meta: Meta },
+
+    #[serde(rename = "output/compile/wsCompileKill")]
+    CompileKill { meta: Meta },
+
+    #[serde(rename = "output/format/wsFormatRequest")]
+    FormatRequest { payload: FormatRequest, meta: Meta },
+
+    #[serde(rename = "output/format/wsFormatKill")]
+    FormatKill { meta: Meta },
+
+    #[serde(rename = "output/clippy/wsClippyRequest")]
+    ClippyRequest { payload: ClippyRequest, meta: Meta },
+
+    #[serde(rename = "output/clippy/wsClippyKill")]
+    ClippyKill { meta: Meta },
+
+    #[serde(rename = "output/miri/wsMiriRequest")]
+    MiriRequest { payload: MiriRequest, meta: Meta },
+
+    #[serde(rename = "output/miri/wsMiriKill")]
+    MiriKill { meta: Meta },
+
+    #[serde(rename = "output/bench/wsBenchRequest")]
+    BenchRequest { payload: BenchRequest, meta: Meta },
+
+    #[serde(rename = "output/bench/wsBenchKill")]
+    BenchKill { meta: Meta },
+
+    #[serde(rename = "output/macroExpansion/wsMacroExpansionRequest")]
+    MacroExpansionRequest {
+        payload: MacroExpansionRequest,
+        meta: Meta,
+    },
+
+    #[serde(rename = "output/macroExpansion/wsMacroExpansionKill")]
+    MacroExpansionKill { meta: Meta },
 }
 
 #[derive(serde::Deserialize)]
@@ -84,6 +128,20 @@ struct ExecuteRequest {
     tests: bool,
     code: Code,
     backtrace: bool,
+    #[serde(default)]
+    rustc_flags: Vec<String>,
+    #[serde(default)]
+    features: Vec<String>,
+    #[serde(default)]
+    envs: BTreeMap<String, String>,
+    #[serde(default)]
+    json_diagnostics: bool,
+    #[serde(default)]
+    test_options: api::TestOptions,
+    #[serde(default)]
+    sanitizer: Option<String>,
+    #[serde(default)]
+    coverage: bool,
 }
 
 impl TryFrom<ExecuteRequest> for coordinator::ExecuteRequest {
@@ -98,15 +156,29 @@ impl TryFrom<ExecuteRequest> for coordinator::ExecuteRequest {
             tests,
             code,
             backtrace,
+            rustc_flags,
+            features,
+            envs,
+            json_diagnostics,
+            test_options,
+            sanitizer,
+            coverage,
         } = value;
 
+        let channel = parse_channel(&channel)?;
+
         Ok(coordinator::ExecuteRequest {
-            channel: parse_channel(&channel)?,
+            channel,
             mode: parse_mode(&mode)?,
             edition: parse_edition(&edition)?,
             crate_type: parse_crate_type(&crate_type)?,
             tests,
             backtrace,
+            test_options: coordinator::TestOptions::try_from(test_options)?,
+            extra: coordinator::ExtraOptions::new(channel, rustc_flags, features, envs)?,
+            json_diagnostics,
+            sanitizer: sanitizer.as_deref().map(parse_sanitizer).transpose()?,
+            coverage,
             code: code.into(),
         })
     }
@@ -125,16 +197,392 @@ pub(crate) enum ExecuteRequestParseError {
 
     #[snafu(transparent)]
     Edition { source: ParseEditionError },
+
+    #[snafu(transparent)]
+    ExtraOptions {
+        source: coordinator::ExtraOptionsError,
+    },
+
+    #[snafu(transparent)]
+    TestOptions {
+        source: coordinator::TestFilterError,
+    },
+
+    #[snafu(transparent)]
+    Sanitizer { source: ParseSanitizerError },
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompileRequest {
+    target: String,
+    #[serde(default)]
+    target_triple: Option<String>,
+    assembly_flavor: Option<String>,
+    demangle_assembly: Option<String>,
+    process_assembly: Option<String>,
+    #[serde(default)]
+    mir_opt_level: Option<String>,
+    #[serde(default)]
+    mir_pass: Option<String>,
+    channel: String,
+    mode: String,
+    edition: String,
+    crate_type: String,
+    tests: bool,
+    code: Code,
+    backtrace: bool,
+    #[serde(default)]
+    rustc_flags: Vec<String>,
+    #[serde(default)]
+    features: Vec<String>,
+    #[serde(default)]
+    envs: BTreeMap<String, String>,
+    #[serde(default)]
+    json_diagnostics: bool,
+}
+
+impl TryFrom<CompileRequest> for coordinator::CompileRequest {
+    type Error = CompileRequestParseError;
+
+    fn try_from(value: CompileRequest) -> Result<Self, Self::Error> {
+        let CompileRequest {
+            target,
+            target_triple,
+            assembly_flavor,
+            demangle_assembly,
+            process_assembly,
+            mir_opt_level,
+            mir_pass,
+            channel,
+            mode,
+            edition,
+            crate_type,
+            tests,
+            code,
+            backtrace,
+            rustc_flags,
+            features,
+            envs,
+            json_diagnostics,
+        } = value;
+
+        let channel = parse_channel(&channel)?;
+
+        Ok(coordinator::CompileRequest {
+            target: parse_target(
+                &target,
+                assembly_flavor.as_deref(),
+                demangle_assembly.as_deref(),
+                process_assembly.as_deref(),
+                mir_opt_level.as_deref(),
+                mir_pass.as_deref(),
+            )?,
+            target_triple: target_triple
+                .as_deref()
+                .map(parse_target_triple)
+                .transpose()?,
+            channel,
+            crate_type: parse_crate_type(&crate_type)?,
+            mode: parse_mode(&mode)?,
+            edition: parse_edition(&edition)?,
+            tests,
+            backtrace,
+            extra: coordinator::ExtraOptions::new(channel, rustc_flags, features, envs)?,
+            json_diagnostics,
+            // Only available through the HTTP API
+            export_artifact: false,
+            code: code.into(),
+        })
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub(crate) enum CompileRequestParseError {
+    #[snafu(transparent)]
+    Target { source: ParseCompileTargetError },
+
+    #[snafu(transparent)]
+    TargetTriple { source: ParseTargetTripleError },
+
+    #[snafu(transparent)]
+    Channel { source: ParseChannelError },
+
+    #[snafu(transparent)]
+    CrateType { source: ParseCrateTypeError },
+
+    #[snafu(transparent)]
+    Mode { source: ParseModeError },
+
+    #[snafu(transparent)]
+    Edition { source: ParseEditionError },
+
+    #[snafu(transparent)]
+    ExtraOptions {
+        source: coordinator::ExtraOptionsError,
+    },
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FormatRequest {
+    channel: String,
+    crate_type: String,
+    edition: String,
+    #[serde(default)]
+    rustfmt_options: api::RustfmtOptions,
+    code: Code,
+}
+
+impl TryFrom<FormatRequest> for coordinator::FormatRequest {
+    type Error = FormatRequestParseError;
+
+    fn try_from(value: FormatRequest) -> Result<Self, Self::Error> {
+        let FormatRequest {
+            channel,
+            crate_type,
+            edition,
+            rustfmt_options,
+            code,
+        } = value;
+
+        Ok(coordinator::FormatRequest {
+            channel: parse_channel(&channel)?,
+            crate_type: parse_crate_type(&crate_type)?,
+            edition: parse_edition(&edition)?,
+            rustfmt_options: coordinator::RustfmtOptions::try_from(rustfmt_options)?,
+            code: code.into(),
+        })
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub(crate) enum FormatRequestParseError {
+    #[snafu(transparent)]
+    Channel { source: ParseChannelError },
+
+    #[snafu(transparent)]
+    CrateType { source: ParseCrateTypeError },
+
+    #[snafu(transparent)]
+    Edition { source: ParseEditionError },
+
+    #[snafu(transparent)]
+    RustfmtOptions { source: ParseRustfmtOptionsError },
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClippyRequest {
+    channel: String,
+    crate_type: String,
+    edition: String,
+    code: Code,
+    #[serde(default)]
+    json_diagnostics: bool,
+    #[serde(default)]
+    lints: api::ClippyLints,
+}
+
+impl TryFrom<ClippyRequest> for coordinator::ClippyRequest {
+    type Error = ClippyRequestParseError;
+
+    fn try_from(value: ClippyRequest) -> Result<Self, Self::Error> {
+        let ClippyRequest {
+            channel,
+            crate_type,
+            edition,
+            code,
+            json_diagnostics,
+            lints,
+        } = value;
+
+        Ok(coordinator::ClippyRequest {
+            channel: parse_channel(&channel)?,
+            crate_type: parse_crate_type(&crate_type)?,
+            edition: parse_edition(&edition)?,
+            json_diagnostics,
+            lints: coordinator::ClippyLints::try_from(lints)?,
+            code: code.into(),
+        })
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub(crate) enum ClippyRequestParseError {
+    #[snafu(transparent)]
+    Channel { source: ParseChannelError },
+
+    #[snafu(transparent)]
+    CrateType { source: ParseCrateTypeError },
+
+    #[snafu(transparent)]
+    Edition { source: ParseEditionError },
+
+    #[snafu(transparent)]
+    Lints { source: ParseClippyLintsError },
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MiriRequest {
+    crate_type: String,
+    edition: String,
+    tests: bool,
+    aliasing_model: String,
+    #[serde(default)]
+    options: api::MiriOptions,
+    code: Code,
+}
+
+impl TryFrom<MiriRequest> for coordinator::MiriRequest {
+    type Error = MiriRequestParseError;
+
+    fn try_from(value: MiriRequest) -> Result<Self, Self::Error> {
+        let MiriRequest {
+            crate_type,
+            edition,
+            tests,
+            aliasing_model,
+            options,
+            code,
+        } = value;
+
+        Ok(coordinator::MiriRequest {
+            channel: coordinator::Channel::Nightly, // Miri is only available on nightly
+            crate_type: parse_crate_type(&crate_type)?,
+            edition: parse_edition(&edition)?,
+            tests,
+            aliasing_model: parse_aliasing_model(&aliasing_model)?,
+            options: coordinator::MiriOptions::try_from(options)?,
+            code: code.into(),
+        })
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub(crate) enum MiriRequestParseError {
+    #[snafu(transparent)]
+    CrateType { source: ParseCrateTypeError },
+
+    #[snafu(transparent)]
+    Edition { source: ParseEditionError },
+
+    #[snafu(transparent)]
+    AliasingModel { source: ParseAliasingModelError },
+
+    #[snafu(transparent)]
+    Options {
+        source: coordinator::MiriOptionsError,
+    },
 }
 
 #[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BenchRequest {
+    channel: String,
+    crate_type: String,
+    edition: String,
+    #[serde(default)]
+    filter: Option<String>,
+    #[serde(default)]
+    time_limit_secs: Option<u64>,
+    code: Code,
+}
+
+impl TryFrom<BenchRequest> for coordinator::BenchRequest {
+    type Error = BenchRequestParseError;
+
+    fn try_from(value: BenchRequest) -> Result<Self, Self::Error> {
+        let BenchRequest {
+            channel,
+            crate_type,
+            edition,
+            filter,
+            time_limit_secs,
+            code,
+        } = value;
+
+        let time_limit = time_limit_secs.map_or(
+            coordinator::BenchRequest::MAX_TIME_LIMIT,
+            Duration::from_secs,
+        );
+
+        let request = coordinator::BenchRequest {
+            channel: parse_channel(&channel)?,
+            crate_type: parse_crate_type(&crate_type)?,
+            edition: parse_edition(&edition)?,
+            filter,
+            time_limit,
+            code: code.into(),
+        };
+        request.validate()?;
+
+        Ok(request)
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub(crate) enum BenchRequestParseError {
+    #[snafu(transparent)]
+    Channel { source: ParseChannelError },
+
+    #[snafu(transparent)]
+    CrateType { source: ParseCrateTypeError },
+
+    #[snafu(transparent)]
+    Edition { source: ParseEditionError },
+
+    #[snafu(transparent)]
+    Request {
+        source: coordinator::BenchRequestError,
+    },
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MacroExpansionRequest {
+    crate_type: String,
+    edition: String,
+    code: Code,
+}
+
+impl TryFrom<MacroExpansionRequest> for coordinator::MacroExpansionRequest {
+    type Error = MacroExpansionRequestParseError;
+
+    fn try_from(value: MacroExpansionRequest) -> Result<Self, Self::Error> {
+        let MacroExpansionRequest {
+            crate_type,
+            edition,
+            code,
+        } = value;
+
+        Ok(coordinator::MacroExpansionRequest {
+            channel: coordinator::Channel::Nightly, // `-Zunpretty` is only available on nightly
+            crate_type: parse_crate_type(&crate_type)?,
+            edition: parse_edition(&edition)?,
+            code: code.into(),
+        })
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub(crate) enum MacroExpansionRequestParseError {
+    #[snafu(transparent)]
+    CrateType { source: ParseCrateTypeError },
+
+    #[snafu(transparent)]
+    Edition { source: ParseEditionError },
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
 #[serde(untagged)]
 enum Code {
     Single(String),
     Multiple(Vec<CodeFile>),
 }
 
-#[derive(serde::Deserialize)]
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
 struct CodeFile {
     name: String,
     content: String,
@@ -151,6 +599,17 @@ impl From<Code> for coordinator::Code {
     }
 }
 
+impl From<coordinator::Code> for Code {
+    fn from(value: coordinator::Code) -> Self {
+        match value {
+            coordinator::Code::Single(c) => Code::Single(c),
+            coordinator::Code::Multiple(f) => {
+                Code::Multiple(f.into_iter().map(Into::into).collect())
+            }
+        }
+    }
+}
+
 impl From<CodeFile> for coordinator::CodeFile {
     fn from(value: CodeFile) -> Self {
         let CodeFile { name, content } = value;
@@ -158,6 +617,13 @@ impl From<CodeFile> for coordinator::CodeFile {
     }
 }
 
+impl From<coordinator::CodeFile> for CodeFile {
+    fn from(value: coordinator::CodeFile) -> Self {
+        let coordinator::CodeFile { name, content } = value;
+        Self { name, content }
+    }
+}
+
 #[derive(Debug, serde::Serialize)]
 #[serde(tag = "type")]
 enum MessageResponse {
@@ -179,11 +645,101 @@ enum MessageResponse {
     #[serde(rename = "output/execute/wsExecuteStatus")]
     ExecuteStatus { payload: ExecuteStatus, meta: Meta },
 
+    #[serde(rename = "output/execute/wsExecuteTest")]
+    ExecuteTest {
+        payload: api::TestResult,
+        meta: Meta,
+    },
+
     #[serde(rename = "output/execute/wsExecuteEnd")]
     ExecuteEnd {
         payload: ExecuteResponse,
         meta: Meta,
     },
+
+    #[serde(rename = "output/compile/wsCompileBegin")]
+    CompileBegin { meta: Meta },
+
+    #[serde(rename = "output/compile/wsCompileStdout")]
+    CompileStdout { payload: String, meta: Meta },
+
+    #[serde(rename = "output/compile/wsCompileStderr")]
+    CompileStderr { payload: String, meta: Meta },
+
+    #[serde(rename = "output/compile/wsCompileEnd")]
+    CompileEnd {
+        payload: CompileResponse,
+        meta: Meta,
+    },
+
+    #[serde(rename = "output/format/wsFormatBegin")]
+    FormatBegin { meta: Meta },
+
+    #[serde(rename = "output/format/wsFormatStdout")]
+    FormatStdout { payload: String, meta: Meta },
+
+    #[serde(rename = "output/format/wsFormatStderr")]
+    FormatStderr { payload: String, meta: Meta },
+
+    #[serde(rename = "output/format/wsFormatEnd")]
+    FormatEnd { payload: FormatResponse, meta: Meta },
+
+    #[serde(rename = "output/clippy/wsClippyBegin")]
+    ClippyBegin { meta: Meta },
+
+    #[serde(rename = "output/clippy/wsClippyStdout")]
+    ClippyStdout { payload: String, meta: Meta },
+
+    #[serde(rename = "output/clippy/wsClippyStderr")]
+    ClippyStderr { payload: String, meta: Meta },
+
+    #[serde(rename = "output/clippy/wsClippyEnd")]
+    ClippyEnd { payload: ClippyResponse, meta: Meta },
+
+    #[serde(rename = "output/miri/wsMiriBegin")]
+    MiriBegin { meta: Meta },
+
+    #[serde(rename = "output/miri/wsMiriStdout")]
+    MiriStdout { payload: String, meta: Meta },
+
+    #[serde(rename = "output/miri/wsMiriStderr")]
+    MiriStderr { payload: String, meta: Meta },
+
+    #[serde(rename = "output/miri/wsMiriEnd")]
+    MiriEnd { payload: MiriResponse, meta: Meta },
+
+    #[serde(rename = "output/bench/wsBenchBegin")]
+    BenchBegin { meta: Meta },
+
+    #[serde(rename = "output/bench/wsBenchStdout")]
+    BenchStdout { payload: String, meta: Meta },
+
+    #[serde(rename = "output/bench/wsBenchStderr")]
+    BenchStderr { payload: String, meta: Meta },
+
+    #[serde(rename = "output/bench/wsBenchResult")]
+    BenchResult {
+        payload: api::BenchResult,
+        meta: Meta,
+    },
+
+    #[serde(rename = "output/bench/wsBenchEnd")]
+    BenchEnd { payload: BenchResponse, meta: Meta },
+
+    #[serde(rename = "output/macroExpansion/wsMacroExpansionBegin")]
+    MacroExpansionBegin { meta: Meta },
+
+    #[serde(rename = "output/macroExpansion/wsMacroExpansionStdout")]
+    MacroExpansionStdout { payload: String, meta: Meta },
+
+    #[serde(rename = "output/macroExpansion/wsMacroExpansionStderr")]
+    MacroExpansionStderr { payload: String, meta: Meta },
+
+    #[serde(rename = "output/macroExpansion/wsMacroExpansionEnd")]
+    MacroExpansionEnd {
+        payload: MacroExpansionResponse,
+        meta: Meta,
+    },
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -232,6 +788,57 @@ impl From<orchestrator::coordinator::ExecuteStatus> for ExecuteStatus {
 struct ExecuteResponse {
     success: bool,
     exit_detail: String,
+    diagnostics: Vec<api::Diagnostic>,
+    tests: Vec<api::TestResult>,
+    sanitizer_reports: Vec<api::SanitizerReport>,
+    coverage: Vec<api::FileCoverage>,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CompileResponse {
+    success: bool,
+    exit_detail: String,
+    code: String,
+    diagnostics: Vec<api::Diagnostic>,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FormatResponse {
+    success: bool,
+    exit_detail: String,
+    code: Code,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ClippyResponse {
+    success: bool,
+    exit_detail: String,
+    diagnostics: Vec<api::Diagnostic>,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MiriResponse {
+    success: bool,
+    exit_detail: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BenchResponse {
+    success: bool,
+    exit_detail: String,
+    benchmarks: Vec<api::BenchResult>,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MacroExpansionResponse {
+    success: bool,
+    exit_detail: String,
 }
 
 #[instrument(skip_all, fields(ws_id))]
@@ -289,7 +896,7 @@ pub(crate) async fn handle(
 
 type TaggedError = (Error, Option<Meta>);
 type ResponseTx = mpsc::Sender<Result<MessageResponse, TaggedError>>;
-type SharedCoordinator = Arc<Coordinator<DockerBackend>>;
+type SharedCoordinator = Arc<Coordinator<ConfiguredBackend>>;
 
 /// Manages a limited amount of access to the `Coordinator`.
 ///
@@ -311,12 +918,18 @@ struct CoordinatorManager {
 impl CoordinatorManager {
     const N_PARALLEL: usize = 2;
 
-    const N_KINDS: usize = 1;
+    const N_KINDS: usize = 7;
     const KIND_EXECUTE: usize = 0;
+    const KIND_COMPILE: usize = 1;
+    const KIND_FORMAT: usize = 2;
+    const KIND_CLIPPY: usize = 3;
+    const KIND_MIRI: usize = 4;
+    const KIND_MACRO_EXPANSION: usize = 5;
+    const KIND_BENCH: usize = 6;
 
     fn new(factory: &CoordinatorFactory) -> Self {
         Self {
-            coordinator: Arc::new(factory.build()),
+            coordinator: Arc::new(factory.build_configured()),
             tasks: Default::default(),
             semaphore: Arc::new(Semaphore::new(Self::N_PARALLEL)),
             abort_handles: Default::default(),
@@ -333,7 +946,7 @@ impl CoordinatorManager {
         self.tasks.join_next().await
     }
 
-    async fn spawn<F, Fut>(&mut self, handler: F) -> CoordinatorManagerResult<()>
+    async fn spawn<F, Fut>(&mut self, kind: usize, handler: F) -> CoordinatorManagerResult<()>
     where
         F: FnOnce(SharedCoordinator) -> Fut,
         F: 'static + Send,
@@ -351,7 +964,6 @@ impl CoordinatorManager {
             .in_current_span(),
         );
 
-        let kind = Self::KIND_EXECUTE; // TODO: parameterize when we get a second kind
         let old_abort_handle = self.abort_handles[kind].replace(new_abort_handle);
 
         if let Some(abort_handle) = old_abort_handle {
@@ -437,6 +1049,7 @@ async fn handle_core(
     let mut idle_timeout = pin!(Fuse::terminated());
 
     let mut active_executions = BTreeMap::new();
+    let mut active_jobs = BTreeMap::new();
     let mut active_execution_gc_interval = time::interval(Duration::from_secs(30));
 
     loop {
@@ -478,7 +1091,15 @@ async fn handle_core(
                     None => break,
 
                     Some(Ok(Message::Text(txt))) => {
-                        handle_msg(&txt, &tx, &mut manager, &mut active_executions, &db).await
+                        handle_msg(
+                            &txt,
+                            &tx,
+                            &mut manager,
+                            &mut active_executions,
+                            &mut active_jobs,
+                            &db,
+                        )
+                        .await
                     }
 
                     // unknown message type
@@ -542,6 +1163,7 @@ async fn handle_core(
             GarbageCollection => {
                 active_executions
                     .retain(|_id, (_, tx)| tx.as_ref().is_some_and(|tx| !tx.is_closed()));
+                active_jobs.retain(|_id, token: &mut CancellationToken| !token.is_cancelled());
             }
 
             IdleTimeout | IdleRequest => {
@@ -626,6 +1248,7 @@ async fn handle_msg(
     tx: &ResponseTx,
     manager: &mut CoordinatorManager,
     active_executions: &mut BTreeMap<i64, ActiveExecutionInfo>,
+    active_jobs: &mut ActiveJobs,
     db: &Handle,
 ) {
     use WSMessageRequest::*;
@@ -644,9 +1267,10 @@ async fn handle_msg(
                 (token.clone().drop_guard(), Some(execution_tx)),
             );
 
-            // TODO: Should a single execute / build / etc. session have a timeout of some kind?
+            // Each process is stopped by the worker once the
+            // configured process timeout has passed.
             let spawned = manager
-                .spawn({
+                .spawn(CoordinatorManager::KIND_EXECUTE, {
                     let tx = tx.clone();
                     let meta = meta.clone();
                     async |coordinator| {
@@ -706,12 +1330,210 @@ async fn handle_msg(
             drop(token);
         }
 
+        Ok(CompileRequest { payload, meta }) => {
+            let guard = db.clone().start_with_guard("ws.Compile", txt).await;
+
+            spawn_job(
+                manager,
+                active_jobs,
+                tx,
+                CoordinatorManager::KIND_COMPILE,
+                meta,
+                async |token, tx, coordinator, meta| {
+                    let r = handle_job::<CompileJob>(token, tx, coordinator, payload, meta.clone())
+                        .context(StreamingCompileSnafu)
+                        .map_err(|e| (e, Some(meta)))
+                        .await;
+
+                    guard.complete_now(r)
+                },
+            )
+            .await;
+        }
+
+        Ok(FormatRequest { payload, meta }) => {
+            let guard = db.clone().start_with_guard("ws.Format", txt).await;
+
+            spawn_job(
+                manager,
+                active_jobs,
+                tx,
+                CoordinatorManager::KIND_FORMAT,
+                meta,
+                async |token, tx, coordinator, meta| {
+                    let r = handle_job::<FormatJob>(token, tx, coordinator, payload, meta.clone())
+                        .context(StreamingFormatSnafu)
+                        .map_err(|e| (e, Some(meta)))
+                        .await;
+
+                    guard.complete_now(r)
+                },
+            )
+            .await;
+        }
+
+        Ok(ClippyRequest { payload, meta }) => {
+            let guard = db.clone().start_with_guard("ws.Clippy", txt).await;
+
+            spawn_job(
+                manager,
+                active_jobs,
+                tx,
+                CoordinatorManager::KIND_CLIPPY,
+                meta,
+                async |token, tx, coordinator, meta| {
+                    let r = handle_job::<ClippyJob>(token, tx, coordinator, payload, meta.clone())
+                        .context(StreamingClippySnafu)
+                        .map_err(|e| (e, Some(meta)))
+                        .await;
+
+                    guard.complete_now(r)
+                },
+            )
+            .await;
+        }
+
+        Ok(MiriRequest { payload, meta }) => {
+            let guard = db.clone().start_with_guard("ws.Miri", txt).await;
+
+            spawn_job(
+                manager,
+                active_jobs,
+                tx,
+                CoordinatorManager::KIND_MIRI,
+                meta,
+                async |token, tx, coordinator, meta| {
+                    let r = handle_job::<MiriJob>(token, tx, coordinator, payload, meta.clone())
+                        .context(StreamingMiriSnafu)
+                        .map_err(|e| (e, Some(meta)))
+                        .await;
+
+                    guard.complete_now(r)
+                },
+            )
+            .await;
+        }
+
+        Ok(BenchRequest { payload, meta }) => {
+            let guard = db.clone().start_with_guard("ws.Bench", txt).await;
+
+            spawn_job(
+                manager,
+                active_jobs,
+                tx,
+                CoordinatorManager::KIND_BENCH,
+                meta,
+                async |token, tx, coordinator, meta| {
+                    let r = handle_job::<BenchJob>(token, tx, coordinator, payload, meta.clone())
+                        .context(StreamingBenchSnafu)
+                        .map_err(|e| (e, Some(meta)))
+                        .await;
+
+                    guard.complete_now(r)
+                },
+            )
+            .await;
+        }
+
+        Ok(MacroExpansionRequest { payload, meta }) => {
+            let guard = db.clone().start_with_guard("ws.MacroExpansion", txt).await;
+
+            spawn_job(
+                manager,
+                active_jobs,
+                tx,
+                CoordinatorManager::KIND_MACRO_EXPANSION,
+                meta,
+                async |token, tx, coordinator, meta| {
+                    let r = handle_job::<MacroExpansionJob>(
+                        token,
+                        tx,
+                        coordinator,
+                        payload,
+                        meta.clone(),
+                    )
+                    .context(StreamingMacroExpansionSnafu)
+                    .map_err(|e| (e, Some(meta)))
+                    .await;
+
+                    guard.complete_now(r)
+                },
+            )
+            .await;
+        }
+
+        Ok(CompileKill { meta }) => kill_job(active_jobs, CoordinatorManager::KIND_COMPILE, meta),
+
+        Ok(FormatKill { meta }) => kill_job(active_jobs, CoordinatorManager::KIND_FORMAT, meta),
+
+        Ok(ClippyKill { meta }) => kill_job(active_jobs, CoordinatorManager::KIND_CLIPPY, meta),
+
+        Ok(MiriKill { meta }) => kill_job(active_jobs, CoordinatorManager::KIND_MIRI, meta),
+
+        Ok(BenchKill { meta }) => kill_job(active_jobs, CoordinatorManager::KIND_BENCH, meta),
+
+        Ok(MacroExpansionKill { meta }) => {
+            kill_job(active_jobs, CoordinatorManager::KIND_MACRO_EXPANSION, meta)
+        }
+
         Err(e) => {
             tx.send(Err((e, None))).await.ok(/* We don't care if the channel is closed */);
         }
     }
 }
 
+/// Jobs that can be killed by the client, by kind and sequence number.
+type ActiveJobs = BTreeMap<(usize, i64), CancellationToken>;
+
+/// Spawns a job that only streams output back to the client.
+///
+/// The job can be cancelled by its kind and sequence number until it
+/// completes.
+async fn spawn_job<F, Fut>(
+    manager: &mut CoordinatorManager,
+    active_jobs: &mut ActiveJobs,
+    tx: &ResponseTx,
+    kind: usize,
+    meta: Meta,
+    handler: F,
+) where
+    F: FnOnce(CancellationToken, ResponseTx, SharedCoordinator, Meta) -> Fut,
+    F: 'static + Send,
+    Fut: Future<Output = Result<(), TaggedError>>,
+    Fut: 'static + Send,
+{
+    let token = CancellationToken::new();
+    active_jobs.insert((kind, meta.sequence_number), token.clone());
+
+    // Marks the job as no longer active, even if the task is aborted
+    // by a newer job of the same kind before it ever runs.
+    let guard = token.clone().drop_guard();
+
+    let spawned = manager
+        .spawn(kind, {
+            let tx = tx.clone();
+            let meta = meta.clone();
+            async move |coordinator| {
+                let _guard = guard;
+                handler(token, tx, coordinator, meta).await
+            }
+        })
+        .await
+        .context(StreamingCoordinatorSpawnSnafu);
+
+    if let Err(e) = spawned {
+        tx.send(Err((e, Some(meta)))).await.ok(/* We don't care if the channel is closed */);
+    }
+}
+
+fn kill_job(active_jobs: &mut ActiveJobs, kind: usize, meta: Meta) {
+    let Some(token) = active_jobs.remove(&(kind, meta.sequence_number)) else {
+        warn!("Received kill for a job that is no longer active");
+        return;
+    };
+    token.cancel();
+}
+
 #[derive(Debug)]
 enum CompletedOrAbandoned<T> {
     Abandoned,
@@ -775,6 +1597,7 @@ async fn handle_execute_inner(
         mut stdout_rx,
         mut stderr_rx,
         mut status_rx,
+        mut test_rx,
     } = coordinator
         .begin_execute(token, req.clone())
         .await
@@ -799,6 +1622,13 @@ async fn handle_execute_inner(
             .await
     };
 
+    let send_test = async |test: coordinator::TestResult| {
+        let payload = test.into();
+        let meta = meta.clone();
+        tx.send(Ok(MessageResponse::ExecuteTest { payload, meta }))
+            .await
+    };
+
     let mut reported = false;
 
     let status = loop {
@@ -807,6 +1637,7 @@ async fn handle_execute_inner(
             Stdout(String),
             Stderr(String),
             Status(coordinator::ExecuteStatus),
+            Test(coordinator::TestResult),
         }
         use Event::*;
 
@@ -819,7 +1650,9 @@ async fn handle_execute_inner(
 
             Some(stderr) = stderr_rx.recv() => Stderr(stderr),
 
-            Some(status) = status_rx.next() => Status(status)
+            Some(status) = status_rx.next() => Status(status),
+
+            Some(test) = test_rx.next() => Test(test)
         };
 
         match event {
@@ -860,6 +1693,11 @@ async fn handle_execute_inner(
                     .await;
                 abandon_if_closed!(sent);
             }
+
+            Test(test) => {
+                let sent = send_test(test).await;
+                abandon_if_closed!(sent);
+            }
         }
     };
 
@@ -874,12 +1712,21 @@ async fn handle_execute_inner(
         abandon_if_closed!(sent);
     }
 
+    while let Some(Some(test)) = test_rx.next().now_or_never() {
+        let sent = send_test(test).await;
+        abandon_if_closed!(sent);
+    }
+
     let status = status.context(EndSnafu)?;
     let outcome = Outcome::from_success(&status);
 
     let coordinator::ExecuteResponse {
         success,
         exit_detail,
+        diagnostics,
+        tests,
+        sanitizer_reports,
+        coverage,
     } = status;
 
     let sent = tx
@@ -887,6 +1734,10 @@ async fn handle_execute_inner(
             payload: ExecuteResponse {
                 success,
                 exit_detail,
+                diagnostics: diagnostics.into_iter().map(Into::into).collect(),
+                tests: tests.into_iter().map(Into::into).collect(),
+                sanitizer_reports: sanitizer_reports.into_iter().map(Into::into).collect(),
+                coverage: coverage.into_iter().map(Into::into).collect(),
             },
             meta,
         }))
@@ -916,6 +1767,650 @@ pub(crate) enum ExecuteError {
 
 type ExecuteResult<T, E = ExecuteError> = std::result::Result<T, E>;
 
+/// Forwards the stdout and stderr of a job to the client until the
+/// job completes.
+async fn stream_output<T>(
+    tx: &ResponseTx,
+    meta: &Meta,
+    mut task: BoxFuture<'static, T>,
+    mut stdout_rx: mpsc::Receiver<String>,
+    mut stderr_rx: mpsc::Receiver<String>,
+    stdout_response: fn(String, Meta) -> MessageResponse,
+    stderr_response: fn(String, Meta) -> MessageResponse,
+) -> CompletedOrAbandoned<T> {
+    use CompletedOrAbandoned::*;
+
+    enum Event {
+        Stdout(String),
+        Stderr(String),
+    }
+    use Event::*;
+
+    let send = async |event| {
+        let response = match event {
+            Stdout(stdout) => stdout_response(stdout, meta.clone()),
+            Stderr(stderr) => stderr_response(stderr, meta.clone()),
+        };
+        tx.send(Ok(response)).await
+    };
+
+    let response = loop {
+        let event = tokio::select! {
+            response = &mut task => break response,
+
+            Some(stdout) = stdout_rx.recv() => Stdout(stdout),
+
+            Some(stderr) = stderr_rx.recv() => Stderr(stderr),
+        };
+
+        if send(event).await.is_err() {
+            return Abandoned;
+        }
+    };
+
+    // Keep the remaining output in order until both streams are closed
+    loop {
+        let event = tokio::select! {
+            Some(stdout) = stdout_rx.recv() => Stdout(stdout),
+
+            Some(stderr) = stderr_rx.recv() => Stderr(stderr),
+
+            else => break,
+        };
+
+        if send(event).await.is_err() {
+            return Abandoned;
+        }
+    }
+
+    Completed(response)
+}
+
+/// A job that streams its output to the client while it runs and
+/// then sends a single response.
+trait StreamingJob {
+    /// The request as sent by the client.
+    type ClientRequest;
+    type ParseError: std::error::Error + 'static;
+    type Request: HasLabelsCore + TryFrom<Self::ClientRequest, Error = Self::ParseError>;
+    type Response: IsSuccess;
+    type Error: std::error::Error + 'static;
+    /// Reported to the client while the job runs, in addition to its
+    /// output.
+    type Progress;
+
+    const ENDPOINT: Endpoint;
+
+    fn begin(
+        coordinator: SharedCoordinator,
+        token: CancellationToken,
+        req: Self::Request,
+    ) -> BoxFuture<'static, Result<ActiveJob<Self>, Self::Error>>;
+
+    fn begin_message(meta: Meta) -> MessageResponse;
+
+    fn stdout_message(payload: String, meta: Meta) -> MessageResponse;
+
+    fn stderr_message(payload: String, meta: Meta) -> MessageResponse;
+
+    fn progress_message(progress: Self::Progress, meta: Meta) -> MessageResponse;
+
+    fn end_message(response: Self::Response, meta: Meta) -> MessageResponse;
+}
+
+struct ActiveJob<J: StreamingJob + ?Sized> {
+    permit: Box<dyn coordinator::ProcessPermit>,
+    task: BoxFuture<'static, Result<J::Response, J::Error>>,
+    stdout_rx: mpsc::Receiver<String>,
+    stderr_rx: mpsc::Receiver<String>,
+    progress: BoxStream<'static, J::Progress>,
+}
+
+async fn handle_job<J: StreamingJob>(
+    token: CancellationToken,
+    tx: ResponseTx,
+    coordinator: SharedCoordinator,
+    req: J::ClientRequest,
+    meta: Meta,
+) -> JobResult<(), J::ParseError, J::Error> {
+    use job_error::*;
+    use CompletedOrAbandoned::*;
+
+    let req = J::Request::try_from(req).context(BadRequestSnafu)?;
+
+    let labels_core = req.labels_core();
+
+    let start = Instant::now();
+    let v = handle_job_inner::<J>(token, tx, coordinator, req, meta).await;
+    let elapsed = start.elapsed();
+
+    let outcome = match &v {
+        Ok(Abandoned) => Outcome::Abandoned,
+        Ok(Completed(v)) => *v,
+        Err(_) => Outcome::ErrorServer,
+    };
+
+    record_metric(J::ENDPOINT, labels_core, outcome, elapsed);
+
+    v?;
+    Ok(())
+}
+
+async fn handle_job_inner<J: StreamingJob>(
+    token: CancellationToken,
+    tx: ResponseTx,
+    coordinator: SharedCoordinator,
+    req: J::Request,
+    meta: Meta,
+) -> JobResult<CompletedOrAbandoned<Outcome>, J::ParseError, J::Error> {
+    use job_error::*;
+    use CompletedOrAbandoned::*;
+
+    let ActiveJob {
+        permit: _permit,
+        task,
+        stdout_rx,
+        stderr_rx,
+        mut progress,
+    } = J::begin(coordinator, token, req)
+        .await
+        .context(BeginSnafu)?;
+
+    let sent = tx.send(Ok(J::begin_message(meta.clone()))).await;
+    abandon_if_closed!(sent);
+
+    let output = stream_output(
+        &tx,
+        &meta,
+        task,
+        stdout_rx,
+        stderr_rx,
+        J::stdout_message,
+        J::stderr_message,
+    );
+
+    // The stream of progress ends once the task has completed
+    let progress = async {
+        while let Some(progress) = progress.next().await {
+            let sent = tx
+                .send(Ok(J::progress_message(progress, meta.clone())))
+                .await;
+            if sent.is_err() {
+                return Abandoned;
+            }
+        }
+        Completed(())
+    };
+
+    let (response, progress) = futures::join!(output, progress);
+
+    let (Completed(response), Completed(())) = (response, progress) else {
+        return Ok(Abandoned);
+    };
+
+    let response = response.context(EndSnafu)?;
+    let outcome = Outcome::from_success(&response);
+
+    let sent = tx.send(Ok(J::end_message(response, meta))).await;
+    abandon_if_closed!(sent);
+
+    Ok(Completed(outcome))
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+pub(crate) enum JobError<P, E>
+where
+    P: std::error::Error + 'static,
+    E: std::error::Error + 'static,
+{
+    #[snafu(display("The request could not be parsed"))]
+    BadRequest { source: P },
+
+    #[snafu(display("Could not begin the session"))]
+    Begin { source: E },
+
+    #[snafu(display("Could not end the session"))]
+    End { source: E },
+}
+
+type JobResult<T, P, E> = std::result::Result<T, JobError<P, E>>;
+
+pub(crate) type CompileError = JobError<CompileRequestParseError, coordinator::CompileError>;
+
+struct CompileJob;
+
+impl StreamingJob for CompileJob {
+    type ClientRequest = CompileRequest;
+    type ParseError = CompileRequestParseError;
+    type Request = coordinator::CompileRequest;
+    type Response = coordinator::CompileResponse;
+    type Error = coordinator::CompileError;
+    type Progress = Infallible;
+
+    const ENDPOINT: Endpoint = Endpoint::Compile;
+
+    fn begin(
+        coordinator: SharedCoordinator,
+        token: CancellationToken,
+        req: Self::Request,
+    ) -> BoxFuture<'static, Result<ActiveJob<Self>, Self::Error>> {
+        async move {
+            let coordinator::ActiveCompilation {
+                permit,
+                task,
+                stdout_rx,
+                stderr_rx,
+            } = coordinator.begin_compile(token, req).await?;
+
+            Ok(ActiveJob {
+                permit,
+                task,
+                stdout_rx,
+                stderr_rx,
+                progress: stream::empty().boxed(),
+            })
+        }
+        .boxed()
+    }
+
+    fn begin_message(meta: Meta) -> MessageResponse {
+        MessageResponse::CompileBegin { meta }
+    }
+
+    fn stdout_message(payload: String, meta: Meta) -> MessageResponse {
+        MessageResponse::CompileStdout { payload, meta }
+    }
+
+    fn stderr_message(payload: String, meta: Meta) -> MessageResponse {
+        MessageResponse::CompileStderr { payload, meta }
+    }
+
+    fn progress_message(progress: Infallible, _meta: Meta) -> MessageResponse {
+        match progress {}
+    }
+
+    fn end_message(response: Self::Response, meta: Meta) -> MessageResponse {
+        let coordinator::CompileResponse {
+            success,
+            exit_detail,
+            code,
+            diagnostics,
+            artifact: _,
+            artifact_error: _,
+        } = response;
+
+        MessageResponse::CompileEnd {
+            payload: CompileResponse {
+                success,
+                exit_detail,
+                code,
+                diagnostics: diagnostics.into_iter().map(Into::into).collect(),
+            },
+            meta,
+        }
+    }
+}
+
+pub(crate) type FormatError = JobError<FormatRequestParseError, coordinator::FormatError>;
+
+struct FormatJob;
+
+impl StreamingJob for FormatJob {
+    type ClientRequest = FormatRequest;
+    type ParseError = FormatRequestParseError;
+    type Request = coordinator::FormatRequest;
+    type Response = coordinator::FormatResponse;
+    type Error = coordinator::FormatError;
+    type Progress = Infallible;
+
+    const ENDPOINT: Endpoint = Endpoint::Format;
+
+    fn begin(
+        coordinator: SharedCoordinator,
+        token: CancellationToken,
+        req: Self::Request,
+    ) -> BoxFuture<'static, Result<ActiveJob<Self>, Self::Error>> {
+        async move {
+            let coordinator::ActiveFormatting {
+                permit,
+                task,
+                stdout_rx,
+                stderr_rx,
+            } = coordinator.begin_format(token, req).await?;
+
+            Ok(ActiveJob {
+                permit,
+                task,
+                stdout_rx,
+                stderr_rx,
+                progress: stream::empty().boxed(),
+            })
+        }
+        .boxed()
+    }
+
+    fn begin_message(meta: Meta) -> MessageResponse {
+        MessageResponse::FormatBegin { meta }
+    }
+
+    fn stdout_message(payload: String, meta: Meta) -> MessageResponse {
+        MessageResponse::FormatStdout { payload, meta }
+    }
+
+    fn stderr_message(payload: String, meta: Meta) -> MessageResponse {
+        MessageResponse::FormatStderr { payload, meta }
+    }
+
+    fn progress_message(progress: Infallible, _meta: Meta) -> MessageResponse {
+        match progress {}
+    }
+
+    fn end_message(response: Self::Response, meta: Meta) -> MessageResponse {
+        let coordinator::FormatResponse {
+            success,
+            exit_detail,
+            code,
+        } = response;
+
+        MessageResponse::FormatEnd {
+            payload: FormatResponse {
+                success,
+                exit_detail,
+                code: code.into(),
+            },
+            meta,
+        }
+    }
+}
+
+pub(crate) type ClippyError = JobError<ClippyRequestParseError, coordinator::ClippyError>;
+
+struct ClippyJob;
+
+impl StreamingJob for ClippyJob {
+    type ClientRequest = ClippyRequest;
+    type ParseError = ClippyRequestParseError;
+    type Request = coordinator::ClippyRequest;
+    type Response = coordinator::ClippyResponse;
+    type Error = coordinator::ClippyError;
+    type Progress = Infallible;
+
+    const ENDPOINT: Endpoint = Endpoint::Clippy;
+
+    fn begin(
+        coordinator: SharedCoordinator,
+        token: CancellationToken,
+        req: Self::Request,
+    ) -> BoxFuture<'static, Result<ActiveJob<Self>, Self::Error>> {
+        async move {
+            let coordinator::ActiveClippy {
+                permit,
+                task,
+                stdout_rx,
+                stderr_rx,
+            } = coordinator.begin_clippy(token, req).await?;
+
+            Ok(ActiveJob {
+                permit,
+                task,
+                stdout_rx,
+                stderr_rx,
+                progress: stream::empty().boxed(),
+            })
+        }
+        .boxed()
+    }
+
+    fn begin_message(meta: Meta) -> MessageResponse {
+        MessageResponse::ClippyBegin { meta }
+    }
+
+    fn stdout_message(payload: String, meta: Meta) -> MessageResponse {
+        MessageResponse::ClippyStdout { payload, meta }
+    }
+
+    fn stderr_message(payload: String, meta: Meta) -> MessageResponse {
+        MessageResponse::ClippyStderr { payload, meta }
+    }
+
+    fn progress_message(progress: Infallible, _meta: Meta) -> MessageResponse {
+        match progress {}
+    }
+
+    fn end_message(response: Self::Response, meta: Meta) -> MessageResponse {
+        let coordinator::ClippyResponse {
+            success,
+            exit_detail,
+            diagnostics,
+        } = response;
+
+        MessageResponse::ClippyEnd {
+            payload: ClippyResponse {
+                success,
+                exit_detail,
+                diagnostics: diagnostics.into_iter().map(Into::into).collect(),
+            },
+            meta,
+        }
+    }
+}
+
+pub(crate) type MiriError = JobError<MiriRequestParseError, coordinator::MiriError>;
+
+struct MiriJob;
+
+impl StreamingJob for MiriJob {
+    type ClientRequest = MiriRequest;
+    type ParseError = MiriRequestParseError;
+    type Request = coordinator::MiriRequest;
+    type Response = coordinator::MiriResponse;
+    type Error = coordinator::MiriError;
+    type Progress = Infallible;
+
+    const ENDPOINT: Endpoint = Endpoint::Miri;
+
+    fn begin(
+        coordinator: SharedCoordinator,
+        token: CancellationToken,
+        req: Self::Request,
+    ) -> BoxFuture<'static, Result<ActiveJob<Self>, Self::Error>> {
+        async move {
+            let coordinator::ActiveMiri {
+                permit,
+                task,
+                stdout_rx,
+                stderr_rx,
+            } = coordinator.begin_miri(token, req).await?;
+
+            Ok(ActiveJob {
+                permit,
+                task,
+                stdout_rx,
+                stderr_rx,
+                progress: stream::empty().boxed(),
+            })
+        }
+        .boxed()
+    }
+
+    fn begin_message(meta: Meta) -> MessageResponse {
+        MessageResponse::MiriBegin { meta }
+    }
+
+    fn stdout_message(payload: String, meta: Meta) -> MessageResponse {
+        MessageResponse::MiriStdout { payload, meta }
+    }
+
+    fn stderr_message(payload: String, meta: Meta) -> MessageResponse {
+        MessageResponse::MiriStderr { payload, meta }
+    }
+
+    fn progress_message(progress: Infallible, _meta: Meta) -> MessageResponse {
+        match progress {}
+    }
+
+    fn end_message(response: Self::Response, meta: Meta) -> MessageResponse {
+        let coordinator::MiriResponse {
+            success,
+            exit_detail,
+        } = response;
+
+        MessageResponse::MiriEnd {
+            payload: MiriResponse {
+                success,
+                exit_detail,
+            },
+            meta,
+        }
+    }
+}
+
+pub(crate) type BenchError = JobError<BenchRequestParseError, coordinator::BenchError>;
+
+struct BenchJob;
+
+impl StreamingJob for BenchJob {
+    type ClientRequest = BenchRequest;
+    type ParseError = BenchRequestParseError;
+    type Request = coordinator::BenchRequest;
+    type Response = coordinator::BenchResponse;
+    type Error = coordinator::BenchError;
+    type Progress = coordinator::BenchResult;
+
+    const ENDPOINT: Endpoint = Endpoint::Bench;
+
+    fn begin(
+        coordinator: SharedCoordinator,
+        token: CancellationToken,
+        req: Self::Request,
+    ) -> BoxFuture<'static, Result<ActiveJob<Self>, Self::Error>> {
+        async move {
+            let coordinator::ActiveBench {
+                permit,
+                task,
+                stdout_rx,
+                stderr_rx,
+                bench_rx,
+            } = coordinator.begin_bench(token, req).await?;
+
+            Ok(ActiveJob {
+                permit,
+                task,
+                stdout_rx,
+                stderr_rx,
+                progress: bench_rx,
+            })
+        }
+        .boxed()
+    }
+
+    fn begin_message(meta: Meta) -> MessageResponse {
+        MessageResponse::BenchBegin { meta }
+    }
+
+    fn stdout_message(payload: String, meta: Meta) -> MessageResponse {
+        MessageResponse::BenchStdout { payload, meta }
+    }
+
+    fn stderr_message(payload: String, meta: Meta) -> MessageResponse {
+        MessageResponse::BenchStderr { payload, meta }
+    }
+
+    fn progress_message(progress: coordinator::BenchResult, meta: Meta) -> MessageResponse {
+        let payload = progress.into();
+        MessageResponse::BenchResult { payload, meta }
+    }
+
+    fn end_message(response: Self::Response, meta: Meta) -> MessageResponse {
+        let coordinator::BenchResponse {
+            success,
+            exit_detail,
+            benchmarks,
+        } = response;
+
+        MessageResponse::BenchEnd {
+            payload: BenchResponse {
+                success,
+                exit_detail,
+                benchmarks: benchmarks.into_iter().map(Into::into).collect(),
+            },
+            meta,
+        }
+    }
+}
+
+pub(crate) type MacroExpansionError =
+    JobError<MacroExpansionRequestParseError, coordinator::MacroExpansionError>;
+
+struct MacroExpansionJob;
+
+impl StreamingJob for MacroExpansionJob {
+    type ClientRequest = MacroExpansionRequest;
+    type ParseError = MacroExpansionRequestParseError;
+    type Request = coordinator::MacroExpansionRequest;
+    type Response = coordinator::MacroExpansionResponse;
+    type Error = coordinator::MacroExpansionError;
+    type Progress = Infallible;
+
+    const ENDPOINT: Endpoint = Endpoint::MacroExpansion;
+
+    fn begin(
+        coordinator: SharedCoordinator,
+        token: CancellationToken,
+        req: Self::Request,
+    ) -> BoxFuture<'static, Result<ActiveJob<Self>, Self::Error>> {
+        async move {
+            let coordinator::ActiveMacroExpansion {
+                permit,
+                task,
+                stdout_rx,
+                stderr_rx,
+            } = coordinator.begin_macro_expansion(token, req).await?;
+
+            Ok(ActiveJob {
+                permit,
+                task,
+                stdout_rx,
+                stderr_rx,
+                progress: stream::empty().boxed(),
+            })
+        }
+        .boxed()
+    }
+
+    fn begin_message(meta: Meta) -> MessageResponse {
+        MessageResponse::MacroExpansionBegin { meta }
+    }
+
+    fn stdout_message(payload: String, meta: Meta) -> MessageResponse {
+        MessageResponse::MacroExpansionStdout { payload, meta }
+    }
+
+    fn stderr_message(payload: String, meta: Meta) -> MessageResponse {
+        MessageResponse::MacroExpansionStderr { payload, meta }
+    }
+
+    fn progress_message(progress: Infallible, _meta: Meta) -> MessageResponse {
+        match progress {}
+    }
+
+    fn end_message(response: Self::Response, meta: Meta) -> MessageResponse {
+        let coordinator::MacroExpansionResponse {
+            success,
+            exit_detail,
+        } = response;
+
+        MessageResponse::MacroExpansionEnd {
+            payload: MacroExpansionResponse {
+                success,
+                exit_detail,
+            },
+            meta,
+        }
+    }
+}
+
 #[derive(Debug, Snafu)]
 enum Error {
     #[snafu(display("Unable to deserialize request"))]
@@ -933,6 +2428,24 @@ enum Error {
     #[snafu(display("Unable to perform a streaming execute"))]
     StreamingExecute { source: ExecuteError },
 
+    #[snafu(display("Unable to perform a streaming compile"))]
+    StreamingCompile { source: CompileError },
+
+    #[snafu(display("Unable to perform a streaming format"))]
+    StreamingFormat { source: FormatError },
+
+    #[snafu(display("Unable to perform a streaming Clippy"))]
+    StreamingClippy { source: ClippyError },
+
+    #[snafu(display("Unable to perform a streaming Miri"))]
+    StreamingMiri { source: MiriError },
+
+    #[snafu(display("Unable to perform a streaming benchmark"))]
+    StreamingBench { source: BenchError },
+
+    #[snafu(display("Unable to perform a streaming macro expansion"))]
+    StreamingMacroExpansion { source: MacroExpansionError },
+
     #[snafu(display("Unable to pass stdin to the active execution"))]
     StreamingCoordinatorExecuteStdin {
         source: tokio::sync::mpsc::error::SendError<()>,